@@ -0,0 +1,549 @@
+use crate::{
+    logging,
+    settings::load_settings,
+    ssh::{display_name, AuthQuestions, RecursiveProgress, SSHConnection, SshError, SyncMode},
+    ui::parse_connection_target,
+};
+use log::LevelFilter;
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    sync::atomic::AtomicBool,
+    time::Duration,
+};
+
+const USAGE: &str = "\
+Usage:
+  ssh-browser                                         start the graphical interface
+  ssh-browser list     [options] [user@]host[:port] [REMOTE_DIR]
+  ssh-browser download [options] [user@]host[:port] REMOTE LOCAL
+  ssh-browser upload   [options] [user@]host[:port] LOCAL REMOTE
+
+Options:
+  -u, --user USER      user name, if not given with the host (default: $USER)
+  -p, --port PORT      SSH port (default: 22)
+  -i, --identity FILE  private key to log in with, offered before any other
+  -r, --recursive      transfer a directory and its contents
+  --parents            create missing remote directories above the upload target
+  --skip-unchanged     skip files whose destination has the same size and is as new
+  --skip-existing      skip files that exist at the destination
+  --limit KIB          transfer at most KIB KiB per second, 0 for no limit
+                       (default: the limit set in the window)
+  -v, --verbose        log connection details; twice for more
+  -h, --help           show this help
+
+Keys from the SSH agent and ~/.ssh are tried first. The password is read
+from the SSH_BROWSER_PASSWORD environment variable, or asked for on the
+terminal if no key is accepted.";
+
+/// Environment variable holding the password, so that scripts need not type it
+const PASSWORD_VARIABLE: &str = "SSH_BROWSER_PASSWORD";
+
+/// What to do once connected
+#[derive(Debug, PartialEq)]
+enum Command {
+    List(String),
+    Download { remote: String, local: String },
+    Upload { local: String, remote: String },
+}
+
+/// A parsed command line
+#[derive(Debug)]
+struct Invocation {
+    hostname: String,
+    username: String,
+    port: u16,
+    /// A private key file to offer first
+    identity: Option<PathBuf>,
+    command: Command,
+    recursive: bool,
+    /// Create missing directories above the upload target
+    create_parents: bool,
+    sync: SyncMode,
+    /// KiB per second, overriding the saved setting
+    speed_limit_kib: Option<u32>,
+    log_level: LevelFilter,
+}
+
+/// The words that start the command-line mode
+const COMMANDS: &[&str] = &[
+    "list", "ls", "download", "get", "upload", "put", "help", "-h", "--help",
+];
+
+/// Whether the arguments ask for the command-line mode instead of the window.
+/// Only a command as the first argument does: launchers may pass arguments of
+/// their own, such as a file from a desktop entry or macOS's `-psn_…`.
+pub fn is_cli(args: &[String]) -> bool {
+    args.first()
+        .is_some_and(|first| COMMANDS.contains(&first.as_str()))
+}
+
+/// Run a command-line invocation and return the process exit status:
+/// 0 on success, 1 if the operation failed and 2 for a bad command line.
+pub fn run(args: &[String]) -> i32 {
+    if wants_help(args) {
+        println!("{}", USAGE);
+        return 0;
+    }
+    let invocation = match parse_args(args) {
+        Ok(invocation) => invocation,
+        Err(message) => {
+            eprintln!("ssh-browser: {}\n\n{}", message, USAGE);
+            return 2;
+        }
+    };
+    // Stderr only: the output of `list` is meant for other programs
+    let _ = logging::init(invocation.log_level, "");
+
+    let password = std::env::var(PASSWORD_VARIABLE).ok();
+    match execute(&invocation, password) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("ssh-browser: {}", e);
+            1
+        }
+    }
+}
+
+/// Whether the usage should be printed instead: `help` as the command, or
+/// `-h`/`--help` as an option. A path that happens to be called `help`, or
+/// comes after `--`, is not asking for it.
+fn wants_help(args: &[String]) -> bool {
+    args.first().is_some_and(|first| first == "help")
+        || args
+            .iter()
+            .take_while(|arg| *arg != "--")
+            .any(|arg| arg == "-h" || arg == "--help")
+}
+
+fn parse_args(args: &[String]) -> Result<Invocation, String> {
+    let mut username = None;
+    let mut port = None;
+    let mut identity = None;
+    let mut recursive = false;
+    let mut create_parents = false;
+    let mut sync = SyncMode::Always;
+    let mut speed_limit_kib = None;
+    let mut verbosity = 0;
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-u" | "--user" => {
+                username = Some(args.next().ok_or("--user needs a value")?.clone());
+            }
+            "-p" | "--port" => {
+                let value = args.next().ok_or("--port needs a value")?;
+                port = Some(
+                    value
+                        .parse::<u16>()
+                        .map_err(|_| format!("invalid port: {}", value))?,
+                );
+            }
+            "-i" | "--identity" => {
+                identity = Some(PathBuf::from(
+                    args.next().ok_or("--identity needs a value")?,
+                ));
+            }
+            "-r" | "--recursive" => recursive = true,
+            "--parents" => create_parents = true,
+            "--skip-unchanged" => sync = SyncMode::SkipUnchanged,
+            "--skip-existing" => sync = SyncMode::SkipExisting,
+            "--limit" => {
+                let value = args.next().ok_or("--limit needs a value")?;
+                speed_limit_kib = Some(
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid speed limit: {}", value))?,
+                );
+            }
+            "-v" | "--verbose" => verbosity += 1,
+            "-vv" => verbosity += 2,
+            "--" => positional.extend(args.by_ref().cloned()),
+            option if option.starts_with('-') && option.len() > 1 => {
+                return Err(format!("unknown option: {}", option));
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let name = positional.next().ok_or("missing command")?;
+    let host = positional.next().ok_or("missing host")?;
+    let operands: Vec<String> = positional.collect();
+
+    // `user@host:port` and `sftp://` URLs are read like in the connect form;
+    // anything else is a plain host name
+    let target = parse_connection_target(&host);
+    let (hostname, target_user, target_port, target_path) = match target {
+        Some(target) => (target.hostname, target.username, target.port, target.path),
+        None => (host, None, None, None),
+    };
+    let command = match (name.as_str(), operands.as_slice()) {
+        ("list" | "ls", []) => Command::List(target_path.unwrap_or_else(|| ".".to_string())),
+        ("list" | "ls", [dir]) => Command::List(dir.clone()),
+        ("download" | "get", [remote, local]) => Command::Download {
+            remote: remote.clone(),
+            local: local.clone(),
+        },
+        ("upload" | "put", [local, remote]) => Command::Upload {
+            local: local.clone(),
+            remote: remote.clone(),
+        },
+        ("list" | "ls" | "download" | "get" | "upload" | "put", _) => {
+            return Err(format!("wrong number of paths for {}", name));
+        }
+        _ => return Err(format!("unknown command: {}", name)),
+    };
+
+    let username = username
+        .or(target_user)
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .ok_or("missing user name")?;
+
+    Ok(Invocation {
+        hostname,
+        username,
+        port: port.or(target_port).unwrap_or(22),
+        identity,
+        command,
+        recursive,
+        create_parents,
+        sync,
+        speed_limit_kib,
+        log_level: match verbosity {
+            0 => LevelFilter::Warn,
+            1 => LevelFilter::Info,
+            _ => LevelFilter::Debug,
+        },
+    })
+}
+
+/// Connect and run the command. Without a `password`, keys are tried
+/// first and the password is only asked for if none is accepted.
+fn execute(invocation: &Invocation, password: Option<String>) -> Result<(), SshError> {
+    let settings = load_settings();
+    let connect = |password: &str| -> Result<SSHConnection, SshError> {
+        let mut connection = SSHConnection::new(
+            &invocation.hostname,
+            &invocation.username,
+            password,
+            invocation.port,
+        );
+        connection.set_identity(invocation.identity.clone());
+        connection.set_timeout(
+            Some(settings.operation_timeout_secs)
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+        );
+        connection.set_transfer_retries(settings.transfer_retries);
+        let speed_limit_kib = invocation
+            .speed_limit_kib
+            .unwrap_or(settings.speed_limit_kib);
+        connection.set_speed_limit(u64::from(speed_limit_kib) * 1024);
+        connection.connect_cancellable(
+            &AtomicBool::new(false),
+            &mut |_| {},
+            &mut answer_questions,
+        )?;
+        Ok(connection)
+    };
+    let mut connection = match password {
+        Some(password) => connect(&password)?,
+        None => match connect("") {
+            Ok(connection) => connection,
+            Err(SshError::Auth(..)) => {
+                let password = read_password(&format!(
+                    "Password for {}@{}: ",
+                    invocation.username, invocation.hostname
+                ))
+                .map_err(|e| SshError::Io(format!("Failed to read the password: {}", e), None))?;
+                connect(&password)?
+            }
+            Err(e) => return Err(e),
+        },
+    };
+
+    let result = run_command(&connection, invocation);
+    connection.disconnect();
+    result
+}
+
+fn run_command(connection: &SSHConnection, invocation: &Invocation) -> Result<(), SshError> {
+    // Nothing can stop a transfer from the command line but ending the process
+    let cancel = AtomicBool::new(false);
+    if let Command::Upload { remote, .. } = &invocation.command {
+        if invocation.create_parents {
+            connection.create_parent_directories(remote)?;
+        }
+    }
+    match &invocation.command {
+        Command::List(dir) => list(connection, dir),
+        Command::Download { remote, local } if invocation.recursive => connection
+            .download_directory(
+                remote,
+                local,
+                invocation.sync,
+                &cancel,
+                &mut report_progress,
+            ),
+        Command::Download { remote, local } => connection.download_file(remote, local, &cancel),
+        Command::Upload { local, remote } if invocation.recursive => connection.upload_directory(
+            local,
+            remote,
+            invocation.sync,
+            &cancel,
+            &mut report_progress,
+        ),
+        Command::Upload { local, remote } => connection.upload_file(local, remote, &cancel),
+    }
+}
+
+/// Print a directory listing, one entry per line: type, size and name
+fn list(connection: &SSHConnection, dir: &str) -> Result<(), SshError> {
+    let listing = connection.list_directory(dir)?;
+    let mut stdout = io::stdout().lock();
+    for entry in &listing.entries {
+        let kind = if entry.is_dir { 'd' } else { '-' };
+        let _ = writeln!(
+            stdout,
+            "{} {:>12} {}",
+            kind,
+            entry.size,
+            display_name(&entry.name)
+        );
+    }
+    if listing.truncated {
+        eprintln!(
+            "ssh-browser: only the first {} entries are listed",
+            listing.entries.len()
+        );
+    }
+    Ok(())
+}
+
+/// Show the file being transferred on stderr
+fn report_progress(progress: RecursiveProgress) {
+    if let RecursiveProgress::Item {
+        done,
+        total,
+        current,
+    } = progress
+    {
+        eprintln!("[{}/{}] {}", done, total, display_name(&current));
+    }
+}
+
+/// Ask the server's login questions, such as for a one-time code, on the terminal
+fn answer_questions(questions: &AuthQuestions) -> Option<Vec<String>> {
+    if !questions.instructions.is_empty() {
+        eprintln!("{}", questions.instructions);
+    }
+    questions
+        .prompts
+        .iter()
+        .map(|(prompt, echo)| {
+            let prompt = format!("{} ", prompt);
+            if *echo {
+                eprint!("{}", prompt);
+                let mut line = String::new();
+                io::stdin().lock().read_line(&mut line)?;
+                Ok(line.trim_end_matches(['\r', '\n']).to_string())
+            } else {
+                read_password(&prompt)
+            }
+        })
+        .collect::<io::Result<_>>()
+        .ok()
+}
+
+/// Ask for the password on stderr and read it from stdin, without echo on a terminal
+fn read_password(prompt: &str) -> io::Result<String> {
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+    let echo = EchoGuard::disable();
+    let mut line = String::new();
+    let read = io::stdin().lock().read_line(&mut line);
+    drop(echo);
+    eprintln!();
+    read?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Turns terminal echo off while it lives
+struct EchoGuard {
+    #[cfg(unix)]
+    saved: Option<libc::termios>,
+}
+
+impl EchoGuard {
+    #[cfg(unix)]
+    fn disable() -> Self {
+        // SAFETY: termios is plain data filled in by tcgetattr before it is used
+        let saved = unsafe {
+            let mut termios: libc::termios = std::mem::zeroed();
+            (libc::tcgetattr(libc::STDIN_FILENO, &mut termios) == 0).then_some(termios)
+        };
+        if let Some(mut termios) = saved {
+            termios.c_lflag &= !libc::ECHO;
+            // SAFETY: a valid termios read above for the same descriptor
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) };
+        }
+        Self { saved }
+    }
+
+    #[cfg(not(unix))]
+    fn disable() -> Self {
+        Self {}
+    }
+}
+
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(termios) = &self.saved {
+            // SAFETY: restores the settings read in `disable`
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, termios) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn only_commands_start_the_cli() {
+        assert!(is_cli(&args(&["list", "host"])));
+        assert!(is_cli(&args(&["get", "host", "a", "b"])));
+        assert!(is_cli(&args(&["--help"])));
+        assert!(!is_cli(&args(&[])));
+        assert!(!is_cli(&args(&["-psn_0_12345"])));
+        assert!(!is_cli(&args(&["/home/user/notes.txt"])));
+        assert!(!is_cli(&args(&["sftp://host/srv"])));
+    }
+
+    #[test]
+    fn help_is_only_a_command_or_an_option() {
+        assert!(wants_help(&args(&["help"])));
+        assert!(wants_help(&args(&["--help"])));
+        assert!(wants_help(&args(&["list", "host", "-h"])));
+        assert!(!wants_help(&args(&["download", "host", "help", "./help"])));
+        assert!(!wants_help(&args(&["list", "host", "--", "--help"])));
+    }
+
+    #[test]
+    fn the_target_gives_user_port_and_directory() {
+        let invocation =
+            parse_args(&args(&["list", "sftp://alice@example.com:2222/var/log"])).unwrap();
+        assert_eq!(invocation.hostname, "example.com");
+        assert_eq!(invocation.username, "alice");
+        assert_eq!(invocation.port, 2222);
+        assert_eq!(invocation.command, Command::List("/var/log".to_string()));
+        assert_eq!(invocation.identity, None);
+
+        // Like scp, `host:dir` names a directory, not a port
+        let invocation = parse_args(&args(&["list", "alice@example.com:logs"])).unwrap();
+        assert_eq!(invocation.port, 22);
+        assert_eq!(invocation.command, Command::List("logs".to_string()));
+
+        let invocation = parse_args(&args(&["ls", "-u", "bob", "example.com"])).unwrap();
+        assert_eq!(invocation.username, "bob");
+        assert_eq!(invocation.port, 22);
+        assert_eq!(invocation.command, Command::List(".".to_string()));
+    }
+
+    #[test]
+    fn options_override_the_target() {
+        let invocation = parse_args(&args(&[
+            "get",
+            "-p",
+            "2200",
+            "-u",
+            "carol",
+            "--identity",
+            "/keys/id_deploy",
+            "dave@host:2222",
+            "/etc/hosts",
+            "hosts",
+        ]))
+        .unwrap();
+        assert_eq!(invocation.username, "carol");
+        assert_eq!(invocation.port, 2200);
+        assert_eq!(invocation.identity, Some(PathBuf::from("/keys/id_deploy")));
+        assert_eq!(
+            invocation.command,
+            Command::Download {
+                remote: "/etc/hosts".to_string(),
+                local: "hosts".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn transfer_options_are_read() {
+        let invocation = parse_args(&args(&[
+            "put",
+            "-r",
+            "--parents",
+            "--skip-existing",
+            "--limit",
+            "512",
+            "-vv",
+            "u@host",
+            "./site",
+            "/srv/www",
+        ]))
+        .unwrap();
+        assert!(invocation.recursive);
+        assert!(invocation.create_parents);
+        assert_eq!(invocation.sync, SyncMode::SkipExisting);
+        assert_eq!(invocation.speed_limit_kib, Some(512));
+        assert_eq!(invocation.log_level, LevelFilter::Debug);
+        assert_eq!(
+            invocation.command,
+            Command::Upload {
+                local: "./site".to_string(),
+                remote: "/srv/www".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn paths_after_a_double_dash_may_look_like_options() {
+        let invocation = parse_args(&args(&["get", "u@host", "--", "-notes", "help"])).unwrap();
+        assert_eq!(
+            invocation.command,
+            Command::Download {
+                remote: "-notes".to_string(),
+                local: "help".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn bad_command_lines_are_refused() {
+        let error = |list: &[&str]| parse_args(&args(list)).unwrap_err();
+        assert_eq!(error(&["list"]), "missing host");
+        assert_eq!(
+            error(&["get", "u@host", "a"]),
+            "wrong number of paths for get"
+        );
+        assert_eq!(
+            error(&["list", "-p", "99999", "u@host"]),
+            "invalid port: 99999"
+        );
+        assert_eq!(
+            error(&["list", "--limit", "fast", "u@host"]),
+            "invalid speed limit: fast"
+        );
+        assert_eq!(error(&["list", "u@host", "-i"]), "--identity needs a value");
+        assert_eq!(error(&["list", "-x", "u@host"]), "unknown option: -x");
+    }
+}
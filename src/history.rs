@@ -0,0 +1,133 @@
+use crate::{logging, queue::Transfer, settings::config_file, ssh::SshError};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The file finished transfers are appended to, one JSON object per line
+const HISTORY_FILE: &str = "transfer_history.jsonl";
+
+/// Which way a transfer went
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Upload,
+    Download,
+}
+
+/// How a transfer ended
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Done,
+    Cancelled,
+    Failed(String),
+    /// The connection was lost; the transfer is retried after reconnecting
+    Interrupted(String),
+}
+
+/// A finished transfer, as recorded in the history file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    /// When the transfer ended, as `YYYY-MM-DD HH:MM:SS.mmm` in UTC
+    pub time: String,
+    /// The server, as `user@host:port`
+    pub server: String,
+    pub direction: Direction,
+    pub remote: String,
+    pub local: String,
+    /// Bytes of the local copy, for a successful transfer; the total of its
+    /// files for a directory
+    pub size: Option<u64>,
+    pub outcome: Outcome,
+}
+
+impl HistoryEntry {
+    /// Describe a transfer of the queue that just ended with `result`
+    pub fn new(server: String, transfer: &Transfer, result: &Result<(), SshError>) -> Self {
+        let (direction, remote, local) = match transfer {
+            Transfer::DownloadFile { remote, local }
+            | Transfer::DownloadDirectory { remote, local, .. } => {
+                (Direction::Download, remote, local)
+            }
+            Transfer::UploadFile { local, remote, .. }
+            | Transfer::UploadDirectory { local, remote, .. } => (Direction::Upload, remote, local),
+        };
+        let outcome = match result {
+            Ok(()) => Outcome::Done,
+            Err(SshError::Cancelled(..)) => Outcome::Cancelled,
+            Err(e @ (SshError::Network(..) | SshError::Timeout(..) | SshError::NotConnected)) => {
+                Outcome::Interrupted(e.to_string())
+            }
+            Err(e) => Outcome::Failed(e.to_string()),
+        };
+        Self {
+            time: logging::timestamp(),
+            server,
+            direction,
+            remote: remote.clone(),
+            local: local.clone(),
+            size: result.is_ok().then(|| local_size(Path::new(local))),
+            outcome,
+        }
+    }
+}
+
+/// Append a finished transfer to the history file. A failure is only logged:
+/// it must not fail the transfer.
+pub fn record(entry: &HistoryEntry) {
+    let result = serde_json::to_string(entry)
+        .map_err(|e| e.to_string())
+        .and_then(|line| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(config_file(HISTORY_FILE))
+                .and_then(|mut file| writeln!(file, "{}", line))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        log::warn!("Failed to record the transfer history: {}", e);
+    }
+}
+
+/// The recorded transfers, newest first. Lines that cannot be read, e.g. from
+/// a write cut short, are skipped.
+pub fn load() -> Vec<HistoryEntry> {
+    let content = std::fs::read_to_string(config_file(HISTORY_FILE)).unwrap_or_default();
+    let mut entries: Vec<HistoryEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// Forget every recorded transfer
+pub fn clear() -> Result<(), String> {
+    match std::fs::remove_file(config_file(HISTORY_FILE)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// The size of a local file, or the total size of the files below a directory.
+/// Unreadable entries count as empty.
+fn local_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![PathBuf::from(path)];
+    while let Some(path) = pending.pop() {
+        // Links are not followed, so that a loop cannot keep the walk going
+        let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if !metadata.is_dir() {
+            total += metadata.len();
+            continue;
+        }
+        if let Ok(entries) = std::fs::read_dir(&path) {
+            pending.extend(entries.flatten().map(|entry| entry.path()));
+        }
+    }
+    total
+}
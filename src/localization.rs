@@ -28,22 +28,32 @@ impl Localizer {
         );
 
         translations.insert(
-            "switch_light_mode",
+            "theme_dark",
             HashMap::from([
-                (Language::English, "Switch to Light Mode"),
-                (Language::Arabic, "التحويل إلى الوضع الفاتح"),
-                (Language::French, "Passer en mode clair"),
-                (Language::Chinese, "切换到浅色模式"),
+                (Language::English, "Dark"),
+                (Language::Arabic, "داكن"),
+                (Language::French, "Sombre"),
+                (Language::Chinese, "深色"),
             ]),
         );
 
         translations.insert(
-            "switch_dark_mode",
+            "theme_light",
             HashMap::from([
-                (Language::English, "Switch to Dark Mode"),
-                (Language::Arabic, "التحويل إلى الوضع الداكن"),
-                (Language::French, "Passer en mode sombre"),
-                (Language::Chinese, "切换到深色模式"),
+                (Language::English, "Light"),
+                (Language::Arabic, "فاتح"),
+                (Language::French, "Clair"),
+                (Language::Chinese, "浅色"),
+            ]),
+        );
+
+        translations.insert(
+            "theme_system",
+            HashMap::from([
+                (Language::English, "Follow system"),
+                (Language::Arabic, "اتباع النظام"),
+                (Language::French, "Suivre le système"),
+                (Language::Chinese, "跟随系统"),
             ]),
         );
 
@@ -353,13 +363,3114 @@ impl Localizer {
         translations.insert(
             "upload_file_button",
             HashMap::from([
-                (Language::English, "Upload File"),
-                (Language::Arabic, "رفع ملف"),
-                (Language::French, "Téléverser un fichier"),
+                (Language::English, "Upload Files"),
+                (Language::Arabic, "رفع ملفات"),
+                (Language::French, "Téléverser des fichiers"),
                 (Language::Chinese, "上传文件"),
             ]),
         );
 
+        translations.insert(
+            "append_label",
+            HashMap::from([
+                (Language::English, "Append text:"),
+                (Language::Arabic, "إلحاق نص:"),
+                (Language::French, "Ajouter du texte :"),
+                (Language::Chinese, "追加文本："),
+            ]),
+        );
+
+        translations.insert(
+            "append_button",
+            HashMap::from([
+                (Language::English, "Append"),
+                (Language::Arabic, "إلحاق"),
+                (Language::French, "Ajouter"),
+                (Language::Chinese, "追加"),
+            ]),
+        );
+
+        translations.insert(
+            "reconnect_button",
+            HashMap::from([
+                (Language::English, "Reconnect"),
+                (Language::Arabic, "إعادة الاتصال"),
+                (Language::French, "Se reconnecter"),
+                (Language::Chinese, "重新连接"),
+            ]),
+        );
+
+        translations.insert(
+            "wrap_lines",
+            HashMap::from([
+                (Language::English, "Wrap lines"),
+                (Language::Arabic, "التفاف الأسطر"),
+                (Language::French, "Retour à la ligne"),
+                (Language::Chinese, "自动换行"),
+            ]),
+        );
+
+        translations.insert(
+            "go_to_line_label",
+            HashMap::from([
+                (Language::English, "Go to line:"),
+                (Language::Arabic, "الانتقال إلى السطر:"),
+                (Language::French, "Aller à la ligne :"),
+                (Language::Chinese, "跳转到行："),
+            ]),
+        );
+
+        translations.insert(
+            "go_button",
+            HashMap::from([
+                (Language::English, "Go"),
+                (Language::Arabic, "انتقال"),
+                (Language::French, "Aller"),
+                (Language::Chinese, "跳转"),
+            ]),
+        );
+
+        translations.insert(
+            "listing_shell_fallback",
+            HashMap::from([
+                (Language::English, "(listed via ls fallback)"),
+                (Language::Arabic, "(تم العرض عبر ls البديل)"),
+                (Language::French, "(listé via ls en secours)"),
+                (Language::Chinese, "（通过 ls 备用方式列出）"),
+            ]),
+        );
+
+        translations.insert(
+            "listing_shell_fallback_hint",
+            HashMap::from([
+                (Language::English, "SFTP denied reading this directory, so its contents were listed by running `ls -la` on the server."),
+                (Language::Arabic, "رفض SFTP قراءة هذا المجلد، لذلك تم عرض محتوياته بتشغيل `ls -la` على الخادم."),
+                (Language::French, "SFTP a refusé la lecture de ce répertoire ; son contenu a été listé en exécutant `ls -la` sur le serveur."),
+                (Language::Chinese, "SFTP 拒绝读取此目录，因此通过在服务器上运行 `ls -la` 列出其内容。"),
+            ]),
+        );
+
+        translations.insert(
+            "max_edit_size_label",
+            HashMap::from([
+                (Language::English, "Max editor file size:"),
+                (Language::Arabic, "الحد الأقصى لحجم الملف في المحرر:"),
+                (Language::French, "Taille max. dans l'éditeur :"),
+                (Language::Chinese, "编辑器最大文件大小："),
+            ]),
+        );
+
+        translations.insert(
+            "file_too_large_window",
+            HashMap::from([
+                (Language::English, "File Too Large"),
+                (Language::Arabic, "الملف كبير جداً"),
+                (Language::French, "Fichier trop volumineux"),
+                (Language::Chinese, "文件过大"),
+            ]),
+        );
+
+        translations.insert(
+            "file_too_large_message",
+            HashMap::from([
+                (
+                    Language::English,
+                    "This file is larger than the editor limit.",
+                ),
+                (Language::Arabic, "هذا الملف أكبر من حد المحرر."),
+                (
+                    Language::French,
+                    "Ce fichier dépasse la limite de l'éditeur.",
+                ),
+                (Language::Chinese, "此文件超过编辑器的大小限制。"),
+            ]),
+        );
+
+        translations.insert(
+            "preview_head_button",
+            HashMap::from([
+                (Language::English, "Preview beginning (read-only)"),
+                (Language::Arabic, "معاينة البداية (للقراءة فقط)"),
+                (Language::French, "Aperçu du début (lecture seule)"),
+                (Language::Chinese, "预览开头（只读）"),
+            ]),
+        );
+
+        translations.insert(
+            "preview_notice",
+            HashMap::from([
+                (Language::English, "Preview: only the beginning of the file is shown. Saving is disabled because it would truncate the file."),
+                (Language::Arabic, "معاينة: يتم عرض بداية الملف فقط. الحفظ معطل لأنه سيؤدي إلى اقتطاع الملف."),
+                (Language::French, "Aperçu : seul le début du fichier est affiché. L'enregistrement est désactivé car il tronquerait le fichier."),
+                (Language::Chinese, "预览：仅显示文件开头。已禁用保存，因为保存会截断文件。"),
+            ]),
+        );
+
+        translations.insert(
+            "touch_button",
+            HashMap::from([
+                (Language::English, "Touch"),
+                (Language::Arabic, "تحديث الوقت"),
+                (Language::French, "Toucher"),
+                (Language::Chinese, "更新时间"),
+            ]),
+        );
+
+        translations.insert(
+            "touch_create_hint",
+            HashMap::from([
+                (Language::English, "Update the file's modification time, creating it if it does not exist."),
+                (Language::Arabic, "تحديث وقت تعديل الملف، وإنشاؤه إذا لم يكن موجوداً."),
+                (Language::French, "Met à jour la date de modification du fichier, en le créant s'il n'existe pas."),
+                (Language::Chinese, "更新文件的修改时间，如果文件不存在则创建它。"),
+            ]),
+        );
+
+        translations.insert(
+            "progress_item",
+            HashMap::from([
+                (Language::English, "Item"),
+                (Language::Arabic, "العنصر"),
+                (Language::French, "Élément"),
+                (Language::Chinese, "项目"),
+            ]),
+        );
+
+        translations.insert(
+            "upload_folder_button",
+            HashMap::from([
+                (Language::English, "Upload Folder"),
+                (Language::Arabic, "رفع مجلد"),
+                (Language::French, "Téléverser un dossier"),
+                (Language::Chinese, "上传文件夹"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_delete_window",
+            HashMap::from([
+                (Language::English, "Confirm Delete"),
+                (Language::Arabic, "تأكيد الحذف"),
+                (Language::French, "Confirmer la suppression"),
+                (Language::Chinese, "确认删除"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_delete_directory",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Delete this directory and everything inside it?",
+                ),
+                (Language::Arabic, "حذف هذا المجلد وكل ما بداخله؟"),
+                (
+                    Language::French,
+                    "Supprimer ce répertoire et tout son contenu ?",
+                ),
+                (Language::Chinese, "删除此目录及其中的所有内容？"),
+            ]),
+        );
+
+        translations.insert(
+            "cursor_line",
+            HashMap::from([
+                (Language::English, "Ln"),
+                (Language::Arabic, "سطر"),
+                (Language::French, "Ln"),
+                (Language::Chinese, "行"),
+            ]),
+        );
+
+        translations.insert(
+            "cursor_column",
+            HashMap::from([
+                (Language::English, "Col"),
+                (Language::Arabic, "عمود"),
+                (Language::French, "Col"),
+                (Language::Chinese, "列"),
+            ]),
+        );
+
+        translations.insert(
+            "total_lines",
+            HashMap::from([
+                (Language::English, "lines"),
+                (Language::Arabic, "سطر إجمالاً"),
+                (Language::French, "lignes"),
+                (Language::Chinese, "行（总计）"),
+            ]),
+        );
+
+        translations.insert(
+            "command_panel",
+            HashMap::from([
+                (Language::English, "Run Command"),
+                (Language::Arabic, "تشغيل أمر"),
+                (Language::French, "Exécuter une commande"),
+                (Language::Chinese, "运行命令"),
+            ]),
+        );
+
+        translations.insert(
+            "run_button",
+            HashMap::from([
+                (Language::English, "Run"),
+                (Language::Arabic, "تشغيل"),
+                (Language::French, "Exécuter"),
+                (Language::Chinese, "运行"),
+            ]),
+        );
+
+        translations.insert(
+            "exit_code_label",
+            HashMap::from([
+                (Language::English, "Exit code:"),
+                (Language::Arabic, "رمز الخروج:"),
+                (Language::French, "Code de sortie :"),
+                (Language::Chinese, "退出码："),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_exit_window",
+            HashMap::from([
+                (Language::English, "Transfers in Progress"),
+                (Language::Arabic, "عمليات نقل جارية"),
+                (Language::French, "Transferts en cours"),
+                (Language::Chinese, "正在传输"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_disconnect_message",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Transfers are running or queued — disconnect anyway?",
+                ),
+                (
+                    Language::Arabic,
+                    "عمليات نقل جارية أو في الانتظار — هل تريد قطع الاتصال على أي حال؟",
+                ),
+                (
+                    Language::French,
+                    "Transferts en cours ou en attente — se déconnecter quand même ?",
+                ),
+                (
+                    Language::Chinese,
+                    "有正在进行或等待中的传输 — 仍要断开连接吗？",
+                ),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_quit_message",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Transfers are running or queued — quit anyway?",
+                ),
+                (
+                    Language::Arabic,
+                    "عمليات نقل جارية أو في الانتظار — هل تريد الخروج على أي حال؟",
+                ),
+                (
+                    Language::French,
+                    "Transferts en cours ou en attente — quitter quand même ?",
+                ),
+                (Language::Chinese, "有正在进行或等待中的传输 — 仍要退出吗？"),
+            ]),
+        );
+
+        translations.insert(
+            "disconnect_anyway",
+            HashMap::from([
+                (Language::English, "Disconnect anyway"),
+                (Language::Arabic, "قطع الاتصال على أي حال"),
+                (Language::French, "Se déconnecter quand même"),
+                (Language::Chinese, "仍然断开"),
+            ]),
+        );
+
+        translations.insert(
+            "quit_anyway",
+            HashMap::from([
+                (Language::English, "Quit anyway"),
+                (Language::Arabic, "الخروج على أي حال"),
+                (Language::French, "Quitter quand même"),
+                (Language::Chinese, "仍然退出"),
+            ]),
+        );
+
+        translations.insert(
+            "mode_label",
+            HashMap::from([
+                (Language::English, "Mode:"),
+                (Language::Arabic, "الصلاحيات:"),
+                (Language::French, "Mode :"),
+                (Language::Chinese, "权限："),
+            ]),
+        );
+
+        translations.insert(
+            "invalid_mode_error",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Invalid permission mode. Use octal digits, e.g. 755.",
+                ),
+                (
+                    Language::Arabic,
+                    "صلاحيات غير صالحة. استخدم أرقاماً ثمانية، مثل 755.",
+                ),
+                (
+                    Language::French,
+                    "Mode de permission invalide. Utilisez des chiffres octaux, par ex. 755.",
+                ),
+                (
+                    Language::Chinese,
+                    "权限模式无效。请使用八进制数字，例如 755。",
+                ),
+            ]),
+        );
+
+        translations.insert(
+            "file_missing_window",
+            HashMap::from([
+                (Language::English, "File No Longer Exists"),
+                (Language::Arabic, "الملف لم يعد موجوداً"),
+                (Language::French, "Le fichier n'existe plus"),
+                (Language::Chinese, "文件已不存在"),
+            ]),
+        );
+
+        translations.insert(
+            "file_missing_message",
+            HashMap::from([
+                (Language::English, "This file was deleted or renamed on the server since you opened it. Recreate it with your edits, or discard them?"),
+                (Language::Arabic, "تم حذف هذا الملف أو إعادة تسميته على الخادم منذ فتحه. هل تريد إعادة إنشائه بتعديلاتك أم تجاهلها؟"),
+                (Language::French, "Ce fichier a été supprimé ou renommé sur le serveur depuis son ouverture. Le recréer avec vos modifications, ou les abandonner ?"),
+                (Language::Chinese, "自打开以来，此文件已在服务器上被删除或重命名。要用您的修改重新创建它，还是放弃修改？"),
+            ]),
+        );
+
+        translations.insert(
+            "recreate_button",
+            HashMap::from([
+                (Language::English, "Recreate"),
+                (Language::Arabic, "إعادة الإنشاء"),
+                (Language::French, "Recréer"),
+                (Language::Chinese, "重新创建"),
+            ]),
+        );
+
+        translations.insert(
+            "discard_edits_button",
+            HashMap::from([
+                (Language::English, "Discard edits"),
+                (Language::Arabic, "تجاهل التعديلات"),
+                (Language::French, "Abandonner les modifications"),
+                (Language::Chinese, "放弃修改"),
+            ]),
+        );
+
+        translations.insert(
+            "permission_denied_toast",
+            HashMap::from([
+                (Language::English, "Permission denied"),
+                (Language::Arabic, "تم رفض الإذن"),
+                (Language::French, "Permission refusée"),
+                (Language::Chinese, "权限被拒绝"),
+            ]),
+        );
+
+        translations.insert(
+            "not_found_toast",
+            HashMap::from([
+                (Language::English, "No such file or directory"),
+                (Language::Arabic, "لا يوجد ملف أو مجلد بهذا الاسم"),
+                (Language::French, "Fichier ou dossier introuvable"),
+                (Language::Chinese, "没有该文件或目录"),
+            ]),
+        );
+
+        translations.insert(
+            "status_directory_created",
+            HashMap::from([
+                (Language::English, "Directory created successfully."),
+                (Language::Arabic, "تم إنشاء المجلد بنجاح."),
+                (Language::French, "Dossier créé avec succès."),
+                (Language::Chinese, "目录创建成功。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_file_created",
+            HashMap::from([
+                (Language::English, "File created successfully."),
+                (Language::Arabic, "تم إنشاء الملف بنجاح."),
+                (Language::French, "Fichier créé avec succès."),
+                (Language::Chinese, "文件创建成功。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_download_successful",
+            HashMap::from([
+                (Language::English, "Download successful"),
+                (Language::Arabic, "تم التنزيل بنجاح"),
+                (Language::French, "Téléchargement réussi"),
+                (Language::Chinese, "下载成功"),
+            ]),
+        );
+
+        translations.insert(
+            "status_upload_successful",
+            HashMap::from([
+                (Language::English, "Upload successful"),
+                (Language::Arabic, "تم الرفع بنجاح"),
+                (Language::French, "Envoi réussi"),
+                (Language::Chinese, "上传成功"),
+            ]),
+        );
+
+        translations.insert(
+            "status_directory_deleted",
+            HashMap::from([
+                (Language::English, "Directory deleted successfully."),
+                (Language::Arabic, "تم حذف المجلد بنجاح."),
+                (Language::French, "Dossier supprimé avec succès."),
+                (Language::Chinese, "目录删除成功。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_file_deleted",
+            HashMap::from([
+                (Language::English, "File deleted successfully."),
+                (Language::Arabic, "تم حذف الملف بنجاح."),
+                (Language::French, "Fichier supprimé avec succès."),
+                (Language::Chinese, "文件删除成功。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_file_renamed",
+            HashMap::from([
+                (Language::English, "File renamed successfully."),
+                (Language::Arabic, "تمت إعادة تسمية الملف بنجاح."),
+                (Language::French, "Fichier renommé avec succès."),
+                (Language::Chinese, "文件重命名成功。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_file_loaded",
+            HashMap::from([
+                (Language::English, "File content loaded."),
+                (Language::Arabic, "تم تحميل محتوى الملف."),
+                (Language::French, "Contenu du fichier chargé."),
+                (Language::Chinese, "文件内容已加载。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_file_saved",
+            HashMap::from([
+                (Language::English, "File saved successfully."),
+                (Language::Arabic, "تم حفظ الملف بنجاح."),
+                (Language::French, "Fichier enregistré avec succès."),
+                (Language::Chinese, "文件保存成功。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_content_appended",
+            HashMap::from([
+                (Language::English, "Content appended successfully."),
+                (Language::Arabic, "تمت إضافة المحتوى بنجاح."),
+                (Language::French, "Contenu ajouté avec succès."),
+                (Language::Chinese, "内容追加成功。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_touched",
+            HashMap::from([
+                (Language::English, "Modification time updated."),
+                (Language::Arabic, "تم تحديث وقت التعديل."),
+                (Language::French, "Date de modification mise à jour."),
+                (Language::Chinese, "修改时间已更新。"),
+            ]),
+        );
+
+        translations.insert(
+            "status_disconnected",
+            HashMap::from([
+                (Language::English, "Disconnected"),
+                (Language::Arabic, "تم قطع الاتصال"),
+                (Language::French, "Déconnecté"),
+                (Language::Chinese, "已断开连接"),
+            ]),
+        );
+
+        translations.insert(
+            "not_connected",
+            HashMap::from([
+                (Language::English, "Not connected"),
+                (Language::Arabic, "غير متصل"),
+                (Language::French, "Non connecté"),
+                (Language::Chinese, "未连接"),
+            ]),
+        );
+
+        translations.insert(
+            "advanced_algorithms",
+            HashMap::from([
+                (Language::English, "Advanced: algorithms"),
+                (Language::Arabic, "متقدم: الخوارزميات"),
+                (Language::French, "Avancé : algorithmes"),
+                (Language::Chinese, "高级：算法"),
+            ]),
+        );
+
+        translations.insert(
+            "algorithms_hint",
+            HashMap::from([
+                (Language::English, "Comma-separated lists for legacy servers. Leave blank to use the defaults."),
+                (Language::Arabic, "قوائم مفصولة بفواصل للخوادم القديمة. اتركها فارغة لاستخدام القيم الافتراضية."),
+                (Language::French, "Listes séparées par des virgules pour les anciens serveurs. Laissez vide pour utiliser les valeurs par défaut."),
+                (Language::Chinese, "用于旧服务器的逗号分隔列表。留空则使用默认值。"),
+            ]),
+        );
+
+        translations.insert(
+            "kex_label",
+            HashMap::from([
+                (Language::English, "Key exchange:"),
+                (Language::Arabic, "تبادل المفاتيح:"),
+                (Language::French, "Échange de clés :"),
+                (Language::Chinese, "密钥交换："),
+            ]),
+        );
+
+        translations.insert(
+            "host_key_label",
+            HashMap::from([
+                (Language::English, "Host key:"),
+                (Language::Arabic, "مفتاح المضيف:"),
+                (Language::French, "Clé d'hôte :"),
+                (Language::Chinese, "主机密钥："),
+            ]),
+        );
+
+        translations.insert(
+            "ciphers_label",
+            HashMap::from([
+                (Language::English, "Ciphers:"),
+                (Language::Arabic, "خوارزميات التشفير:"),
+                (Language::French, "Chiffrements :"),
+                (Language::Chinese, "加密算法："),
+            ]),
+        );
+
+        translations.insert(
+            "macs_label",
+            HashMap::from([
+                (Language::English, "MACs:"),
+                (Language::Arabic, "خوارزميات MAC:"),
+                (Language::French, "MAC :"),
+                (Language::Chinese, "MAC 算法："),
+            ]),
+        );
+
+        translations.insert(
+            "show_password",
+            HashMap::from([
+                (Language::English, "Show password"),
+                (Language::Arabic, "إظهار كلمة المرور"),
+                (Language::French, "Afficher le mot de passe"),
+                (Language::Chinese, "显示密码"),
+            ]),
+        );
+
+        translations.insert(
+            "hide_password",
+            HashMap::from([
+                (Language::English, "Hide password"),
+                (Language::Arabic, "إخفاء كلمة المرور"),
+                (Language::French, "Masquer le mot de passe"),
+                (Language::Chinese, "隐藏密码"),
+            ]),
+        );
+
+        translations.insert(
+            "progress_enumerating",
+            HashMap::from([
+                (Language::English, "Enumerating... items found:"),
+                (
+                    Language::Arabic,
+                    "جارٍ الحصر... العناصر التي تم العثور عليها:",
+                ),
+                (Language::French, "Énumération... éléments trouvés :"),
+                (Language::Chinese, "正在枚举... 已找到项目："),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_queue",
+            HashMap::from([
+                (Language::English, "Transfer queue"),
+                (Language::Arabic, "قائمة انتظار النقل"),
+                (Language::French, "File de transferts"),
+                (Language::Chinese, "传输队列"),
+            ]),
+        );
+
+        translations.insert(
+            "queue_paused",
+            HashMap::from([
+                (Language::English, "Paused"),
+                (Language::Arabic, "متوقف مؤقتًا"),
+                (Language::French, "En pause"),
+                (Language::Chinese, "已暂停"),
+            ]),
+        );
+
+        translations.insert(
+            "pause_queue",
+            HashMap::from([
+                (Language::English, "Pause"),
+                (Language::Arabic, "إيقاف مؤقت"),
+                (Language::French, "Pause"),
+                (Language::Chinese, "暂停"),
+            ]),
+        );
+
+        translations.insert(
+            "resume_queue",
+            HashMap::from([
+                (Language::English, "Resume"),
+                (Language::Arabic, "استئناف"),
+                (Language::French, "Reprendre"),
+                (Language::Chinese, "继续"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_pending",
+            HashMap::from([
+                (Language::English, "Pending"),
+                (Language::Arabic, "قيد الانتظار"),
+                (Language::French, "En attente"),
+                (Language::Chinese, "等待中"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_active",
+            HashMap::from([
+                (Language::English, "In progress"),
+                (Language::Arabic, "قيد التنفيذ"),
+                (Language::French, "En cours"),
+                (Language::Chinese, "进行中"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_done",
+            HashMap::from([
+                (Language::English, "Done"),
+                (Language::Arabic, "تم"),
+                (Language::French, "Terminé"),
+                (Language::Chinese, "完成"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_cancelled",
+            HashMap::from([
+                (Language::English, "Cancelled"),
+                (Language::Arabic, "أُلغي"),
+                (Language::French, "Annulé"),
+                (Language::Chinese, "已取消"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_failed",
+            HashMap::from([
+                (Language::English, "Failed"),
+                (Language::Arabic, "فشل"),
+                (Language::French, "Échoué"),
+                (Language::Chinese, "失败"),
+            ]),
+        );
+
+        translations.insert(
+            "rename_same_path",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Source and destination are the same, nothing to rename.",
+                ),
+                (
+                    Language::Arabic,
+                    "المصدر والوجهة متطابقان، لا شيء لإعادة تسميته.",
+                ),
+                (
+                    Language::French,
+                    "La source et la destination sont identiques, rien à renommer.",
+                ),
+                (Language::Chinese, "源和目标相同，无需重命名。"),
+            ]),
+        );
+
+        translations.insert(
+            "progress_transfers",
+            HashMap::from([
+                (Language::English, "Transfers"),
+                (Language::Arabic, "عمليات النقل"),
+                (Language::French, "Transferts"),
+                (Language::Chinese, "传输"),
+            ]),
+        );
+
+        translations.insert(
+            "clear_finished",
+            HashMap::from([
+                (Language::English, "Clear finished"),
+                (Language::Arabic, "مسح المكتملة"),
+                (Language::French, "Effacer les terminés"),
+                (Language::Chinese, "清除已完成"),
+            ]),
+        );
+
+        translations.insert(
+            "remove_button",
+            HashMap::from([
+                (Language::English, "Remove"),
+                (Language::Arabic, "إزالة"),
+                (Language::French, "Retirer"),
+                (Language::Chinese, "移除"),
+            ]),
+        );
+
+        translations.insert(
+            "drag_to_reorder",
+            HashMap::from([
+                (Language::English, "Drag to change the order"),
+                (Language::Arabic, "اسحب لتغيير الترتيب"),
+                (Language::French, "Faites glisser pour changer l'ordre"),
+                (Language::Chinese, "拖动以更改顺序"),
+            ]),
+        );
+
+        translations.insert(
+            "latency_label",
+            HashMap::from([
+                (Language::English, "Latency:"),
+                (Language::Arabic, "زمن الاستجابة:"),
+                (Language::French, "Latence :"),
+                (Language::Chinese, "延迟："),
+            ]),
+        );
+
+        translations.insert(
+            "error_details",
+            HashMap::from([
+                (Language::English, "Details"),
+                (Language::Arabic, "التفاصيل"),
+                (Language::French, "Détails"),
+                (Language::Chinese, "详细信息"),
+            ]),
+        );
+
+        translations.insert(
+            "copy_button",
+            HashMap::from([
+                (Language::English, "Copy"),
+                (Language::Arabic, "نسخ"),
+                (Language::French, "Copier"),
+                (Language::Chinese, "复制"),
+            ]),
+        );
+
+        translations.insert(
+            "hostname_empty",
+            HashMap::from([
+                (Language::English, "Enter a hostname or IP address."),
+                (Language::Arabic, "أدخل اسم المضيف أو عنوان IP."),
+                (
+                    Language::French,
+                    "Saisissez un nom d'hôte ou une adresse IP.",
+                ),
+                (Language::Chinese, "请输入主机名或 IP 地址。"),
+            ]),
+        );
+
+        translations.insert(
+            "hostname_invalid",
+            HashMap::from([
+                (
+                    Language::English,
+                    "This does not look like a hostname or IP address.",
+                ),
+                (Language::Arabic, "هذا لا يبدو اسم مضيف أو عنوان IP."),
+                (
+                    Language::French,
+                    "Ceci ne ressemble pas à un nom d'hôte ou à une adresse IP.",
+                ),
+                (Language::Chinese, "这看起来不像主机名或 IP 地址。"),
+            ]),
+        );
+
+        translations.insert(
+            "port_invalid",
+            HashMap::from([
+                (Language::English, "The port must be between 1 and 65535."),
+                (Language::Arabic, "يجب أن يكون المنفذ بين 1 و65535."),
+                (
+                    Language::French,
+                    "Le port doit être compris entre 1 et 65535.",
+                ),
+                (Language::Chinese, "端口必须在 1 到 65535 之间。"),
+            ]),
+        );
+
+        translations.insert(
+            "already_connected",
+            HashMap::from([
+                (Language::English, "Already connected. Disconnect first to connect to another server."),
+                (Language::Arabic, "متصل بالفعل. افصل الاتصال أولاً للاتصال بخادم آخر."),
+                (Language::French, "Déjà connecté. Déconnectez-vous d'abord pour vous connecter à un autre serveur."),
+                (Language::Chinese, "已连接。请先断开连接再连接到其他服务器。"),
+            ]),
+        );
+
+        translations.insert(
+            "skip_unchanged_label",
+            HashMap::from([
+                (Language::English, "Skip unchanged files"),
+                (Language::Arabic, "تخطي الملفات غير المعدلة"),
+                (Language::French, "Ignorer les fichiers inchangés"),
+                (Language::Chinese, "跳过未更改的文件"),
+            ]),
+        );
+
+        translations.insert(
+            "skip_unchanged_hint",
+            HashMap::from([
+                (Language::English, "Folder uploads and downloads skip files that already exist at the destination with the same size and a newer or equal modification time."),
+                (Language::Arabic, "تتخطى عمليات رفع وتنزيل المجلدات الملفات الموجودة بالفعل في الوجهة بنفس الحجم ووقت تعديل أحدث أو مساوٍ."),
+                (Language::French, "Les envois et téléchargements de dossiers ignorent les fichiers déjà présents à destination avec la même taille et une date de modification plus récente ou égale."),
+                (Language::Chinese, "文件夹上传和下载会跳过目标位置已存在、大小相同且修改时间更新或相同的文件。"),
+            ]),
+        );
+
+        translations.insert(
+            "back_button",
+            HashMap::from([
+                (Language::English, "Back"),
+                (Language::Arabic, "رجوع"),
+                (Language::French, "Précédent"),
+                (Language::Chinese, "后退"),
+            ]),
+        );
+
+        translations.insert(
+            "forward_button",
+            HashMap::from([
+                (Language::English, "Forward"),
+                (Language::Arabic, "تقدم"),
+                (Language::French, "Suivant"),
+                (Language::Chinese, "前进"),
+            ]),
+        );
+
+        translations.insert(
+            "encoding_label",
+            HashMap::from([
+                (Language::English, "Encoding:"),
+                (Language::Arabic, "الترميز:"),
+                (Language::French, "Encodage :"),
+                (Language::Chinese, "编码："),
+            ]),
+        );
+
+        translations.insert(
+            "encoding_hint",
+            HashMap::from([
+                (Language::English, "Detected when the file is opened. Choosing another encoding reloads the original content with it and saves in it."),
+                (Language::Arabic, "يُكتشف عند فتح الملف. اختيار ترميز آخر يعيد تحميل المحتوى الأصلي به ويحفظ به."),
+                (Language::French, "Détecté à l'ouverture du fichier. Choisir un autre encodage recharge le contenu d'origine avec celui-ci et enregistre dans cet encodage."),
+                (Language::Chinese, "打开文件时自动检测。选择其他编码会用该编码重新加载原始内容并以该编码保存。"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_label",
+            HashMap::from([
+                (Language::English, "Mirror (offer to delete extra files)"),
+                (Language::Arabic, "نسخ مطابق (اقتراح حذف الملفات الزائدة)"),
+                (
+                    Language::French,
+                    "Miroir (proposer de supprimer les fichiers en trop)",
+                ),
+                (Language::Chinese, "镜像（提示删除多余文件）"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_hint",
+            HashMap::from([
+                (Language::English, "After a folder upload or download, files at the destination that no longer exist in the source are listed for deletion. Nothing is deleted without confirmation."),
+                (Language::Arabic, "بعد رفع مجلد أو تنزيله، تُعرض الملفات الموجودة في الوجهة وغير الموجودة في المصدر للحذف. لا يُحذف شيء دون تأكيد."),
+                (Language::French, "Après l'envoi ou le téléchargement d'un dossier, les fichiers de la destination absents de la source sont listés pour suppression. Rien n'est supprimé sans confirmation."),
+                (Language::Chinese, "文件夹上传或下载后，会列出目标中源已不存在的文件以供删除。未经确认不会删除任何内容。"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_confirm_window",
+            HashMap::from([
+                (Language::English, "Confirm Mirror Deletion"),
+                (Language::Arabic, "تأكيد حذف النسخ المطابق"),
+                (Language::French, "Confirmer la suppression du miroir"),
+                (Language::Chinese, "确认镜像删除"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_confirm_message",
+            HashMap::from([
+                (
+                    Language::English,
+                    "entries exist only at the destination and will be deleted",
+                ),
+                (Language::Arabic, "عنصرًا موجودة في الوجهة فقط وسيتم حذفها"),
+                (
+                    Language::French,
+                    "éléments n'existent qu'à la destination et seront supprimés",
+                ),
+                (Language::Chinese, "个条目仅存在于目标中，将被删除"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_side_local",
+            HashMap::from([
+                (Language::English, "on this computer"),
+                (Language::Arabic, "على هذا الحاسوب"),
+                (Language::French, "sur cet ordinateur"),
+                (Language::Chinese, "在本机上"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_side_remote",
+            HashMap::from([
+                (Language::English, "on the server"),
+                (Language::Arabic, "على الخادم"),
+                (Language::French, "sur le serveur"),
+                (Language::Chinese, "在服务器上"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_large_warning",
+            HashMap::from([
+                (Language::English, "This deletes many files or most of the destination. Check the list carefully."),
+                (Language::Arabic, "سيؤدي هذا إلى حذف العديد من الملفات أو معظم الوجهة. تحقق من القائمة بعناية."),
+                (Language::French, "Ceci supprime de nombreux fichiers ou la majeure partie de la destination. Vérifiez soigneusement la liste."),
+                (Language::Chinese, "这将删除大量文件或目标的大部分内容。请仔细检查列表。"),
+            ]),
+        );
+
+        translations.insert(
+            "mirror_acknowledge",
+            HashMap::from([
+                (
+                    Language::English,
+                    "I checked the list and want to delete these entries",
+                ),
+                (Language::Arabic, "راجعت القائمة وأريد حذف هذه العناصر"),
+                (
+                    Language::French,
+                    "J'ai vérifié la liste et je veux supprimer ces éléments",
+                ),
+                (Language::Chinese, "我已检查列表并确认删除这些条目"),
+            ]),
+        );
+
+        translations.insert(
+            "status_mirror_done",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Extra files deleted, the destination now mirrors the source.",
+                ),
+                (
+                    Language::Arabic,
+                    "تم حذف الملفات الزائدة، وأصبحت الوجهة مطابقة للمصدر.",
+                ),
+                (
+                    Language::French,
+                    "Fichiers en trop supprimés, la destination reflète maintenant la source.",
+                ),
+                (Language::Chinese, "多余文件已删除，目标现已与源一致。"),
+            ]),
+        );
+
+        translations.insert(
+            "path_box_hint",
+            HashMap::from([
+                (Language::English, "Ctrl+L to jump here. Matching folders are suggested as you type; Enter opens the path, relative to the current folder unless it starts with /."),
+                (Language::Arabic, "Ctrl+L للانتقال إلى هنا. تُقترح المجلدات المطابقة أثناء الكتابة؛ Enter يفتح المسار، نسبةً إلى المجلد الحالي ما لم يبدأ بـ /."),
+                (Language::French, "Ctrl+L pour venir ici. Les dossiers correspondants sont suggérés pendant la saisie ; Entrée ouvre le chemin, relatif au dossier courant s'il ne commence pas par /."),
+                (Language::Chinese, "按 Ctrl+L 跳转到此处。输入时会提示匹配的文件夹；按回车打开路径，不以 / 开头的路径相对于当前文件夹。"),
+            ]),
+        );
+
+        translations.insert(
+            "find_panel",
+            HashMap::from([
+                (Language::English, "Find files"),
+                (Language::Arabic, "البحث عن الملفات"),
+                (Language::French, "Rechercher des fichiers"),
+                (Language::Chinese, "查找文件"),
+            ]),
+        );
+
+        translations.insert(
+            "find_depth_label",
+            HashMap::from([
+                (Language::English, "Depth:"),
+                (Language::Arabic, "العمق:"),
+                (Language::French, "Profondeur :"),
+                (Language::Chinese, "深度："),
+            ]),
+        );
+
+        translations.insert(
+            "find_time_label",
+            HashMap::from([
+                (Language::English, "Time limit:"),
+                (Language::Arabic, "الحد الزمني:"),
+                (Language::French, "Limite de temps :"),
+                (Language::Chinese, "时间限制："),
+            ]),
+        );
+
+        translations.insert(
+            "find_button",
+            HashMap::from([
+                (Language::English, "Find"),
+                (Language::Arabic, "بحث"),
+                (Language::French, "Rechercher"),
+                (Language::Chinese, "查找"),
+            ]),
+        );
+
+        translations.insert(
+            "find_results_count",
+            HashMap::from([
+                (Language::English, "matches"),
+                (Language::Arabic, "نتيجة"),
+                (Language::French, "résultats"),
+                (Language::Chinese, "个匹配项"),
+            ]),
+        );
+
+        translations.insert(
+            "find_results_truncated",
+            HashMap::from([
+                (Language::English, "limit reached, refine the search"),
+                (Language::Arabic, "تم بلوغ الحد، حسّن البحث"),
+                (Language::French, "limite atteinte, affinez la recherche"),
+                (Language::Chinese, "已达上限，请缩小搜索范围"),
+            ]),
+        );
+
+        translations.insert(
+            "find_result_hint",
+            HashMap::from([
+                (Language::English, "Open the folder containing this entry"),
+                (Language::Arabic, "فتح المجلد الذي يحتوي على هذا العنصر"),
+                (Language::French, "Ouvrir le dossier contenant cet élément"),
+                (Language::Chinese, "打开包含此项的文件夹"),
+            ]),
+        );
+
+        translations.insert(
+            "sudo_ran_elevated",
+            HashMap::from([
+                (Language::English, "Done with sudo:"),
+                (Language::Arabic, "تم باستخدام sudo:"),
+                (Language::French, "Effectué avec sudo :"),
+                (Language::Chinese, "已通过 sudo 完成："),
+            ]),
+        );
+
+        translations.insert(
+            "use_sudo_checkbox",
+            HashMap::from([
+                (Language::English, "Use sudo"),
+                (Language::Arabic, "استخدام sudo"),
+                (Language::French, "Utiliser sudo"),
+                (Language::Chinese, "使用 sudo"),
+            ]),
+        );
+
+        translations.insert(
+            "use_sudo_hint",
+            HashMap::from([
+                (Language::English, "Save, delete, rename and create through sudo on the server instead of SFTP, and read files and folders that SFTP may not"),
+                (Language::Arabic, "الحفظ والحذف وإعادة التسمية والإنشاء عبر sudo على الخادم بدلاً من SFTP، وقراءة الملفات والمجلدات التي لا يسمح بها SFTP"),
+                (Language::French, "Enregistrer, supprimer, renommer et créer via sudo sur le serveur au lieu de SFTP, et lire les fichiers et dossiers interdits à SFTP"),
+                (Language::Chinese, "在服务器上通过 sudo 而不是 SFTP 进行保存、删除、重命名和创建，并读取 SFTP 无权访问的文件和文件夹"),
+            ]),
+        );
+
+        translations.insert(
+            "sudo_password_hint",
+            HashMap::from([
+                (Language::English, "sudo password (blank: login password)"),
+                (Language::Arabic, "كلمة مرور sudo (فارغة: كلمة مرور الدخول)"),
+                (
+                    Language::French,
+                    "mot de passe sudo (vide : celui de connexion)",
+                ),
+                (Language::Chinese, "sudo 密码（留空：登录密码）"),
+            ]),
+        );
+
+        translations.insert(
+            "sudo_active",
+            HashMap::from([
+                (Language::English, "SUDO"),
+                (Language::Arabic, "SUDO"),
+                (Language::French, "SUDO"),
+                (Language::Chinese, "SUDO"),
+            ]),
+        );
+
+        translations.insert(
+            "hostname_paste_hint",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Paste sftp://user@host:port/path or user@host to fill in the form",
+                ),
+                (
+                    Language::Arabic,
+                    "الصق sftp://user@host:port/path أو user@host لملء النموذج",
+                ),
+                (
+                    Language::French,
+                    "Collez sftp://user@host:port/chemin ou user@host pour remplir le formulaire",
+                ),
+                (
+                    Language::Chinese,
+                    "粘贴 sftp://user@host:port/path 或 user@host 以填写表单",
+                ),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_interrupted",
+            HashMap::from([
+                (Language::English, "Interrupted"),
+                (Language::Arabic, "متوقف"),
+                (Language::French, "Interrompu"),
+                (Language::Chinese, "已中断"),
+            ]),
+        );
+
+        translations.insert(
+            "transfers_requeued",
+            HashMap::from([
+                (Language::English, "interrupted transfers queued again"),
+                (
+                    Language::Arabic,
+                    "عمليات نقل متوقفة أعيدت إلى قائمة الانتظار",
+                ),
+                (
+                    Language::French,
+                    "transferts interrompus remis en file d'attente",
+                ),
+                (Language::Chinese, "个中断的传输已重新排队"),
+            ]),
+        );
+
+        translations.insert(
+            "restore_path_failed",
+            HashMap::from([
+                (Language::English, "Could not reopen"),
+                (Language::Arabic, "تعذر إعادة فتح"),
+                (Language::French, "Impossible de rouvrir"),
+                (Language::Chinese, "无法重新打开"),
+            ]),
+        );
+
+        translations.insert(
+            "canonical_path_label",
+            HashMap::from([
+                (Language::English, "Real path:"),
+                (Language::Arabic, "المسار الحقيقي:"),
+                (Language::French, "Chemin réel :"),
+                (Language::Chinese, "实际路径："),
+            ]),
+        );
+
+        translations.insert(
+            "canonical_path_hint",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Where this directory is after following symbolic links",
+                ),
+                (Language::Arabic, "موقع هذا المجلد بعد تتبع الروابط الرمزية"),
+                (
+                    Language::French,
+                    "Emplacement de ce dossier après résolution des liens symboliques",
+                ),
+                (Language::Chinese, "跟随符号链接后此目录的位置"),
+            ]),
+        );
+
+        translations.insert(
+            "canonical_paths_checkbox",
+            HashMap::from([
+                (Language::English, "Follow real paths"),
+                (Language::Arabic, "اتباع المسارات الحقيقية"),
+                (Language::French, "Suivre les chemins réels"),
+                (Language::Chinese, "使用实际路径"),
+            ]),
+        );
+
+        translations.insert(
+            "canonical_paths_hint",
+            HashMap::from([
+                (Language::English, "Replace symlinked paths with their real location when opening a directory"),
+                (Language::Arabic, "استبدال المسارات المرتبطة رمزياً بموقعها الحقيقي عند فتح مجلد"),
+                (Language::French, "Remplacer les chemins via liens symboliques par leur emplacement réel à l'ouverture d'un dossier"),
+                (Language::Chinese, "打开目录时用实际位置替换符号链接路径"),
+            ]),
+        );
+
+        translations.insert(
+            "select_all_button",
+            HashMap::from([
+                (Language::English, "Select all"),
+                (Language::Arabic, "تحديد الكل"),
+                (Language::French, "Tout sélectionner"),
+                (Language::Chinese, "全选"),
+            ]),
+        );
+
+        translations.insert(
+            "selected_count",
+            HashMap::from([
+                (Language::English, "selected"),
+                (Language::Arabic, "محدد"),
+                (Language::French, "sélectionnés"),
+                (Language::Chinese, "项已选择"),
+            ]),
+        );
+
+        translations.insert(
+            "download_selected_button",
+            HashMap::from([
+                (Language::English, "Download selected…"),
+                (Language::Arabic, "تنزيل المحدد…"),
+                (Language::French, "Télécharger la sélection…"),
+                (Language::Chinese, "下载所选项…"),
+            ]),
+        );
+
+        translations.insert(
+            "clear_selection_button",
+            HashMap::from([
+                (Language::English, "Clear selection"),
+                (Language::Arabic, "مسح التحديد"),
+                (Language::French, "Effacer la sélection"),
+                (Language::Chinese, "清除选择"),
+            ]),
+        );
+
+        translations.insert(
+            "edit_after_create_checkbox",
+            HashMap::from([
+                (Language::English, "Open in editor"),
+                (Language::Arabic, "فتح في المحرر"),
+                (Language::French, "Ouvrir dans l'éditeur"),
+                (Language::Chinese, "在编辑器中打开"),
+            ]),
+        );
+
+        translations.insert(
+            "view_list",
+            HashMap::from([
+                (Language::English, "☰ List"),
+                (Language::Arabic, "☰ قائمة"),
+                (Language::French, "☰ Liste"),
+                (Language::Chinese, "☰ 列表"),
+            ]),
+        );
+
+        translations.insert(
+            "view_grid",
+            HashMap::from([
+                (Language::English, "▦ Grid"),
+                (Language::Arabic, "▦ شبكة"),
+                (Language::French, "▦ Grille"),
+                (Language::Chinese, "▦ 网格"),
+            ]),
+        );
+
+        translations.insert(
+            "open_button",
+            HashMap::from([
+                (Language::English, "Open"),
+                (Language::Arabic, "فتح"),
+                (Language::French, "Ouvrir"),
+                (Language::Chinese, "打开"),
+            ]),
+        );
+
+        translations.insert(
+            "select_entry_checkbox",
+            HashMap::from([
+                (Language::English, "Selected"),
+                (Language::Arabic, "محدد"),
+                (Language::French, "Sélectionné"),
+                (Language::Chinese, "已选择"),
+            ]),
+        );
+
+        translations.insert(
+            "hardlink_button",
+            HashMap::from([
+                (Language::English, "Hard link…"),
+                (Language::Arabic, "رابط صلب…"),
+                (Language::French, "Lien physique…"),
+                (Language::Chinese, "硬链接…"),
+            ]),
+        );
+
+        translations.insert(
+            "hardlink_window",
+            HashMap::from([
+                (Language::English, "Create hard link"),
+                (Language::Arabic, "إنشاء رابط صلب"),
+                (Language::French, "Créer un lien physique"),
+                (Language::Chinese, "创建硬链接"),
+            ]),
+        );
+
+        translations.insert(
+            "hardlink_target_label",
+            HashMap::from([
+                (Language::English, "Link to:"),
+                (Language::Arabic, "ربط بـ:"),
+                (Language::French, "Lier à :"),
+                (Language::Chinese, "链接到："),
+            ]),
+        );
+
+        translations.insert(
+            "hardlink_path_label",
+            HashMap::from([
+                (Language::English, "New link path:"),
+                (Language::Arabic, "مسار الرابط الجديد:"),
+                (Language::French, "Chemin du nouveau lien :"),
+                (Language::Chinese, "新链接路径："),
+            ]),
+        );
+
+        translations.insert(
+            "status_hardlink_created",
+            HashMap::from([
+                (Language::English, "Hard link created"),
+                (Language::Arabic, "تم إنشاء الرابط الصلب"),
+                (Language::French, "Lien physique créé"),
+                (Language::Chinese, "硬链接已创建"),
+            ]),
+        );
+
+        translations.insert(
+            "connection_label_label",
+            HashMap::from([
+                (Language::English, "Label:"),
+                (Language::Arabic, "التسمية:"),
+                (Language::French, "Libellé :"),
+                (Language::Chinese, "标签："),
+            ]),
+        );
+
+        translations.insert(
+            "connection_label_hint",
+            HashMap::from([
+                (Language::English, "e.g. PRODUCTION"),
+                (Language::Arabic, "مثل PRODUCTION"),
+                (Language::French, "ex. PRODUCTION"),
+                (Language::Chinese, "例如 PRODUCTION"),
+            ]),
+        );
+
+        translations.insert(
+            "connection_color_checkbox",
+            HashMap::from([
+                (Language::English, "Color"),
+                (Language::Arabic, "اللون"),
+                (Language::French, "Couleur"),
+                (Language::Chinese, "颜色"),
+            ]),
+        );
+
+        translations.insert(
+            "show_diff_button",
+            HashMap::from([
+                (Language::English, "Show diff"),
+                (Language::Arabic, "عرض الفروق"),
+                (Language::French, "Afficher les différences"),
+                (Language::Chinese, "显示差异"),
+            ]),
+        );
+
+        translations.insert(
+            "review_before_save",
+            HashMap::from([
+                (Language::English, "Review before saving"),
+                (Language::Arabic, "المراجعة قبل الحفظ"),
+                (Language::French, "Relire avant d'enregistrer"),
+                (Language::Chinese, "保存前审阅"),
+            ]),
+        );
+
+        translations.insert(
+            "review_before_save_hint",
+            HashMap::from([
+                (Language::English, "Keep Save disabled until the current changes have been shown in the diff"),
+                (Language::Arabic, "إبقاء الحفظ معطلاً حتى تُعرض التغييرات الحالية في الفروق"),
+                (Language::French, "Garder Enregistrer désactivé tant que les modifications n'ont pas été affichées"),
+                (Language::Chinese, "在差异中查看当前更改之前禁用保存"),
+            ]),
+        );
+
+        translations.insert(
+            "diff_window",
+            HashMap::from([
+                (Language::English, "Changes"),
+                (Language::Arabic, "التغييرات"),
+                (Language::French, "Modifications"),
+                (Language::Chinese, "更改"),
+            ]),
+        );
+
+        translations.insert(
+            "diff_no_changes",
+            HashMap::from([
+                (Language::English, "No changes"),
+                (Language::Arabic, "لا توجد تغييرات"),
+                (Language::French, "Aucune modification"),
+                (Language::Chinese, "没有更改"),
+            ]),
+        );
+
+        translations.insert(
+            "diff_outdated",
+            HashMap::from([
+                (
+                    Language::English,
+                    "The text changed since this diff was made; click Show diff again.",
+                ),
+                (
+                    Language::Arabic,
+                    "تغير النص منذ إنشاء هذه الفروق؛ انقر على عرض الفروق مجدداً.",
+                ),
+                (
+                    Language::French,
+                    "Le texte a changé depuis ; cliquez à nouveau sur Afficher les différences.",
+                ),
+                (
+                    Language::Chinese,
+                    "文本在生成此差异后已更改；请再次点击显示差异。",
+                ),
+            ]),
+        );
+
+        translations.insert(
+            "sort_label",
+            HashMap::from([
+                (Language::English, "Sort:"),
+                (Language::Arabic, "الترتيب:"),
+                (Language::French, "Tri :"),
+                (Language::Chinese, "排序："),
+            ]),
+        );
+
+        translations.insert(
+            "sort_name",
+            HashMap::from([
+                (Language::English, "Name"),
+                (Language::Arabic, "الاسم"),
+                (Language::French, "Nom"),
+                (Language::Chinese, "名称"),
+            ]),
+        );
+
+        translations.insert(
+            "sort_modified",
+            HashMap::from([
+                (Language::English, "Modified"),
+                (Language::Arabic, "تاريخ التعديل"),
+                (Language::French, "Modification"),
+                (Language::Chinese, "修改时间"),
+            ]),
+        );
+
+        translations.insert(
+            "sort_size",
+            HashMap::from([
+                (Language::English, "Size"),
+                (Language::Arabic, "الحجم"),
+                (Language::French, "Taille"),
+                (Language::Chinese, "大小"),
+            ]),
+        );
+
+        translations.insert(
+            "sort_descending",
+            HashMap::from([
+                (Language::English, "Reverse the order"),
+                (Language::Arabic, "عكس الترتيب"),
+                (Language::French, "Inverser l'ordre"),
+                (Language::Chinese, "反向排序"),
+            ]),
+        );
+
+        translations.insert(
+            "stop_button",
+            HashMap::from([
+                (Language::English, "Stop"),
+                (Language::Arabic, "إيقاف"),
+                (Language::French, "Arrêter"),
+                (Language::Chinese, "停止"),
+            ]),
+        );
+
+        translations.insert(
+            "command_running",
+            HashMap::from([
+                (Language::English, "Running…"),
+                (Language::Arabic, "قيد التشغيل…"),
+                (Language::French, "En cours…"),
+                (Language::Chinese, "运行中…"),
+            ]),
+        );
+
+        translations.insert(
+            "status_command_stopped",
+            HashMap::from([
+                (Language::English, "Command stopped"),
+                (Language::Arabic, "تم إيقاف الأمر"),
+                (Language::French, "Commande arrêtée"),
+                (Language::Chinese, "命令已停止"),
+            ]),
+        );
+
+        translations.insert(
+            "atomic_save",
+            HashMap::from([
+                (Language::English, "Safe save"),
+                (Language::Arabic, "حفظ آمن"),
+                (Language::French, "Enregistrement sûr"),
+                (Language::Chinese, "安全保存"),
+            ]),
+        );
+
+        translations.insert(
+            "atomic_save_hint",
+            HashMap::from([
+                (Language::English, "Write to a temporary file and rename it over the original, so an interrupted save never leaves it half written"),
+                (Language::Arabic, "الكتابة في ملف مؤقت ثم إعادة تسميته فوق الأصلي، حتى لا يبقى الملف مكتوبًا جزئيًا عند انقطاع الحفظ"),
+                (Language::French, "Écrire dans un fichier temporaire puis le renommer à la place de l'original, pour qu'un enregistrement interrompu ne le laisse jamais à moitié écrit"),
+                (Language::Chinese, "先写入临时文件再重命名覆盖原文件，保存中断时原文件不会只写入一半"),
+            ]),
+        );
+
+        translations.insert(
+            "saved_connections_skipped",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Saved connections that could not be read were skipped:",
+                ),
+                (
+                    Language::Arabic,
+                    "تم تخطي الاتصالات المحفوظة التي تعذرت قراءتها:",
+                ),
+                (
+                    Language::French,
+                    "Connexions enregistrées illisibles ignorées :",
+                ),
+                (Language::Chinese, "已跳过无法读取的已保存连接："),
+            ]),
+        );
+
+        translations.insert(
+            "default_encoding_label",
+            HashMap::from([
+                (Language::English, "Open files as:"),
+                (Language::Arabic, "فتح الملفات بترميز:"),
+                (Language::French, "Ouvrir les fichiers en :"),
+                (Language::Chinese, "打开文件编码："),
+            ]),
+        );
+
+        translations.insert(
+            "encoding_auto",
+            HashMap::from([
+                (Language::English, "Auto-detect"),
+                (Language::Arabic, "اكتشاف تلقائي"),
+                (Language::French, "Détection automatique"),
+                (Language::Chinese, "自动检测"),
+            ]),
+        );
+
+        translations.insert(
+            "default_encoding_hint",
+            HashMap::from([
+                (Language::English, "The encoding used to read files opened in the editor. Auto-detect guesses it from the content; a byte order mark is always honored."),
+                (Language::Arabic, "الترميز المستخدم لقراءة الملفات المفتوحة في المحرر. الاكتشاف التلقائي يخمنه من المحتوى؛ وتُحترم علامة ترتيب البايت دائمًا."),
+                (Language::French, "L'encodage utilisé pour lire les fichiers ouverts dans l'éditeur. La détection automatique le devine d'après le contenu ; une marque d'ordre des octets est toujours respectée."),
+                (Language::Chinese, "读取在编辑器中打开的文件时使用的编码。自动检测会根据内容推测；字节顺序标记始终优先。"),
+            ]),
+        );
+
+        translations.insert(
+            "operation_timeout_label",
+            HashMap::from([
+                (Language::English, "Timeout:"),
+                (Language::Arabic, "المهلة:"),
+                (Language::French, "Délai :"),
+                (Language::Chinese, "超时："),
+            ]),
+        );
+
+        translations.insert(
+            "operation_timeout_hint",
+            HashMap::from([
+                (Language::English, "How long one operation may wait for the server before it fails, so an unresponsive server cannot block everything else. 0 waits forever."),
+                (Language::Arabic, "المدة التي قد تنتظرها عملية واحدة للخادم قبل أن تفشل، حتى لا يعطل خادم لا يستجيب كل شيء آخر. 0 يعني الانتظار بلا حد."),
+                (Language::French, "Durée pendant laquelle une opération peut attendre le serveur avant d'échouer, pour qu'un serveur qui ne répond plus ne bloque pas tout le reste. 0 attend indéfiniment."),
+                (Language::Chinese, "单个操作在失败前等待服务器的最长时间，避免无响应的服务器阻塞其他一切。0 表示一直等待。"),
+            ]),
+        );
+
+        translations.insert(
+            "upload_conflicts_window",
+            HashMap::from([
+                (Language::English, "Overwrite existing files?"),
+                (Language::Arabic, "استبدال الملفات الموجودة؟"),
+                (Language::French, "Écraser les fichiers existants ?"),
+                (Language::Chinese, "覆盖现有文件？"),
+            ]),
+        );
+
+        translations.insert(
+            "upload_conflicts_message",
+            HashMap::from([
+                (Language::English, "files of this upload already exist in:"),
+                (Language::Arabic, "ملفات من هذا الرفع موجودة بالفعل في:"),
+                (
+                    Language::French,
+                    "fichiers de cet envoi existent déjà dans :",
+                ),
+                (Language::Chinese, "个要上传的文件已存在于："),
+            ]),
+        );
+
+        translations.insert(
+            "skip_existing_button",
+            HashMap::from([
+                (Language::English, "Skip existing"),
+                (Language::Arabic, "تخطي الموجودة"),
+                (Language::French, "Ignorer les existants"),
+                (Language::Chinese, "跳过已存在的"),
+            ]),
+        );
+
+        translations.insert(
+            "overwrite_all_button",
+            HashMap::from([
+                (Language::English, "Overwrite all"),
+                (Language::Arabic, "استبدال الكل"),
+                (Language::French, "Tout écraser"),
+                (Language::Chinese, "全部覆盖"),
+            ]),
+        );
+
+        translations.insert(
+            "log_level_label",
+            HashMap::from([
+                (Language::English, "Log:"),
+                (Language::Arabic, "السجل:"),
+                (Language::French, "Journal :"),
+                (Language::Chinese, "日志："),
+            ]),
+        );
+
+        translations.insert(
+            "log_file_button",
+            HashMap::from([
+                (Language::English, "Log file"),
+                (Language::Arabic, "ملف السجل"),
+                (Language::French, "Fichier journal"),
+                (Language::Chinese, "日志文件"),
+            ]),
+        );
+
+        translations.insert(
+            "log_file_hint",
+            HashMap::from([
+                (Language::English, "The log is only written to the terminal"),
+                (Language::Arabic, "يُكتب السجل في الطرفية فقط"),
+                (
+                    Language::French,
+                    "Le journal n'est écrit que dans le terminal",
+                ),
+                (Language::Chinese, "日志仅写入终端"),
+            ]),
+        );
+
+        translations.insert(
+            "log_file_choose",
+            HashMap::from([
+                (Language::English, "Write to a file…"),
+                (Language::Arabic, "الكتابة في ملف…"),
+                (Language::French, "Écrire dans un fichier…"),
+                (Language::Chinese, "写入文件…"),
+            ]),
+        );
+
+        translations.insert(
+            "log_file_none",
+            HashMap::from([
+                (Language::English, "Stop writing to a file"),
+                (Language::Arabic, "إيقاف الكتابة في ملف"),
+                (Language::French, "Arrêter d'écrire dans un fichier"),
+                (Language::Chinese, "停止写入文件"),
+            ]),
+        );
+
+        translations.insert(
+            "terminal_panel",
+            HashMap::from([
+                (Language::English, "Terminal"),
+                (Language::Arabic, "الطرفية"),
+                (Language::French, "Terminal"),
+                (Language::Chinese, "终端"),
+            ]),
+        );
+
+        translations.insert(
+            "open_terminal_button",
+            HashMap::from([
+                (Language::English, "Open Terminal"),
+                (Language::Arabic, "فتح الطرفية"),
+                (Language::French, "Ouvrir le terminal"),
+                (Language::Chinese, "打开终端"),
+            ]),
+        );
+
+        translations.insert(
+            "close_terminal_button",
+            HashMap::from([
+                (Language::English, "Close Terminal"),
+                (Language::Arabic, "إغلاق الطرفية"),
+                (Language::French, "Fermer le terminal"),
+                (Language::Chinese, "关闭终端"),
+            ]),
+        );
+
+        translations.insert(
+            "terminal_hint",
+            HashMap::from([
+                (Language::English, "Click the terminal to type into it."),
+                (Language::Arabic, "انقر على الطرفية للكتابة فيها."),
+                (
+                    Language::French,
+                    "Cliquez sur le terminal pour y saisir du texte.",
+                ),
+                (Language::Chinese, "点击终端以输入。"),
+            ]),
+        );
+
+        translations.insert(
+            "terminal_exited",
+            HashMap::from([
+                (Language::English, "Terminal closed with exit status"),
+                (Language::Arabic, "أُغلقت الطرفية برمز الخروج"),
+                (Language::French, "Terminal fermé avec le code de sortie"),
+                (Language::Chinese, "终端已关闭，退出状态"),
+            ]),
+        );
+
+        translations.insert(
+            "save_output_button",
+            HashMap::from([
+                (Language::English, "Save Output…"),
+                (Language::Arabic, "حفظ المخرجات…"),
+                (Language::French, "Enregistrer la sortie…"),
+                (Language::Chinese, "保存输出…"),
+            ]),
+        );
+
+        translations.insert(
+            "output_saved",
+            HashMap::from([
+                (Language::English, "Output saved to"),
+                (Language::Arabic, "حُفظت المخرجات في"),
+                (Language::French, "Sortie enregistrée dans"),
+                (Language::Chinese, "输出已保存到"),
+            ]),
+        );
+
+        translations.insert(
+            "output_save_failed",
+            HashMap::from([
+                (Language::English, "Failed to save the output:"),
+                (Language::Arabic, "فشل حفظ المخرجات:"),
+                (Language::French, "Échec de l'enregistrement de la sortie :"),
+                (Language::Chinese, "保存输出失败："),
+            ]),
+        );
+
+        translations.insert(
+            "listing_truncated_prefix",
+            HashMap::from([
+                (
+                    Language::English,
+                    "This folder is too large: only the first",
+                ),
+                (Language::Arabic, "هذا المجلد كبير جدًا: تُعرض أول"),
+                (Language::French, "Ce dossier est trop grand : seules les"),
+                (Language::Chinese, "此文件夹过大：仅显示前"),
+            ]),
+        );
+
+        translations.insert(
+            "listing_truncated_suffix",
+            HashMap::from([
+                (Language::English, "entries are shown. Use Find to locate other files."),
+                (Language::Arabic, "عنصر فقط. استخدم البحث للعثور على ملفات أخرى."),
+                (Language::French, "premières entrées sont affichées. Utilisez la recherche pour trouver les autres fichiers."),
+                (Language::Chinese, "个条目。使用查找来定位其他文件。"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_retries_label",
+            HashMap::from([
+                (Language::English, "Retries:"),
+                (Language::Arabic, "المحاولات:"),
+                (Language::French, "Tentatives :"),
+                (Language::Chinese, "重试次数："),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_retries_hint",
+            HashMap::from([
+                (Language::English, "How often a transfer retries a chunk that failed, e.g. on a flaky network, before giving up. The pause doubles with each retry."),
+                (Language::Arabic, "عدد مرات إعادة محاولة جزء فشل من النقل، مثلًا على شبكة غير مستقرة، قبل الاستسلام. تتضاعف المهلة مع كل محاولة."),
+                (Language::French, "Nombre de nouvelles tentatives d'un bloc de transfert en échec, par exemple sur un réseau instable, avant d'abandonner. La pause double à chaque tentative."),
+                (Language::Chinese, "传输中失败的数据块在放弃前重试的次数，例如网络不稳定时。每次重试的等待时间加倍。"),
+            ]),
+        );
+
+        translations.insert(
+            "file_changed_window",
+            HashMap::from([
+                (Language::English, "File Changed on the Server"),
+                (Language::Arabic, "تغيّر الملف على الخادم"),
+                (Language::French, "Fichier modifié sur le serveur"),
+                (Language::Chinese, "服务器上的文件已更改"),
+            ]),
+        );
+
+        translations.insert(
+            "file_changed_message",
+            HashMap::from([
+                (Language::English, "This file was changed on the server since you opened it. Saving now would discard those changes."),
+                (Language::Arabic, "تغيّر هذا الملف على الخادم منذ فتحه. الحفظ الآن سيتجاهل تلك التغييرات."),
+                (Language::French, "Ce fichier a été modifié sur le serveur depuis son ouverture. Enregistrer maintenant effacerait ces modifications."),
+                (Language::Chinese, "自您打开此文件后，它已在服务器上被修改。现在保存将丢弃这些修改。"),
+            ]),
+        );
+
+        translations.insert(
+            "compare_button",
+            HashMap::from([
+                (Language::English, "Compare"),
+                (Language::Arabic, "مقارنة"),
+                (Language::French, "Comparer"),
+                (Language::Chinese, "比较"),
+            ]),
+        );
+
+        translations.insert(
+            "compare_hint",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Show your edits as a diff from the version now on the server",
+                ),
+                (
+                    Language::Arabic,
+                    "عرض تعديلاتك كفرق عن النسخة الموجودة الآن على الخادم",
+                ),
+                (
+                    Language::French,
+                    "Afficher vos modifications par rapport à la version actuelle du serveur",
+                ),
+                (
+                    Language::Chinese,
+                    "以差异形式显示您的编辑与服务器当前版本的区别",
+                ),
+            ]),
+        );
+
+        translations.insert(
+            "reload_button",
+            HashMap::from([
+                (Language::English, "Reload"),
+                (Language::Arabic, "إعادة التحميل"),
+                (Language::French, "Recharger"),
+                (Language::Chinese, "重新加载"),
+            ]),
+        );
+
+        translations.insert(
+            "reload_hint",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Discard your edits and open the version now on the server",
+                ),
+                (
+                    Language::Arabic,
+                    "تجاهل تعديلاتك وفتح النسخة الموجودة الآن على الخادم",
+                ),
+                (
+                    Language::French,
+                    "Abandonner vos modifications et ouvrir la version actuelle du serveur",
+                ),
+                (Language::Chinese, "放弃您的编辑并打开服务器上的当前版本"),
+            ]),
+        );
+
+        translations.insert(
+            "overwrite_button",
+            HashMap::from([
+                (Language::English, "Overwrite"),
+                (Language::Arabic, "استبدال"),
+                (Language::French, "Écraser"),
+                (Language::Chinese, "覆盖"),
+            ]),
+        );
+
+        translations.insert(
+            "about_button",
+            HashMap::from([
+                (Language::English, "About"),
+                (Language::Arabic, "حول"),
+                (Language::French, "À propos"),
+                (Language::Chinese, "关于"),
+            ]),
+        );
+
+        translations.insert(
+            "about_window",
+            HashMap::from([
+                (Language::English, "About"),
+                (Language::Arabic, "حول البرنامج"),
+                (Language::French, "À propos"),
+                (Language::Chinese, "关于"),
+            ]),
+        );
+
+        translations.insert(
+            "about_not_connected",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Connect to a server to see the algorithms negotiated with it.",
+                ),
+                (
+                    Language::Arabic,
+                    "اتصل بخادم لرؤية الخوارزميات المتفق عليها معه.",
+                ),
+                (
+                    Language::French,
+                    "Connectez-vous à un serveur pour voir les algorithmes négociés avec lui.",
+                ),
+                (Language::Chinese, "连接到服务器以查看与其协商的算法。"),
+            ]),
+        );
+
+        translations.insert(
+            "speed_limit_label",
+            HashMap::from([
+                (Language::English, "Speed limit:"),
+                (Language::Arabic, "حد السرعة:"),
+                (Language::French, "Limite de débit :"),
+                (Language::Chinese, "速度限制："),
+            ]),
+        );
+
+        translations.insert(
+            "speed_limit_hint",
+            HashMap::from([
+                (Language::English, "Most data a transfer moves per second, so that it leaves room for other traffic on the link. ∞ (0) transfers as fast as possible."),
+                (Language::Arabic, "أقصى قدر من البيانات ينقله النقل في الثانية، ليترك مجالًا لحركة أخرى على الاتصال. ∞ (0) ينقل بأقصى سرعة ممكنة."),
+                (Language::French, "Quantité maximale de données transférée par seconde, pour laisser de la place aux autres échanges sur la liaison. ∞ (0) transfère aussi vite que possible."),
+                (Language::Chinese, "每个传输每秒最多传送的数据量，为链路上的其他流量留出余地。∞（0）表示尽可能快地传输。"),
+            ]),
+        );
+
+        translations.insert(
+            "own_speed_limit",
+            HashMap::from([
+                (Language::English, "Own limit"),
+                (Language::Arabic, "حد خاص"),
+                (Language::French, "Limite propre"),
+                (Language::Chinese, "单独限速"),
+            ]),
+        );
+
+        translations.insert(
+            "own_speed_limit_hint",
+            HashMap::from([
+                (Language::English, "Give this transfer its own speed limit instead of the one in the toolbar"),
+                (Language::Arabic, "منح هذا النقل حد سرعة خاصًا به بدلًا من الحد في شريط الأدوات"),
+                (Language::French, "Donner à ce transfert sa propre limite de débit au lieu de celle de la barre d'outils"),
+                (Language::Chinese, "为此传输单独设置速度限制，而不使用工具栏中的限制"),
+            ]),
+        );
+
+        translations.insert(
+            "create_parents_label",
+            HashMap::from([
+                (Language::English, "Create parent folders"),
+                (Language::Arabic, "إنشاء المجلدات الأصلية"),
+                (Language::French, "Créer les dossiers parents"),
+                (Language::Chinese, "创建上级文件夹"),
+            ]),
+        );
+
+        translations.insert(
+            "create_parents_hint",
+            HashMap::from([
+                (Language::English, "Create missing folders above the upload destination first, like mkdir -p"),
+                (Language::Arabic, "إنشاء المجلدات المفقودة فوق وجهة الرفع أولًا، مثل mkdir -p"),
+                (Language::French, "Créer d'abord les dossiers manquants au-dessus de la destination, comme mkdir -p"),
+                (Language::Chinese, "先创建上传目标之上缺失的文件夹，类似 mkdir -p"),
+            ]),
+        );
+
+        translations.insert(
+            "missing_parent_window",
+            HashMap::from([
+                (Language::English, "Folder Not Found"),
+                (Language::Arabic, "المجلد غير موجود"),
+                (Language::French, "Dossier introuvable"),
+                (Language::Chinese, "找不到文件夹"),
+            ]),
+        );
+
+        translations.insert(
+            "missing_parent_message",
+            HashMap::from([
+                (
+                    Language::English,
+                    "The upload failed because this folder does not exist on the server:",
+                ),
+                (
+                    Language::Arabic,
+                    "فشل الرفع لأن هذا المجلد غير موجود على الخادم:",
+                ),
+                (
+                    Language::French,
+                    "L'envoi a échoué car ce dossier n'existe pas sur le serveur :",
+                ),
+                (Language::Chinese, "上传失败，因为服务器上不存在此文件夹："),
+            ]),
+        );
+
+        translations.insert(
+            "create_and_upload_button",
+            HashMap::from([
+                (Language::English, "Create Folder and Upload"),
+                (Language::Arabic, "إنشاء المجلد والرفع"),
+                (Language::French, "Créer le dossier et envoyer"),
+                (Language::Chinese, "创建文件夹并上传"),
+            ]),
+        );
+
+        translations.insert(
+            "connection_lost",
+            HashMap::from([
+                (
+                    Language::English,
+                    "The connection to the server was lost. Connect again to continue.",
+                ),
+                (
+                    Language::Arabic,
+                    "انقطع الاتصال بالخادم. اتصل مجددًا للمتابعة.",
+                ),
+                (
+                    Language::French,
+                    "La connexion au serveur a été perdue. Reconnectez-vous pour continuer.",
+                ),
+                (
+                    Language::Chinese,
+                    "与服务器的连接已断开。请重新连接以继续。",
+                ),
+            ]),
+        );
+
+        translations.insert(
+            "close_button",
+            HashMap::from([
+                (Language::English, "Close"),
+                (Language::Arabic, "إغلاق"),
+                (Language::French, "Fermer"),
+                (Language::Chinese, "关闭"),
+            ]),
+        );
+
+        translations.insert(
+            "cancel_connect_button",
+            HashMap::from([
+                (Language::English, "Cancel Connecting"),
+                (Language::Arabic, "إلغاء الاتصال"),
+                (Language::French, "Annuler la connexion"),
+                (Language::Chinese, "取消连接"),
+            ]),
+        );
+
+        translations.insert(
+            "status_connect_cancelled",
+            HashMap::from([
+                (Language::English, "Connection attempt cancelled."),
+                (Language::Arabic, "تم إلغاء محاولة الاتصال."),
+                (Language::French, "Tentative de connexion annulée."),
+                (Language::Chinese, "已取消连接尝试。"),
+            ]),
+        );
+
+        translations.insert(
+            "recent_button",
+            HashMap::from([
+                (Language::English, "Recent"),
+                (Language::Arabic, "الأخيرة"),
+                (Language::French, "Récents"),
+                (Language::Chinese, "最近"),
+            ]),
+        );
+
+        translations.insert(
+            "move_up_hint",
+            HashMap::from([
+                (Language::English, "Run this transfer earlier"),
+                (Language::Arabic, "تشغيل هذا النقل في وقت أبكر"),
+                (Language::French, "Exécuter ce transfert plus tôt"),
+                (Language::Chinese, "提前执行此传输"),
+            ]),
+        );
+
+        translations.insert(
+            "move_down_hint",
+            HashMap::from([
+                (Language::English, "Run this transfer later"),
+                (Language::Arabic, "تشغيل هذا النقل في وقت لاحق"),
+                (Language::French, "Exécuter ce transfert plus tard"),
+                (Language::Chinese, "推后执行此传输"),
+            ]),
+        );
+
+        translations.insert(
+            "completion_tab_hint",
+            HashMap::from([
+                (Language::English, "Tab completes this folder"),
+                (Language::Arabic, "يكمل Tab هذا المجلد"),
+                (Language::French, "Tab complète ce dossier"),
+                (Language::Chinese, "按 Tab 补全此文件夹"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_replace_window",
+            HashMap::from([
+                (Language::English, "Replace File"),
+                (Language::Arabic, "استبدال الملف"),
+                (Language::French, "Remplacer le fichier"),
+                (Language::Chinese, "替换文件"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_replace_message",
+            HashMap::from([
+                (
+                    Language::English,
+                    "A file with this name already exists. Replace it?",
+                ),
+                (
+                    Language::Arabic,
+                    "يوجد ملف بهذا الاسم بالفعل. هل تريد استبداله؟",
+                ),
+                (
+                    Language::French,
+                    "Un fichier portant ce nom existe déjà. Le remplacer ?",
+                ),
+                (Language::Chinese, "同名文件已存在。要替换它吗？"),
+            ]),
+        );
+
+        translations.insert(
+            "replace_button",
+            HashMap::from([
+                (Language::English, "Replace"),
+                (Language::Arabic, "استبدال"),
+                (Language::French, "Remplacer"),
+                (Language::Chinese, "替换"),
+            ]),
+        );
+
+        translations.insert(
+            "path_exists_error",
+            HashMap::from([
+                (Language::English, "Something with this name already exists"),
+                (Language::Arabic, "يوجد بالفعل عنصر بهذا الاسم"),
+                (Language::French, "Un élément portant ce nom existe déjà"),
+                (Language::Chinese, "已存在同名项目"),
+            ]),
+        );
+
+        translations.insert(
+            "save_refused_window",
+            HashMap::from([
+                (Language::English, "Cannot Save"),
+                (Language::Arabic, "تعذر الحفظ"),
+                (Language::French, "Enregistrement impossible"),
+                (Language::Chinese, "无法保存"),
+            ]),
+        );
+
+        translations.insert(
+            "save_permission_denied_message",
+            HashMap::from([
+                (Language::English, "You do not have permission to write this file. Your edits are kept in the editor."),
+                (Language::Arabic, "ليس لديك إذن بالكتابة في هذا الملف. تم الاحتفاظ بتعديلاتك في المحرر."),
+                (Language::French, "Vous n'avez pas le droit d'écrire ce fichier. Vos modifications restent dans l'éditeur."),
+                (Language::Chinese, "您没有写入此文件的权限。您的编辑仍保留在编辑器中。"),
+            ]),
+        );
+
+        translations.insert(
+            "save_read_only_message",
+            HashMap::from([
+                (Language::English, "This file is on a read-only file system. Your edits are kept in the editor."),
+                (Language::Arabic, "هذا الملف موجود على نظام ملفات للقراءة فقط. تم الاحتفاظ بتعديلاتك في المحرر."),
+                (Language::French, "Ce fichier se trouve sur un système de fichiers en lecture seule. Vos modifications restent dans l'éditeur."),
+                (Language::Chinese, "此文件位于只读文件系统上。您的编辑仍保留在编辑器中。"),
+            ]),
+        );
+
+        translations.insert(
+            "save_as_label",
+            HashMap::from([
+                (Language::English, "Save as:"),
+                (Language::Arabic, "حفظ باسم:"),
+                (Language::French, "Enregistrer sous :"),
+                (Language::Chinese, "另存为："),
+            ]),
+        );
+
+        translations.insert(
+            "save_as_button",
+            HashMap::from([
+                (Language::English, "Save Here"),
+                (Language::Arabic, "احفظ هنا"),
+                (Language::French, "Enregistrer ici"),
+                (Language::Chinese, "保存到此处"),
+            ]),
+        );
+
+        translations.insert(
+            "retry_with_sudo_button",
+            HashMap::from([
+                (Language::English, "Retry with Sudo"),
+                (Language::Arabic, "إعادة المحاولة باستخدام sudo"),
+                (Language::French, "Réessayer avec sudo"),
+                (Language::Chinese, "使用 sudo 重试"),
+            ]),
+        );
+
+        translations.insert(
+            "retry_with_sudo_hint",
+            HashMap::from([
+                (Language::English, "Turn on sudo mode and save again. The sudo password is the login password unless another one is set."),
+                (Language::Arabic, "تفعيل وضع sudo والحفظ مرة أخرى. كلمة مرور sudo هي كلمة مرور تسجيل الدخول ما لم تُحدَّد كلمة أخرى."),
+                (Language::French, "Active le mode sudo et enregistre à nouveau. Le mot de passe sudo est celui de connexion, sauf si un autre est défini."),
+                (Language::Chinese, "开启 sudo 模式并重新保存。除非另行设置，sudo 密码即登录密码。"),
+            ]),
+        );
+
+        translations.insert(
+            "write_into_button",
+            HashMap::from([
+                (Language::English, "Append / Write at..."),
+                (Language::Arabic, "إلحاق / كتابة عند..."),
+                (Language::French, "Ajouter / Écrire à..."),
+                (Language::Chinese, "追加 / 在指定位置写入..."),
+            ]),
+        );
+
+        translations.insert(
+            "write_into_hint",
+            HashMap::from([
+                (Language::English, "Append text to the file or overwrite part of it without transferring the whole file"),
+                (Language::Arabic, "إلحاق نص بالملف أو الكتابة فوق جزء منه دون نقل الملف كاملاً"),
+                (Language::French, "Ajouter du texte au fichier ou en remplacer une partie sans transférer le fichier entier"),
+                (Language::Chinese, "向文件追加文本或覆盖其中一部分，而无需传输整个文件"),
+            ]),
+        );
+
+        translations.insert(
+            "write_into_window",
+            HashMap::from([
+                (Language::English, "Write Into File"),
+                (Language::Arabic, "الكتابة في الملف"),
+                (Language::French, "Écrire dans le fichier"),
+                (Language::Chinese, "写入文件"),
+            ]),
+        );
+
+        translations.insert(
+            "write_offset_label",
+            HashMap::from([
+                (Language::English, "At byte offset:"),
+                (Language::Arabic, "عند إزاحة البايت:"),
+                (Language::French, "À l'octet :"),
+                (Language::Chinese, "字节偏移："),
+            ]),
+        );
+
+        translations.insert(
+            "write_offset_hint",
+            HashMap::from([
+                (Language::English, "end of file"),
+                (Language::Arabic, "نهاية الملف"),
+                (Language::French, "fin du fichier"),
+                (Language::Chinese, "文件末尾"),
+            ]),
+        );
+
+        translations.insert(
+            "write_button",
+            HashMap::from([
+                (Language::English, "Write"),
+                (Language::Arabic, "كتابة"),
+                (Language::French, "Écrire"),
+                (Language::Chinese, "写入"),
+            ]),
+        );
+
+        translations.insert(
+            "invalid_offset_error",
+            HashMap::from([
+                (
+                    Language::English,
+                    "The offset must be a whole number of bytes",
+                ),
+                (
+                    Language::Arabic,
+                    "يجب أن تكون الإزاحة عدداً صحيحاً من البايتات",
+                ),
+                (
+                    Language::French,
+                    "Le décalage doit être un nombre entier d'octets",
+                ),
+                (Language::Chinese, "偏移量必须是整数字节数"),
+            ]),
+        );
+
+        translations.insert(
+            "status_written_into",
+            HashMap::from([
+                (Language::English, "Written into the file"),
+                (Language::Arabic, "تمت الكتابة في الملف"),
+                (Language::French, "Écrit dans le fichier"),
+                (Language::Chinese, "已写入文件"),
+            ]),
+        );
+
+        translations.insert(
+            "remote_disk_full",
+            HashMap::from([
+                (
+                    Language::English,
+                    "The remote disk is full, or your disk quota is used up.",
+                ),
+                (
+                    Language::Arabic,
+                    "القرص البعيد ممتلئ، أو استُنفدت حصتك من القرص.",
+                ),
+                (
+                    Language::French,
+                    "Le disque distant est plein, ou votre quota est épuisé.",
+                ),
+                (Language::Chinese, "远程磁盘已满，或您的磁盘配额已用完。"),
+            ]),
+        );
+
+        translations.insert(
+            "hover_actions_checkbox",
+            HashMap::from([
+                (Language::English, "Actions on hover"),
+                (Language::Arabic, "الإجراءات عند التمرير"),
+                (Language::French, "Actions au survol"),
+                (Language::Chinese, "悬停时显示操作"),
+            ]),
+        );
+
+        translations.insert(
+            "hover_actions_hint",
+            HashMap::from([
+                (Language::English, "Show the download, edit, rename and delete buttons of a row only while the pointer is over it. They are also in the context menu."),
+                (Language::Arabic, "إظهار أزرار التنزيل والتحرير وإعادة التسمية والحذف لصف ما فقط عند مرور المؤشر فوقه. وهي موجودة أيضاً في القائمة السياقية."),
+                (Language::French, "N'afficher les boutons télécharger, modifier, renommer et supprimer d'une ligne que lorsque le pointeur la survole. Ils sont aussi dans le menu contextuel."),
+                (Language::Chinese, "仅在指针悬停于某行时显示该行的下载、编辑、重命名和删除按钮。这些操作也在右键菜单中。"),
+            ]),
+        );
+
+        translations.insert(
+            "grep_panel",
+            HashMap::from([
+                (Language::English, "Search File Contents"),
+                (Language::Arabic, "البحث في محتوى الملفات"),
+                (Language::French, "Rechercher dans les fichiers"),
+                (Language::Chinese, "搜索文件内容"),
+            ]),
+        );
+
+        translations.insert(
+            "grep_pattern_hint",
+            HashMap::from([
+                (Language::English, "Pattern (grep regular expression)"),
+                (Language::Arabic, "النمط (تعبير grep النمطي)"),
+                (Language::French, "Motif (expression régulière grep)"),
+                (Language::Chinese, "模式（grep 正则表达式）"),
+            ]),
+        );
+
+        translations.insert(
+            "grep_ignore_case",
+            HashMap::from([
+                (Language::English, "Ignore case"),
+                (Language::Arabic, "تجاهل حالة الأحرف"),
+                (Language::French, "Ignorer la casse"),
+                (Language::Chinese, "忽略大小写"),
+            ]),
+        );
+
+        translations.insert(
+            "grep_files_label",
+            HashMap::from([
+                (Language::English, "Files:"),
+                (Language::Arabic, "الملفات:"),
+                (Language::French, "Fichiers :"),
+                (Language::Chinese, "文件："),
+            ]),
+        );
+
+        translations.insert(
+            "grep_results_count",
+            HashMap::from([
+                (Language::English, "matching lines"),
+                (Language::Arabic, "سطر مطابق"),
+                (Language::French, "lignes correspondantes"),
+                (Language::Chinese, "个匹配行"),
+            ]),
+        );
+
+        translations.insert(
+            "grep_result_hint",
+            HashMap::from([
+                (Language::English, "Open the file at this line"),
+                (Language::Arabic, "فتح الملف عند هذا السطر"),
+                (Language::French, "Ouvrir le fichier à cette ligne"),
+                (Language::Chinese, "在此行打开文件"),
+            ]),
+        );
+
+        translations.insert(
+            "grep_unavailable",
+            HashMap::from([
+                (Language::English, "grep is not available on the server, so file contents cannot be searched"),
+                (Language::Arabic, "الأمر grep غير متوفر على الخادم، لذا لا يمكن البحث في محتوى الملفات"),
+                (Language::French, "grep n'est pas disponible sur le serveur : impossible de rechercher dans les fichiers"),
+                (Language::Chinese, "服务器上没有 grep，无法搜索文件内容"),
+            ]),
+        );
+
+        translations.insert(
+            "filter_hint",
+            HashMap::from([
+                (Language::English, "Filter names"),
+                (Language::Arabic, "تصفية الأسماء"),
+                (Language::French, "Filtrer les noms"),
+                (Language::Chinese, "筛选名称"),
+            ]),
+        );
+
+        translations.insert(
+            "show_hidden_checkbox",
+            HashMap::from([
+                (Language::English, "Hidden files"),
+                (Language::Arabic, "الملفات المخفية"),
+                (Language::French, "Fichiers cachés"),
+                (Language::Chinese, "隐藏文件"),
+            ]),
+        );
+
+        translations.insert(
+            "views_per_directory_checkbox",
+            HashMap::from([
+                (Language::English, "Per folder"),
+                (Language::Arabic, "لكل مجلد"),
+                (Language::French, "Par dossier"),
+                (Language::Chinese, "按文件夹"),
+            ]),
+        );
+
+        translations.insert(
+            "views_per_directory_hint",
+            HashMap::from([
+                (Language::English, "Remember the layout, order, filter and hidden files for each folder; otherwise the same settings apply everywhere"),
+                (Language::Arabic, "تذكر التخطيط والترتيب والتصفية والملفات المخفية لكل مجلد، وإلا تُطبَّق الإعدادات نفسها في كل مكان"),
+                (Language::French, "Mémoriser la disposition, l'ordre, le filtre et les fichiers cachés pour chaque dossier ; sinon les mêmes réglages s'appliquent partout"),
+                (Language::Chinese, "为每个文件夹记住布局、排序、筛选和隐藏文件设置；否则所有位置使用相同设置"),
+            ]),
+        );
+
+        translations.insert(
+            "dry_run_checkbox",
+            HashMap::from([
+                (Language::English, "Dry run"),
+                (Language::Arabic, "تشغيل تجريبي"),
+                (Language::French, "Simulation"),
+                (Language::Chinese, "试运行"),
+            ]),
+        );
+
+        translations.insert(
+            "dry_run_hint",
+            HashMap::from([
+                (Language::English, "List every path a recursive delete or a move would change, and ask before doing it"),
+                (Language::Arabic, "عرض كل مسار سيتغير بالحذف المتكرر أو النقل، والسؤال قبل التنفيذ"),
+                (Language::French, "Lister chaque chemin qu'une suppression récursive ou un déplacement modifierait, et demander avant de le faire"),
+                (Language::Chinese, "列出递归删除或移动将更改的每个路径，并在执行前询问"),
+            ]),
+        );
+
+        translations.insert(
+            "dry_run_window",
+            HashMap::from([
+                (Language::English, "Dry Run"),
+                (Language::Arabic, "تشغيل تجريبي"),
+                (Language::French, "Simulation"),
+                (Language::Chinese, "试运行"),
+            ]),
+        );
+
+        translations.insert(
+            "dry_run_message",
+            HashMap::from([
+                (
+                    Language::English,
+                    "changes would be made. Nothing has been changed yet.",
+                ),
+                (Language::Arabic, "تغييرات ستُجرى. لم يتغير شيء بعد."),
+                (
+                    Language::French,
+                    "modifications seraient faites. Rien n'a encore été modifié.",
+                ),
+                (Language::Chinese, "项更改将被执行。目前尚未更改任何内容。"),
+            ]),
+        );
+
+        translations.insert(
+            "dry_run_delete",
+            HashMap::from([
+                (Language::English, "delete"),
+                (Language::Arabic, "حذف"),
+                (Language::French, "supprimer"),
+                (Language::Chinese, "删除"),
+            ]),
+        );
+
+        translations.insert(
+            "dry_run_move",
+            HashMap::from([
+                (Language::English, "move"),
+                (Language::Arabic, "نقل"),
+                (Language::French, "déplacer"),
+                (Language::Chinese, "移动"),
+            ]),
+        );
+
+        translations.insert(
+            "proceed_button",
+            HashMap::from([
+                (Language::English, "Proceed"),
+                (Language::Arabic, "متابعة"),
+                (Language::French, "Continuer"),
+                (Language::Chinese, "继续"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_history_button",
+            HashMap::from([
+                (Language::English, "Transfer History"),
+                (Language::Arabic, "سجل النقل"),
+                (Language::French, "Historique des transferts"),
+                (Language::Chinese, "传输历史"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_history_window",
+            HashMap::from([
+                (Language::English, "Transfer History"),
+                (Language::Arabic, "سجل النقل"),
+                (Language::French, "Historique des transferts"),
+                (Language::Chinese, "传输历史"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_history_empty",
+            HashMap::from([
+                (Language::English, "No transfers recorded yet."),
+                (Language::Arabic, "لم يُسجَّل أي نقل بعد."),
+                (
+                    Language::French,
+                    "Aucun transfert enregistré pour l'instant.",
+                ),
+                (Language::Chinese, "尚未记录任何传输。"),
+            ]),
+        );
+
+        translations.insert(
+            "transfer_history_clear",
+            HashMap::from([
+                (Language::English, "Clear History"),
+                (Language::Arabic, "مسح السجل"),
+                (Language::French, "Effacer l'historique"),
+                (Language::Chinese, "清除历史"),
+            ]),
+        );
+
+        translations.insert(
+            "confirmations_button",
+            HashMap::from([
+                (Language::English, "Confirmations"),
+                (Language::Arabic, "التأكيدات"),
+                (Language::French, "Confirmations"),
+                (Language::Chinese, "确认提示"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_delete_directory_setting",
+            HashMap::from([
+                (Language::English, "Ask before deleting a folder"),
+                (Language::Arabic, "السؤال قبل حذف مجلد"),
+                (Language::French, "Demander avant de supprimer un dossier"),
+                (Language::Chinese, "删除文件夹前询问"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_overwrite_setting",
+            HashMap::from([
+                (Language::English, "Ask before overwriting files"),
+                (Language::Arabic, "السؤال قبل الكتابة فوق الملفات"),
+                (Language::French, "Demander avant d'écraser des fichiers"),
+                (Language::Chinese, "覆盖文件前询问"),
+            ]),
+        );
+
+        translations.insert(
+            "confirm_exit_with_transfers_setting",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Ask before disconnecting or quitting during transfers",
+                ),
+                (
+                    Language::Arabic,
+                    "السؤال قبل قطع الاتصال أو الخروج أثناء النقل",
+                ),
+                (
+                    Language::French,
+                    "Demander avant de se déconnecter ou de quitter pendant des transferts",
+                ),
+                (Language::Chinese, "传输期间断开连接或退出前询问"),
+            ]),
+        );
+
+        translations.insert(
+            "ok_button",
+            HashMap::from([
+                (Language::English, "OK"),
+                (Language::Arabic, "موافق"),
+                (Language::French, "OK"),
+                (Language::Chinese, "确定"),
+            ]),
+        );
+
+        translations.insert(
+            "auth_questions_window",
+            HashMap::from([
+                (Language::English, "Server Login Question"),
+                (Language::Arabic, "سؤال تسجيل الدخول من الخادم"),
+                (Language::French, "Question de connexion du serveur"),
+                (Language::Chinese, "服务器登录问题"),
+            ]),
+        );
+
+        translations.insert(
+            "auth_step_public_key",
+            HashMap::from([
+                (Language::English, "Trying public key:"),
+                (Language::Arabic, "تجربة المفتاح العام:"),
+                (Language::French, "Essai de la clé publique :"),
+                (Language::Chinese, "正在尝试公钥："),
+            ]),
+        );
+
+        translations.insert(
+            "auth_step_partial",
+            HashMap::from([
+                (Language::English, "Accepted; the server also requires:"),
+                (Language::Arabic, "تم القبول؛ يتطلب الخادم أيضًا:"),
+                (Language::French, "Accepté ; le serveur exige aussi :"),
+                (Language::Chinese, "已接受；服务器还要求："),
+            ]),
+        );
+
+        translations.insert(
+            "auth_step_keyboard_interactive",
+            HashMap::from([
+                (
+                    Language::English,
+                    "Answering the server's questions (e.g. a one-time code)...",
+                ),
+                (
+                    Language::Arabic,
+                    "الإجابة عن أسئلة الخادم (مثل رمز لمرة واحدة)...",
+                ),
+                (
+                    Language::French,
+                    "Réponse aux questions du serveur (par ex. un code à usage unique)...",
+                ),
+                (
+                    Language::Chinese,
+                    "正在回答服务器的问题（例如一次性验证码）...",
+                ),
+            ]),
+        );
+
+        translations.insert(
+            "auth_step_password",
+            HashMap::from([
+                (Language::English, "Trying the password..."),
+                (Language::Arabic, "تجربة كلمة المرور..."),
+                (Language::French, "Essai du mot de passe..."),
+                (Language::Chinese, "正在尝试密码..."),
+            ]),
+        );
+
+        translations.insert(
+            "properties_button",
+            HashMap::from([
+                (Language::English, "Properties"),
+                (Language::Arabic, "الخصائص"),
+                (Language::French, "Propriétés"),
+                (Language::Chinese, "属性"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_window",
+            HashMap::from([
+                (Language::English, "Properties"),
+                (Language::Arabic, "الخصائص"),
+                (Language::French, "Propriétés"),
+                (Language::Chinese, "属性"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_path",
+            HashMap::from([
+                (Language::English, "Path"),
+                (Language::Arabic, "المسار"),
+                (Language::French, "Chemin"),
+                (Language::Chinese, "路径"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_type",
+            HashMap::from([
+                (Language::English, "Type"),
+                (Language::Arabic, "النوع"),
+                (Language::French, "Type"),
+                (Language::Chinese, "类型"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_link_target",
+            HashMap::from([
+                (Language::English, "Link target"),
+                (Language::Arabic, "هدف الرابط"),
+                (Language::French, "Cible du lien"),
+                (Language::Chinese, "链接目标"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_size",
+            HashMap::from([
+                (Language::English, "Size"),
+                (Language::Arabic, "الحجم"),
+                (Language::French, "Taille"),
+                (Language::Chinese, "大小"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_permissions",
+            HashMap::from([
+                (Language::English, "Permissions"),
+                (Language::Arabic, "الأذونات"),
+                (Language::French, "Permissions"),
+                (Language::Chinese, "权限"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_owner",
+            HashMap::from([
+                (Language::English, "Owner"),
+                (Language::Arabic, "المالك"),
+                (Language::French, "Propriétaire"),
+                (Language::Chinese, "所有者"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_group",
+            HashMap::from([
+                (Language::English, "Group"),
+                (Language::Arabic, "المجموعة"),
+                (Language::French, "Groupe"),
+                (Language::Chinese, "组"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_modified",
+            HashMap::from([
+                (Language::English, "Modified"),
+                (Language::Arabic, "آخر تعديل"),
+                (Language::French, "Modifié"),
+                (Language::Chinese, "修改时间"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_accessed",
+            HashMap::from([
+                (Language::English, "Accessed"),
+                (Language::Arabic, "آخر وصول"),
+                (Language::French, "Dernier accès"),
+                (Language::Chinese, "访问时间"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_directory",
+            HashMap::from([
+                (Language::English, "Directory"),
+                (Language::Arabic, "مجلد"),
+                (Language::French, "Dossier"),
+                (Language::Chinese, "目录"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_file",
+            HashMap::from([
+                (Language::English, "File"),
+                (Language::Arabic, "ملف"),
+                (Language::French, "Fichier"),
+                (Language::Chinese, "文件"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_other",
+            HashMap::from([
+                (Language::English, "Other (device, socket or pipe)"),
+                (Language::Arabic, "أخرى (جهاز أو مقبس أو أنبوب)"),
+                (Language::French, "Autre (périphérique, socket ou tube)"),
+                (Language::Chinese, "其他（设备、套接字或管道）"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_broken_link",
+            HashMap::from([
+                (Language::English, "Broken symbolic link"),
+                (Language::Arabic, "رابط رمزي معطل"),
+                (Language::French, "Lien symbolique cassé"),
+                (Language::Chinese, "失效的符号链接"),
+            ]),
+        );
+
+        translations.insert(
+            "stop_all_button",
+            HashMap::from([
+                (Language::English, "Stop all"),
+                (Language::Arabic, "إيقاف الكل"),
+                (Language::French, "Tout arrêter"),
+                (Language::Chinese, "全部停止"),
+            ]),
+        );
+
+        translations.insert(
+            "stop_all_hint",
+            HashMap::from([
+                (Language::English, "Cancel the running transfer and every waiting one; finished transfers are kept"),
+                (Language::Arabic, "إلغاء النقل الجاري وكل نقل منتظر؛ تبقى عمليات النقل المكتملة"),
+                (Language::French, "Annuler le transfert en cours et tous ceux en attente ; les transferts terminés sont conservés"),
+                (Language::Chinese, "取消正在进行的传输和所有等待中的传输；已完成的传输保留"),
+            ]),
+        );
+
+        translations.insert(
+            "properties_links",
+            HashMap::from([
+                (Language::English, "Hard links"),
+                (Language::Arabic, "الروابط الصلبة"),
+                (Language::French, "Liens physiques"),
+                (Language::Chinese, "硬链接数"),
+            ]),
+        );
+
+        translations.insert(
+            "quick_stats_load",
+            HashMap::from([
+                (Language::English, "Load"),
+                (Language::Arabic, "الحمل"),
+                (Language::French, "Charge"),
+                (Language::Chinese, "负载"),
+            ]),
+        );
+
+        translations.insert(
+            "quick_stats_memory",
+            HashMap::from([
+                (Language::English, "Mem"),
+                (Language::Arabic, "الذاكرة"),
+                (Language::French, "Mém"),
+                (Language::Chinese, "内存"),
+            ]),
+        );
+
+        translations.insert(
+            "quick_stats_disk",
+            HashMap::from([
+                (Language::English, "Disk /"),
+                (Language::Arabic, "القرص /"),
+                (Language::French, "Disque /"),
+                (Language::Chinese, "磁盘 /"),
+            ]),
+        );
+
+        translations.insert(
+            "about_version",
+            HashMap::from([
+                (Language::English, "Version"),
+                (Language::Arabic, "الإصدار"),
+                (Language::French, "Version"),
+                (Language::Chinese, "版本"),
+            ]),
+        );
+
+        translations.insert(
+            "about_ssh_library",
+            HashMap::from([
+                (Language::English, "SSH library"),
+                (Language::Arabic, "مكتبة SSH"),
+                (Language::French, "Bibliothèque SSH"),
+                (Language::Chinese, "SSH 库"),
+            ]),
+        );
+
+        translations.insert(
+            "about_os",
+            HashMap::from([
+                (Language::English, "OS"),
+                (Language::Arabic, "نظام التشغيل"),
+                (Language::French, "Système"),
+                (Language::Chinese, "操作系统"),
+            ]),
+        );
+
+        translations.insert(
+            "about_server",
+            HashMap::from([
+                (Language::English, "Server"),
+                (Language::Arabic, "الخادم"),
+                (Language::French, "Serveur"),
+                (Language::Chinese, "服务器"),
+            ]),
+        );
+
+        translations.insert(
+            "about_server_banner",
+            HashMap::from([
+                (Language::English, "Server banner"),
+                (Language::Arabic, "شعار الخادم"),
+                (Language::French, "Bannière du serveur"),
+                (Language::Chinese, "服务器标识"),
+            ]),
+        );
+
         Localizer { translations }
     }
 
@@ -0,0 +1,144 @@
+use env_logger::{Builder, Target};
+use log::LevelFilter;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The file the log is copied to, if one is chosen
+static FILE: Mutex<Option<File>> = Mutex::new(None);
+
+/// The target of the logger: writes each record to stderr and, when one is
+/// chosen, to the log file, which can change while the program runs
+struct Tee;
+
+impl Write for Tee {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let _ = io::stderr().write_all(buf);
+        if let Some(file) = FILE.lock().unwrap().as_mut() {
+            let _ = file.write_all(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(file) = FILE.lock().unwrap().as_mut() {
+            let _ = file.flush();
+        }
+        Ok(())
+    }
+}
+
+/// Install the logger with the level and log file from the settings.
+///
+/// Returns an error if the log file cannot be opened; logging to stderr works regardless.
+pub fn init(level: LevelFilter, file: &str) -> Result<(), String> {
+    let installed = Builder::new()
+        // Libraries such as the GUI toolkit are very chatty below warnings
+        .filter_level(LevelFilter::Warn)
+        .filter_module(env!("CARGO_CRATE_NAME"), LevelFilter::Trace)
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "{} {:<5} [{}] {}",
+                timestamp(),
+                record.level(),
+                record.target(),
+                record.args()
+            )
+        })
+        .target(Target::Pipe(Box::new(Tee)))
+        .try_init()
+        .is_ok();
+    if installed {
+        log::set_max_level(level);
+    }
+    set_file(file)
+}
+
+/// Change which records are logged
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+/// Append the log to `path` from now on, or only write to stderr if it is empty
+pub fn set_file(path: &str) -> Result<(), String> {
+    let file = if path.is_empty() {
+        None
+    } else {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open log file {}: {}", path, e))?;
+        Some(file)
+    };
+    *FILE.lock().unwrap() = file;
+    Ok(())
+}
+
+/// The current time as `YYYY-MM-DD HH:MM:SS.mmm` in UTC
+pub fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{}.{:03}", format_utc(now.as_secs()), now.subsec_millis())
+}
+
+/// Seconds since the epoch as `YYYY-MM-DD HH:MM:SS` in UTC
+pub fn format_utc(secs: u64) -> String {
+    let (days, time) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_utc_handles_the_epoch_and_year_boundaries() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00");
+        assert_eq!(format_utc(946_684_799), "1999-12-31 23:59:59");
+        assert_eq!(format_utc(946_684_800), "2000-01-01 00:00:00");
+        assert_eq!(format_utc(1_735_689_599), "2024-12-31 23:59:59");
+        assert_eq!(format_utc(1_735_689_600), "2025-01-01 00:00:00");
+    }
+
+    #[test]
+    fn format_utc_handles_leap_days() {
+        // 2000 is a leap year although divisible by 100
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00:00");
+        assert_eq!(format_utc(951_868_800), "2000-03-01 00:00:00");
+        assert_eq!(format_utc(1_709_164_800), "2024-02-29 00:00:00");
+        assert_eq!(format_utc(1_709_251_199), "2024-02-29 23:59:59");
+        assert_eq!(format_utc(1_677_628_800), "2023-03-01 00:00:00");
+        // 2100 is not
+        assert_eq!(format_utc(4_107_456_000), "2100-02-28 00:00:00");
+        assert_eq!(format_utc(4_107_542_400), "2100-03-01 00:00:00");
+    }
+}
@@ -1,15 +1,30 @@
+mod cli;
+mod history;
 mod localization;
+mod logging;
+mod queue;
+mod settings;
 mod ssh;
+mod terminal;
+mod text_encoding;
 mod ui;
 
 use eframe::egui;
-use ssh::SSHConnection;
-use ui::{render_ui, UIState};
+use ssh::{name_from_os, SSHConnection};
+use ui::{render_ui, UIState, APP_TITLE};
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args_os()
+        .skip(1)
+        .map(|arg| name_from_os(&arg))
+        .collect();
+    if cli::is_cli(&args) {
+        std::process::exit(cli::run(&args));
+    }
+
     let options = eframe::NativeOptions::default();
     eframe::run_native(
-        "SSH File Manager",
+        APP_TITLE,
         options,
         Box::new(|_cc| Ok(Box::new(App::default()))),
     )
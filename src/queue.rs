@@ -0,0 +1,339 @@
+use crate::ssh::{SshError, SyncMode};
+
+/// A file or directory transfer waiting in the queue
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transfer {
+    /// Download a remote file to a local path
+    DownloadFile { remote: String, local: String },
+    /// Upload a local file to a remote path
+    UploadFile {
+        local: String,
+        remote: String,
+        /// Create missing directories above `remote` first
+        create_parents: bool,
+    },
+    /// Recursively download a remote directory into a local directory
+    DownloadDirectory {
+        remote: String,
+        local: String,
+        sync: SyncMode,
+        /// Afterwards, offer to delete local files missing from the remote directory
+        mirror: bool,
+    },
+    /// Recursively upload a local directory into a remote directory
+    UploadDirectory {
+        local: String,
+        remote: String,
+        sync: SyncMode,
+        /// Afterwards, offer to delete remote files missing from the local directory
+        mirror: bool,
+        /// Create missing directories above `remote` first
+        create_parents: bool,
+    },
+}
+
+impl Transfer {
+    /// Whether the transfer changes the remote side
+    pub fn is_upload(&self) -> bool {
+        matches!(
+            self,
+            Transfer::UploadFile { .. } | Transfer::UploadDirectory { .. }
+        )
+    }
+
+    /// For an upload, the remote path it writes to
+    pub fn upload_target(&self) -> Option<&str> {
+        match self {
+            Transfer::UploadFile { remote, .. } | Transfer::UploadDirectory { remote, .. } => {
+                Some(remote)
+            }
+            Transfer::DownloadFile { .. } | Transfer::DownloadDirectory { .. } => None,
+        }
+    }
+
+    /// Whether an upload creates the missing directories above its target
+    pub fn creates_parents(&self) -> bool {
+        matches!(
+            self,
+            Transfer::UploadFile {
+                create_parents: true,
+                ..
+            } | Transfer::UploadDirectory {
+                create_parents: true,
+                ..
+            }
+        )
+    }
+
+    /// The same transfer, creating missing directories above the target if it is an upload
+    pub fn with_create_parents(mut self) -> Self {
+        if let Transfer::UploadFile { create_parents, .. }
+        | Transfer::UploadDirectory { create_parents, .. } = &mut self
+        {
+            *create_parents = true;
+        }
+        self
+    }
+
+    /// A one-line description for the queue panel
+    pub fn describe(&self) -> String {
+        match self {
+            Transfer::DownloadFile { remote, local }
+            | Transfer::DownloadDirectory { remote, local, .. } => {
+                format!("↓ {} → {}", remote, local)
+            }
+            Transfer::UploadFile { local, remote, .. }
+            | Transfer::UploadDirectory { local, remote, .. } => {
+                format!("↑ {} → {}", local, remote)
+            }
+        }
+    }
+}
+
+/// Where a queued transfer stands
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferStatus {
+    /// Waiting for its turn
+    Pending,
+    /// Being transferred by the worker
+    Active,
+    /// Finished successfully
+    Done,
+    /// Stopped by the user
+    Cancelled,
+    /// Finished with an error
+    Failed(String),
+    /// Stopped because the connection was lost; retried after reconnecting
+    Interrupted(String),
+}
+
+impl TransferStatus {
+    /// Whether the transfer is over, successfully or not
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, TransferStatus::Pending | TransferStatus::Active)
+    }
+}
+
+/// An entry of the transfer queue
+#[derive(Debug, Clone)]
+pub struct QueuedTransfer {
+    /// Identifies the entry while it moves through the queue
+    pub id: u64,
+    pub transfer: Transfer,
+    pub status: TransferStatus,
+    /// KiB per second this transfer is held to instead of the global limit; 0 for no limit
+    pub speed_limit_kib: Option<u32>,
+}
+
+/// Transfers shared between the UI, which adds them, and the worker, which
+/// runs them one at a time in order.
+#[derive(Debug, Default)]
+pub struct TransferQueue {
+    items: Vec<QueuedTransfer>,
+    next_id: u64,
+    /// First id of the transfers added since the queue was last idle
+    batch_start: u64,
+    /// When set, the worker finishes the active transfer but does not start the next one
+    pub paused: bool,
+}
+
+impl TransferQueue {
+    /// Add a transfer at the end of the queue and return its id
+    pub fn push(&mut self, transfer: Transfer) -> u64 {
+        let id = self.next_id;
+        if !self.is_busy() {
+            self.batch_start = id;
+        }
+        self.next_id += 1;
+        self.items.push(QueuedTransfer {
+            id,
+            transfer,
+            status: TransferStatus::Pending,
+            speed_limit_kib: None,
+        });
+        id
+    }
+
+    /// All entries, in queue order
+    pub fn items(&self) -> &[QueuedTransfer] {
+        &self.items
+    }
+
+    /// Whether some transfers are still waiting
+    pub fn has_pending(&self) -> bool {
+        self.items
+            .iter()
+            .any(|item| item.status == TransferStatus::Pending)
+    }
+
+    /// Whether some transfers are waiting or running
+    pub fn is_busy(&self) -> bool {
+        self.items.iter().any(|item| !item.status.is_finished())
+    }
+
+    /// Progress over the transfers added since the queue was last idle, as
+    /// (finished transfers, total transfers)
+    pub fn batch_progress(&self) -> (usize, usize) {
+        let batch = self.items.iter().filter(|item| item.id >= self.batch_start);
+        let (mut finished, mut total) = (0, 0);
+        for item in batch {
+            total += 1;
+            if item.status.is_finished() {
+                finished += 1;
+            }
+        }
+        (finished, total)
+    }
+
+    /// Whether the worker will keep pulling transfers from the queue
+    pub fn will_continue(&self) -> bool {
+        !self.paused && self.has_pending()
+    }
+
+    /// Mark the next pending transfer as active and return it with its own
+    /// speed limit, unless the queue is paused
+    pub fn start_next(&mut self) -> Option<(u64, Transfer, Option<u32>)> {
+        if self.paused {
+            return None;
+        }
+        let item = self
+            .items
+            .iter_mut()
+            .find(|item| item.status == TransferStatus::Pending)?;
+        item.status = TransferStatus::Active;
+        Some((item.id, item.transfer.clone(), item.speed_limit_kib))
+    }
+
+    /// Move a pending transfer so that it runs just before `target`
+    pub fn move_before(&mut self, id: u64, target: u64) {
+        if id == target {
+            return;
+        }
+        let Some(from) = self
+            .items
+            .iter()
+            .position(|item| item.id == id && item.status == TransferStatus::Pending)
+        else {
+            return;
+        };
+        let item = self.items.remove(from);
+        let to = self
+            .items
+            .iter()
+            .position(|item| item.id == target)
+            .unwrap_or(self.items.len());
+        self.items.insert(to, item);
+    }
+
+    /// Swap a pending transfer with the pending one before it, so that it runs sooner
+    pub fn move_up(&mut self, id: u64) {
+        let pending = self.pending_positions();
+        if let Some(at) = pending.iter().position(|&index| self.items[index].id == id) {
+            if at > 0 {
+                self.items.swap(pending[at - 1], pending[at]);
+            }
+        }
+    }
+
+    /// Swap a pending transfer with the pending one after it, so that it runs later
+    pub fn move_down(&mut self, id: u64) {
+        let pending = self.pending_positions();
+        if let Some(at) = pending.iter().position(|&index| self.items[index].id == id) {
+            if at + 1 < pending.len() {
+                self.items.swap(pending[at], pending[at + 1]);
+            }
+        }
+    }
+
+    /// Indexes of the pending transfers, in queue order
+    fn pending_positions(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.status == TransferStatus::Pending)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Give a transfer that has not started yet its own speed limit, or
+    /// `None` to use the global one
+    pub fn set_speed_limit(&mut self, id: u64, speed_limit_kib: Option<u32>) {
+        if let Some(item) = self
+            .items
+            .iter_mut()
+            .find(|item| item.id == id && item.status == TransferStatus::Pending)
+        {
+            item.speed_limit_kib = speed_limit_kib;
+        }
+    }
+
+    /// Cancel a transfer that has not started yet
+    pub fn cancel_pending(&mut self, id: u64) {
+        if let Some(item) = self
+            .items
+            .iter_mut()
+            .find(|item| item.id == id && item.status == TransferStatus::Pending)
+        {
+            item.status = TransferStatus::Cancelled;
+        }
+    }
+
+    /// Cancel every transfer that has not started yet, leaving the active and
+    /// finished ones alone
+    pub fn cancel_all_pending(&mut self) {
+        for item in &mut self.items {
+            if item.status == TransferStatus::Pending {
+                item.status = TransferStatus::Cancelled;
+            }
+        }
+    }
+
+    /// Whether a transfer is running
+    pub fn has_active(&self) -> bool {
+        self.items
+            .iter()
+            .any(|item| item.status == TransferStatus::Active)
+    }
+
+    /// Remove a finished transfer from the list
+    pub fn remove_finished(&mut self, id: u64) {
+        self.items
+            .retain(|item| item.id != id || !item.status.is_finished());
+    }
+
+    /// Remove every finished transfer from the list
+    pub fn clear_finished(&mut self) {
+        self.items.retain(|item| !item.status.is_finished());
+    }
+
+    /// Put the transfers interrupted by a lost connection back in the queue,
+    /// keeping their place. Returns how many were re-queued.
+    pub fn requeue_interrupted(&mut self) -> usize {
+        let was_busy = self.is_busy();
+        let mut requeued = 0;
+        for item in &mut self.items {
+            if matches!(item.status, TransferStatus::Interrupted(_)) {
+                if !was_busy && requeued == 0 {
+                    self.batch_start = item.id;
+                }
+                item.status = TransferStatus::Pending;
+                requeued += 1;
+            }
+        }
+        requeued
+    }
+
+    /// Record the outcome of a transfer
+    pub fn finish(&mut self, id: u64, result: &Result<(), SshError>) {
+        if let Some(item) = self.items.iter_mut().find(|item| item.id == id) {
+            item.status = match result {
+                Ok(()) => TransferStatus::Done,
+                Err(SshError::Cancelled(..)) => TransferStatus::Cancelled,
+                Err(
+                    e @ (SshError::Network(..) | SshError::Timeout(..) | SshError::NotConnected),
+                ) => TransferStatus::Interrupted(e.to_string()),
+                Err(e) => TransferStatus::Failed(e.to_string()),
+            };
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::ssh::DEFAULT_TRANSFER_RETRIES;
+use directories::ProjectDirs;
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// The file where application settings are stored
+const SETTINGS_FILE: &str = "settings.json";
+
+/// The color theme of the application
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemeMode {
+    Dark,
+    Light,
+    /// Follow the operating system's light/dark appearance
+    System,
+}
+
+/// How the entries of a directory are laid out
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ViewMode {
+    /// One row per entry with its action buttons
+    #[default]
+    List,
+    /// Wrapped tiles with an icon and name
+    Grid,
+}
+
+/// The order of the entries of a directory; directories always come first
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Name,
+    /// Last modification time
+    Modified,
+    Size,
+}
+
+/// How the entries of a directory are shown
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct DirectoryView {
+    pub mode: ViewMode,
+    pub sort: SortKey,
+    pub descending: bool,
+    /// Only entries whose name contains this, ignoring case; all when empty
+    pub filter: String,
+    /// Whether entries whose name starts with a dot are listed
+    pub show_hidden: bool,
+}
+
+impl Default for DirectoryView {
+    fn default() -> Self {
+        Self {
+            mode: ViewMode::default(),
+            sort: SortKey::default(),
+            descending: false,
+            filter: String::new(),
+            show_hidden: true,
+        }
+    }
+}
+
+/// Which actions ask for confirmation first
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Confirmations {
+    /// Deleting a folder with everything in it
+    pub delete_directory: bool,
+    /// Creating, renaming or uploading over existing files
+    pub overwrite: bool,
+    /// Disconnecting or quitting while transfers are running or queued
+    pub exit_with_transfers: bool,
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self {
+            delete_directory: true,
+            overwrite: true,
+            exit_with_transfers: true,
+        }
+    }
+}
+
+/// User preferences that persist between sessions
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Whether long lines are soft-wrapped in the file editor
+    pub editor_wrap: bool,
+    /// Whether Save in the editor stays disabled until the changes were shown in the diff
+    pub review_before_save: bool,
+    /// Seconds a single operation may wait for the server before failing; 0 waits forever
+    pub operation_timeout_secs: u64,
+    /// How often a failed chunk of a transfer is retried before the transfer fails
+    pub transfer_retries: u32,
+    /// Most KiB per second a transfer moves unless it has its own limit; 0 for no limit
+    pub speed_limit_kib: u32,
+    /// Which log records are written
+    pub log_level: LevelFilter,
+    /// A file the log is appended to besides stderr; none when empty
+    pub log_file: String,
+    /// The encoding files are opened with, by its label; detected when `None`
+    pub default_encoding: Option<String>,
+    /// Whether the editor saves through a temporary file renamed over the
+    /// original, instead of overwriting it in place
+    pub atomic_save: bool,
+    /// Files larger than this (in MB) are not loaded fully into the editor
+    pub max_edit_size_mb: u64,
+    /// The chosen color theme
+    pub theme: ThemeMode,
+    /// The layout of the file list in directories without their own view
+    pub view_mode: ViewMode,
+    /// The order of the file list in directories without their own view
+    pub sort: SortKey,
+    pub sort_descending: bool,
+    /// The name filter of the file list in directories without their own view
+    pub filter: String,
+    /// Whether dot files are listed in directories without their own view
+    pub show_hidden: bool,
+    /// Whether each directory keeps the view last chosen in it; otherwise one
+    /// view applies everywhere
+    pub views_per_directory: bool,
+    /// Whether the actions of a row in the list view show only while it is
+    /// hovered, as a small toolbar, instead of always
+    pub hover_actions: bool,
+    /// The view last chosen in each directory, while `views_per_directory` is
+    /// set, keyed by `user@host:port/path`
+    pub directory_views: HashMap<String, DirectoryView>,
+    /// Recently opened directories of each server, most recent first, keyed by `user@host:port`
+    pub recent_dirs: HashMap<String, Vec<String>>,
+    /// Which actions ask before they are carried out
+    pub confirmations: Confirmations,
+    /// Whether recursive deletes and moves first list every path they would
+    /// change, and wait for confirmation
+    pub dry_run: bool,
+    /// Whether navigation follows the resolved path of symlinked directories
+    /// instead of the path that was typed or clicked
+    pub canonical_paths: bool,
+    /// The port of the last successful connection, prefilled in the connect form
+    pub default_port: u16,
+    /// The username of the last successful connection, prefilled in the connect form
+    pub default_username: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            editor_wrap: true,
+            review_before_save: false,
+            operation_timeout_secs: 30,
+            transfer_retries: DEFAULT_TRANSFER_RETRIES,
+            speed_limit_kib: 0,
+            log_level: LevelFilter::Warn,
+            log_file: String::new(),
+            default_encoding: None,
+            atomic_save: true,
+            max_edit_size_mb: 10,
+            theme: ThemeMode::Dark,
+            view_mode: ViewMode::List,
+            sort: SortKey::Name,
+            sort_descending: false,
+            filter: String::new(),
+            show_hidden: true,
+            views_per_directory: true,
+            hover_actions: true,
+            directory_views: HashMap::new(),
+            recent_dirs: HashMap::new(),
+            confirmations: Confirmations::default(),
+            dry_run: false,
+            canonical_paths: false,
+            default_port: 22,
+            default_username: String::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// The view remembered for a directory, or the defaults for directories not
+    /// seen yet and when views are not kept per directory
+    pub fn view_for(&self, key: &str) -> DirectoryView {
+        self.directory_views
+            .get(key)
+            .filter(|_| self.views_per_directory)
+            .cloned()
+            .unwrap_or_else(|| DirectoryView {
+                mode: self.view_mode,
+                sort: self.sort,
+                descending: self.sort_descending,
+                filter: self.filter.clone(),
+                show_hidden: self.show_hidden,
+            })
+    }
+
+    /// Remember the view chosen for a directory; it also becomes the default for new ones
+    pub fn set_view(&mut self, key: String, view: DirectoryView) {
+        self.view_mode = view.mode;
+        self.sort = view.sort;
+        self.sort_descending = view.descending;
+        self.filter = view.filter.clone();
+        self.show_hidden = view.show_hidden;
+        if self.views_per_directory {
+            self.directory_views.insert(key, view);
+        }
+    }
+}
+
+/// The path of a state file such as `settings.json` in the platform's
+/// configuration directory, e.g. `~/.config/ssh-browser` on Linux.
+///
+/// A file of that name in the working directory, where earlier versions kept
+/// it, is moved there the first time. Without a usable configuration
+/// directory, the working directory is used.
+pub fn config_file(name: &str) -> PathBuf {
+    let Some(dirs) = ProjectDirs::from("", "", "ssh-browser") else {
+        return PathBuf::from(name);
+    };
+    let dir = dirs.config_dir();
+    if std::fs::create_dir_all(dir).is_err() {
+        return PathBuf::from(name);
+    }
+    let path = dir.join(name);
+    let legacy = Path::new(name);
+    if !path.exists() && legacy.is_file() {
+        // Renaming fails across filesystems, so fall back to copying
+        if std::fs::rename(legacy, &path).is_err() && std::fs::copy(legacy, &path).is_ok() {
+            let _ = std::fs::remove_file(legacy);
+        }
+    }
+    path
+}
+
+/// Load settings from a JSON file, falling back to defaults
+pub fn load_settings() -> Settings {
+    let path = config_file(SETTINGS_FILE);
+    if path.exists() {
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        Settings::default()
+    }
+}
+
+/// Save settings to a JSON file
+pub fn save_settings(settings: &Settings) {
+    if let Ok(content) = serde_json::to_string_pretty(settings) {
+        let _ = std::fs::write(config_file(SETTINGS_FILE), content);
+    }
+}
@@ -1,18 +1,581 @@
-use ssh2::{OpenFlags, OpenType, Session, Sftp};
+use serde::{Deserialize, Serialize};
+use ssh2::{
+    ErrorCode, FileStat, KeyboardInteractivePrompt, MethodType, OpenFlags, OpenType, Prompt,
+    RenameFlags, Session, Sftp,
+};
 use std::{
-    io::{Read, Write},
-    net::TcpStream,
-    path::Path,
+    borrow::Cow,
+    collections::{HashSet, VecDeque},
+    ffi::OsStr,
+    fmt,
+    io::{self, Read, Seek, SeekFrom, Write},
+    net::{Shutdown, TcpStream, ToSocketAddrs},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+/// Start of the private-use range that carries the bytes of remote names
+/// that are not valid UTF-8, see [`name_from_os`]
+const RAW_BYTE_BASE: u32 = 0x10FF00;
+
+/// How long a streaming command waits before polling for more output
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long an idle terminal waits before polling again; shorter than for
+/// commands so that typing feels immediate
+const TERMINAL_POLL_INTERVAL: Duration = Duration::from_millis(15);
+
+/// How often a connection attempt checks whether it was cancelled
+const CONNECT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The terminal type announced to the server, matching what [`crate::terminal::Screen`] understands
+const TERMINAL_TYPE: &str = "vt100";
+
+/// Permissions used for new directories unless another mode is requested
+pub const DEFAULT_DIRECTORY_MODE: i32 = 0o755;
+
+/// Permissions used for new files unless another mode is requested
+pub const DEFAULT_FILE_MODE: i32 = 0o644;
+
+/// Default number of times a failed chunk of a transfer is retried
+pub const DEFAULT_TRANSFER_RETRIES: u32 = 3;
+
+/// Pause before the first retry of a chunk; it doubles with each further retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest single pause of a throttled transfer, so that cancelling stays prompt
+const THROTTLE_STEP: Duration = Duration::from_millis(100);
+
+/// Most entries kept from one directory listing; huge directories such as
+/// maildirs are cut short rather than exhausting memory
+pub const MAX_LISTING_ENTRIES: usize = 100_000;
+
+// libssh2 session error codes (`LIBSSH2_ERROR_*`).
+const LIBSSH2_ERROR_BANNER_RECV: i32 = -2;
+const LIBSSH2_ERROR_KEX_FAILURE: i32 = -5;
+const LIBSSH2_ERROR_SOCKET_SEND: i32 = -7;
+const LIBSSH2_ERROR_TIMEOUT: i32 = -9;
+const LIBSSH2_ERROR_SOCKET_DISCONNECT: i32 = -13;
+/// Also returned by `readdir` at the end of a directory
+const LIBSSH2_ERROR_FILE: i32 = -16;
+const LIBSSH2_ERROR_AUTHENTICATION_FAILED: i32 = -18;
+const LIBSSH2_ERROR_PUBLICKEY_UNVERIFIED: i32 = -19;
+const LIBSSH2_ERROR_SOCKET_TIMEOUT: i32 = -30;
+const LIBSSH2_ERROR_SOCKET_RECV: i32 = -43;
+
+// SFTP status codes (`LIBSSH2_FX_*`).
+const LIBSSH2_FX_NO_SUCH_FILE: i32 = 2;
+const LIBSSH2_FX_PERMISSION_DENIED: i32 = 3;
+const LIBSSH2_FX_OP_UNSUPPORTED: i32 = 8;
+const LIBSSH2_FX_NO_SUCH_PATH: i32 = 10;
+const LIBSSH2_FX_WRITE_PROTECT: i32 = 12;
+const LIBSSH2_FX_NO_SPACE_ON_FILESYSTEM: i32 = 14;
+const LIBSSH2_FX_QUOTA_EXCEEDED: i32 = 15;
+
+/// Errors returned by [`SSHConnection`] operations.
+///
+/// Each variant carries a human readable message so the UI can keep rendering
+/// errors as plain strings, while still being able to react to the kind of
+/// failure (e.g. returning to the login form on an authentication error).
+/// The second field holds diagnostic details such as the libssh2, SFTP or OS
+/// error code, or what a server command printed, shown apart from the message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SshError {
+    /// Authentication was rejected by the server
+    Auth(String, Option<String>),
+    /// The TCP connection could not be established or was lost
+    Network(String, Option<String>),
+    /// An operation did not complete in time
+    Timeout(String, Option<String>),
+    /// The server refused the operation because of file permissions
+    PermissionDenied(String, Option<String>),
+    /// The file is on storage that cannot be written, such as a read-only mount
+    ReadOnly(String, Option<String>),
+    /// The server has no space left for the data, or the user's quota is used up
+    DiskFull(String, Option<String>),
+    /// The requested file or directory does not exist
+    NotFound(String, Option<String>),
+    /// Any other SFTP/SSH protocol error
+    Sftp(String, Option<String>),
+    /// A local I/O error
+    Io(String, Option<String>),
+    /// The operation was cancelled by the user
+    Cancelled(String, Option<String>),
+    /// The operation needs a connection but there is none
+    NotConnected,
+}
+
+impl SshError {
+    /// Classify an `ssh2` error, prefixing its message with `context`.
+    ///
+    /// The libssh2 or SFTP status code goes to the details, see
+    /// [`SshError::details`].
+    fn from_ssh2(context: &str, e: ssh2::Error) -> Self {
+        let msg = format!("{}: {}", context, e.message());
+        let code = Some(match e.code() {
+            ErrorCode::Session(code) => format!("libssh2 session error code {}", code),
+            ErrorCode::SFTP(code) => format!("SFTP status code {}", code),
+        });
+        match e.code() {
+            ErrorCode::Session(LIBSSH2_ERROR_AUTHENTICATION_FAILED)
+            | ErrorCode::Session(LIBSSH2_ERROR_PUBLICKEY_UNVERIFIED) => SshError::Auth(msg, code),
+            ErrorCode::Session(LIBSSH2_ERROR_TIMEOUT)
+            | ErrorCode::Session(LIBSSH2_ERROR_SOCKET_TIMEOUT) => SshError::Timeout(msg, code),
+            ErrorCode::Session(LIBSSH2_ERROR_BANNER_RECV)
+            | ErrorCode::Session(LIBSSH2_ERROR_KEX_FAILURE)
+            | ErrorCode::Session(LIBSSH2_ERROR_SOCKET_SEND)
+            | ErrorCode::Session(LIBSSH2_ERROR_SOCKET_DISCONNECT)
+            | ErrorCode::Session(LIBSSH2_ERROR_SOCKET_RECV) => SshError::Network(msg, code),
+            ErrorCode::SFTP(LIBSSH2_FX_PERMISSION_DENIED) => SshError::PermissionDenied(msg, code),
+            ErrorCode::SFTP(LIBSSH2_FX_WRITE_PROTECT) => SshError::ReadOnly(msg, code),
+            ErrorCode::SFTP(LIBSSH2_FX_NO_SPACE_ON_FILESYSTEM)
+            | ErrorCode::SFTP(LIBSSH2_FX_QUOTA_EXCEEDED) => SshError::DiskFull(msg, code),
+            ErrorCode::SFTP(LIBSSH2_FX_NO_SUCH_FILE) | ErrorCode::SFTP(LIBSSH2_FX_NO_SUCH_PATH) => {
+                SshError::NotFound(msg, code)
+            }
+            _ => SshError::Sftp(msg, code),
+        }
+    }
+
+    /// Classify a `std::io` error, prefixing its message with `context`.
+    fn from_io(context: &str, e: io::Error) -> Self {
+        let msg = format!("{}: {}", context, e);
+        let code = e
+            .raw_os_error()
+            .map(|code| format!("OS error code {}", code));
+        if is_remote_disk_full(&e) {
+            return SshError::DiskFull(msg, code);
+        }
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => SshError::PermissionDenied(msg, code),
+            io::ErrorKind::ReadOnlyFilesystem => SshError::ReadOnly(msg, code),
+            io::ErrorKind::NotFound => SshError::NotFound(msg, code),
+            io::ErrorKind::TimedOut => SshError::Timeout(msg, code),
+            io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::AddrNotAvailable => SshError::Network(msg, code),
+            _ => SshError::Io(msg, code),
+        }
+    }
+
+    /// Returns the message carried by this error.
+    pub fn message(&self) -> &str {
+        match self {
+            SshError::Auth(msg, _)
+            | SshError::Network(msg, _)
+            | SshError::Timeout(msg, _)
+            | SshError::PermissionDenied(msg, _)
+            | SshError::ReadOnly(msg, _)
+            | SshError::DiskFull(msg, _)
+            | SshError::NotFound(msg, _)
+            | SshError::Sftp(msg, _)
+            | SshError::Io(msg, _)
+            | SshError::Cancelled(msg, _) => msg,
+            SshError::NotConnected => "Not connected",
+        }
+    }
+
+    /// Diagnostic details such as the libssh2, SFTP or OS error code, if any.
+    pub fn details(&self) -> Option<&str> {
+        match self {
+            SshError::Auth(_, details)
+            | SshError::Network(_, details)
+            | SshError::Timeout(_, details)
+            | SshError::PermissionDenied(_, details)
+            | SshError::ReadOnly(_, details)
+            | SshError::DiskFull(_, details)
+            | SshError::NotFound(_, details)
+            | SshError::Sftp(_, details)
+            | SshError::Io(_, details)
+            | SshError::Cancelled(_, details) => details.as_deref(),
+            SshError::NotConnected => None,
+        }
+    }
+
+    /// Prefix the message with `context`, keeping the error kind and details.
+    /// [`SshError::NotConnected`] has no message of its own and stays as it is.
+    pub fn with_context(self, context: &str) -> Self {
+        let wrap = |msg: String| format!("{}: {}", context, msg);
+        match self {
+            SshError::Auth(msg, details) => SshError::Auth(wrap(msg), details),
+            SshError::Network(msg, details) => SshError::Network(wrap(msg), details),
+            SshError::Timeout(msg, details) => SshError::Timeout(wrap(msg), details),
+            SshError::PermissionDenied(msg, details) => {
+                SshError::PermissionDenied(wrap(msg), details)
+            }
+            SshError::ReadOnly(msg, details) => SshError::ReadOnly(wrap(msg), details),
+            SshError::DiskFull(msg, details) => SshError::DiskFull(wrap(msg), details),
+            SshError::NotFound(msg, details) => SshError::NotFound(wrap(msg), details),
+            SshError::Sftp(msg, details) => SshError::Sftp(wrap(msg), details),
+            SshError::Io(msg, details) => SshError::Io(wrap(msg), details),
+            SshError::Cancelled(msg, details) => SshError::Cancelled(wrap(msg), details),
+            SshError::NotConnected => SshError::NotConnected,
+        }
+    }
+}
+
+impl fmt::Display for SshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())?;
+        if let Some(details) = self.details() {
+            write!(f, "\n{}", details)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SshError {}
+
+/// How recursive transfers treat files that already exist at the destination
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SyncMode {
+    /// Transfer every file
+    #[default]
+    Always,
+    /// Skip files whose destination has the same size and is at least as new
+    SkipUnchanged,
+    /// Skip files that exist at the destination
+    SkipExisting,
+}
+
+/// Which side of a folder sync a [`MirrorPlan`] deletes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MirrorSide {
+    Local,
+    Remote,
+}
+
+/// Entries at the destination of a folder sync that no longer exist in its
+/// source, i.e. what mirroring would delete
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirrorPlan {
+    pub side: MirrorSide,
+    /// The destination directory; every entry lies below it
+    pub root: String,
+    /// The entries to delete as (full path, is_dir), parents before their contents
+    pub entries: Vec<(String, bool)>,
+    /// Number of entries at the destination, including the ones to delete
+    pub destination_total: usize,
+}
+
+/// A change a recursive or overwriting operation would make, as listed by a dry run
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlannedChange {
+    Delete(String),
+    Move { from: String, to: String },
+}
+
+/// Progress reported by the recursive directory operations
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecursiveProgress {
+    /// The tree is still being walked to count its items; `current` is the
+    /// directory being read
+    Enumerating { found: usize, current: String },
+    /// Item `done` of `total` is being processed
+    Item {
+        done: usize,
+        total: usize,
+        current: String,
+    },
+}
+
+/// Where a search with [`SSHConnection::find`] stops
+#[derive(Debug, Clone, Copy)]
+pub struct FindLimits {
+    /// Levels below the root to look at; 1 for its entries only
+    pub max_depth: u32,
+    /// The search ends with what it found after this long
+    pub time_limit: Duration,
+    pub max_results: usize,
+}
+
+/// What a content search with [`CommandRunner::grep`] looks for
+#[derive(Debug, Clone, Default)]
+pub struct GrepQuery {
+    /// A basic regular expression, as `grep` reads it
+    pub pattern: String,
+    pub ignore_case: bool,
+    /// Only files whose name matches this glob, e.g. `*.conf`; empty for all
+    pub file_glob: String,
+}
+
+/// A line found by [`CommandRunner::grep`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrepMatch {
+    pub path: String,
+    /// 1-based
+    pub line: usize,
+    pub text: String,
+}
+
+/// Matched lines longer than this are cut, e.g. in minified files
+const MAX_GREP_LINE_CHARS: usize = 300;
+
+/// Private keys in `~/.ssh` offered when the SSH agent has none the server accepts
+const DEFAULT_KEY_FILES: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Public keys offered in one login, from the agent and `~/.ssh` together.
+/// Each refused key counts as a failed attempt, and OpenSSH hangs up after
+/// `MaxAuthTries` (6 by default), which must leave room for the other methods.
+const MAX_OFFERED_KEYS: usize = 3;
+
+/// A step of logging in, reported while connecting
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthStep {
+    /// Offering a public key, from the SSH agent (by its comment) or a key file
+    PublicKey(String),
+    /// A method succeeded, but the server requires these ones as well
+    Partial(String),
+    /// Answering the server's questions, e.g. for a one-time code
+    KeyboardInteractive,
+    Password,
+}
+
+/// What the server asks during keyboard-interactive authentication
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthQuestions {
+    pub instructions: String,
+    /// Each question and whether its answer may be shown while typed
+    pub prompts: Vec<(String, bool)>,
+}
+
+/// Answers keyboard-interactive questions: a lone password prompt with the
+/// login password, anything else through `answer`
+struct InteractivePrompter<'a> {
+    password: &'a str,
+    password_used: bool,
+    answer: &'a mut dyn FnMut(&AuthQuestions) -> Option<Vec<String>>,
+    /// Set when `answer` declined to answer
+    cancelled: bool,
+}
+
+impl KeyboardInteractivePrompt for InteractivePrompter<'_> {
+    fn prompt<'b>(
+        &mut self,
+        _username: &str,
+        instructions: &str,
+        prompts: &[Prompt<'b>],
+    ) -> Vec<String> {
+        if let [prompt] = prompts {
+            if !prompt.echo
+                && !self.password_used
+                && !self.password.is_empty()
+                && prompt.text.to_lowercase().contains("password")
+            {
+                self.password_used = true;
+                return vec![self.password.to_string()];
+            }
+        }
+        let questions = AuthQuestions {
+            instructions: instructions.trim().to_string(),
+            prompts: prompts
+                .iter()
+                .map(|prompt| (prompt.text.trim().to_string(), prompt.echo))
+                .collect(),
+        };
+        (self.answer)(&questions).unwrap_or_else(|| {
+            self.cancelled = true;
+            Vec::new()
+        })
+    }
+}
+
 /// Manages SSH and SFTP connections.
 pub struct SSHConnection {
     hostname: String,
     username: String,
     password: String,
     port: u16,
+    algorithms: AlgorithmPrefs,
+    /// A private key file offered before any other, see [`Self::set_identity`]
+    identity: Option<PathBuf>,
+    /// When set, changes run through `sudo` with this password instead of SFTP
+    sudo_password: Option<String>,
+    /// How long a single blocking call may wait for the server, see [`Self::set_timeout`]
+    timeout: Option<Duration>,
+    /// How often a chunk of a transfer is retried, see [`Self::set_transfer_retries`]
+    transfer_retries: u32,
+    /// Most bytes per second a transfer moves, see [`Self::set_speed_limit`]
+    speed_limit: u64,
     session: Option<Session>,
     sftp: Option<Sftp>,
+    /// The server's operating system, detected on connect
+    remote_os: RemoteOs,
+}
+
+/// Preferred algorithms offered during the handshake, as comma-separated lists
+/// (e.g. `diffie-hellman-group14-sha1,diffie-hellman-group1-sha1`).
+///
+/// Older servers and network equipment sometimes only support algorithms that
+/// libssh2 does not offer by default. A blank field keeps libssh2's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlgorithmPrefs {
+    /// Key exchange algorithms
+    pub kex: String,
+    /// Host key algorithms
+    pub host_key: String,
+    /// Ciphers, used in both directions
+    pub ciphers: String,
+    /// MAC algorithms, used in both directions
+    pub macs: String,
+}
+
+impl AlgorithmPrefs {
+    /// Apply the non-blank preferences to a session that has not done its handshake yet.
+    fn apply(&self, session: &Session) -> Result<(), SshError> {
+        let prefs = [
+            (MethodType::Kex, &self.kex),
+            (MethodType::HostKey, &self.host_key),
+            (MethodType::CryptCs, &self.ciphers),
+            (MethodType::CryptSc, &self.ciphers),
+            (MethodType::MacCs, &self.macs),
+            (MethodType::MacSc, &self.macs),
+        ];
+        for (method, list) in prefs {
+            let list = list.trim();
+            if !list.is_empty() {
+                session.method_pref(method, list).map_err(|e| {
+                    SshError::from_ssh2(&format!("Invalid algorithm list \"{}\"", list), e)
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single entry of a remote directory listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileEntry {
+    /// The file name, without its directory
+    pub name: String,
+    /// Whether the entry is a directory
+    pub is_dir: bool,
+    /// The size in bytes
+    pub size: u64,
+    /// The last modification time as a Unix timestamp, if known
+    pub mtime: Option<u64>,
+}
+
+/// The operating system of the server, from `uname -s`, which decides how
+/// [`SSHConnection::fetch_stats`] reads CPU and memory use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RemoteOs {
+    Linux,
+    MacOs,
+    /// FreeBSD, OpenBSD, NetBSD or DragonFly
+    Bsd,
+    /// Not detected, or none of the above
+    #[default]
+    Unknown,
+}
+
+impl RemoteOs {
+    /// Classify the output of `uname -s`
+    fn from_uname(name: &str) -> Self {
+        match name.trim() {
+            "Linux" => RemoteOs::Linux,
+            "Darwin" => RemoteOs::MacOs,
+            "FreeBSD" | "OpenBSD" | "NetBSD" | "DragonFly" => RemoteOs::Bsd,
+            _ => RemoteOs::Unknown,
+        }
+    }
+}
+
+/// What was agreed with the server for the current session
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    /// The identification string the server sent, e.g. `SSH-2.0-OpenSSH_9.6`
+    pub banner: Option<String>,
+    /// Negotiated algorithms as (purpose, algorithm)
+    pub methods: Vec<(&'static str, String)>,
+}
+
+/// What `stat` tells about a file's content, to notice when it changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileVersion {
+    pub size: u64,
+    /// Modification time in seconds since the epoch, if the server reports it
+    pub mtime: Option<u64>,
+}
+
+/// Everything `stat` tells about a remote path, for the properties dialog
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileProperties {
+    pub path: String,
+    /// What the path names, following a symbolic link; `None` for a broken link
+    pub kind: Option<EntryKind>,
+    pub size: Option<u64>,
+    /// Permission and file type bits, as in `st_mode`
+    pub perm: Option<u32>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    /// The owner's and group's names, if `ls` could be run to look them up
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    /// How many hard links the entry has, also from `ls`
+    pub links: Option<u64>,
+    /// Modification and access times in seconds since the epoch
+    pub mtime: Option<u64>,
+    pub atime: Option<u64>,
+    /// Where a symbolic link points, as stored in the link
+    pub link_target: Option<String>,
+}
+
+/// What kind of entry a remote path names, following symbolic links
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    /// A device, socket, pipe or other special file
+    Other,
+}
+
+/// Where [`SSHConnection::sudo_write`] puts the content in the target file
+#[derive(Debug, Clone, Copy)]
+enum WritePosition {
+    Replace,
+    Append,
+    /// Over the bytes from this offset on
+    At(u64),
+}
+
+/// How a directory listing was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListingSource {
+    /// Listed through SFTP `readdir`
+    Sftp,
+    /// Listed by parsing `ls -la` over a shell channel because `readdir` was denied
+    ShellFallback,
+}
+
+/// The contents of a remote directory
+#[derive(Debug, Clone)]
+pub struct DirectoryListing {
+    pub entries: Vec<FileEntry>,
+    pub source: ListingSource,
+    /// The directory with symbolic links and `..` resolved, if the server could tell
+    pub canonical_path: Option<String>,
+    /// Whether the directory has more than [`MAX_LISTING_ENTRIES`] entries and
+    /// only the first ones were kept
+    pub truncated: bool,
+}
+
+/// The result of running a command on the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// The exit status reported by the server
+    pub exit_code: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -20,7 +583,290 @@ pub struct ServerStats {
     pub cpu_usage: String,
     pub memory_usage: String,
     pub disk_usage: String,
+    /// CPU busy percentage (100 - idle), if it could be parsed
+    pub cpu_percent: Option<f32>,
+    /// Used memory as a percentage of total memory, if it could be parsed
+    pub memory_percent: Option<f32>,
+}
+/// Which output stream of a command some text came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// What is needed to open sessions of its own for long-running commands, so
+/// that they neither block nor are blocked by file operations
+#[derive(Debug, Clone)]
+pub struct CommandRunner {
+    hostname: String,
+    username: String,
+    password: String,
+    port: u16,
+    algorithms: AlgorithmPrefs,
+    identity: Option<PathBuf>,
+    timeout: Option<Duration>,
+}
+
+impl CommandRunner {
+    /// Run `cmd` on a new session, passing its output to `output` as it arrives.
+    ///
+    /// Returns the exit status once the command ends. Setting `cancel` closes
+    /// the channel and the session; the server then hangs up on the command,
+    /// which stops it the next time it writes output.
+    pub fn run(
+        &self,
+        cmd: &str,
+        cancel: &AtomicBool,
+        output: &mut dyn FnMut(OutputStream, String),
+    ) -> Result<i32, SshError> {
+        let session = self.open_session()?;
+        let mut channel = session
+            .channel_session()
+            .map_err(|e| SshError::from_ssh2("Failed to open channel", e))?;
+        channel
+            .exec(cmd)
+            .map_err(|e| SshError::from_ssh2(&format!("Failed to exec command {}", cmd), e))?;
+
+        // Poll both streams without blocking so that `cancel` is noticed promptly
+        session.set_blocking(false);
+        let mut buffer = [0u8; 8192];
+        let mut pending = [Vec::new(), Vec::new()];
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                session.set_blocking(true);
+                let _ = channel.close();
+                return Err(SshError::Cancelled("Command stopped".to_string(), None));
+            }
+            let mut received = false;
+            for (index, stream) in [OutputStream::Stdout, OutputStream::Stderr]
+                .into_iter()
+                .enumerate()
+            {
+                let read = match stream {
+                    OutputStream::Stdout => channel.read(&mut buffer),
+                    OutputStream::Stderr => channel.stderr().read(&mut buffer),
+                };
+                match read {
+                    Ok(0) => {}
+                    Ok(n) => {
+                        received = true;
+                        pending[index].extend_from_slice(&buffer[..n]);
+                        let text = take_complete_utf8(&mut pending[index]);
+                        if !text.is_empty() {
+                            output(stream, text);
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                    Err(e) => return Err(SshError::from_io("Failed to read command output", e)),
+                }
+            }
+            if !received {
+                if channel.eof() {
+                    break;
+                }
+                std::thread::sleep(COMMAND_POLL_INTERVAL);
+            }
+        }
+        session.set_blocking(true);
+        for (index, stream) in [OutputStream::Stdout, OutputStream::Stderr]
+            .into_iter()
+            .enumerate()
+        {
+            if !pending[index].is_empty() {
+                output(
+                    stream,
+                    String::from_utf8_lossy(&pending[index]).into_owned(),
+                );
+            }
+        }
+
+        channel
+            .wait_close()
+            .map_err(|e| SshError::from_ssh2("Failed to close channel", e))?;
+        channel
+            .exit_status()
+            .map_err(|e| SshError::from_ssh2("Failed to read exit status", e))
+    }
+
+    /// Search the contents of the files below `root` with `grep -rn` on the
+    /// server, passing each matching line to `found` as it arrives.
+    ///
+    /// At most `max_results` lines are reported, and binary files are skipped.
+    /// A server without `grep` gives [`SshError::NotFound`] rather than an
+    /// empty result.
+    pub fn grep(
+        &self,
+        root: &str,
+        query: &GrepQuery,
+        max_results: usize,
+        cancel: &AtomicBool,
+        found: &mut dyn FnMut(GrepMatch),
+    ) -> Result<(), SshError> {
+        let mut options = String::from("-rnI --null");
+        if query.ignore_case {
+            options.push_str(" -i");
+        }
+        if !query.file_glob.is_empty() {
+            options.push_str(&format!(" --include={}", shell_quote(&query.file_glob)));
+        }
+        // Unreadable files are common below a shared root and not worth
+        // reporting; a missing grep is, hence the explicit check
+        let command = format!(
+            "command -v grep >/dev/null 2>&1 || exit 127; \
+             grep {} -e {} -- {} 2>/dev/null | head -n {}",
+            options,
+            shell_quote(&query.pattern),
+            shell_quote(root),
+            max_results
+        );
+        let mut pending = String::new();
+        let status = self.run(&command, cancel, &mut |stream, text| {
+            if stream != OutputStream::Stdout {
+                return;
+            }
+            pending.push_str(&text);
+            while let Some(end) = pending.find('\n') {
+                let line: String = pending.drain(..=end).collect();
+                if let Some(m) = parse_grep_line(&line[..line.len() - 1]) {
+                    found(m);
+                }
+            }
+        })?;
+        if let Some(m) = parse_grep_line(&pending) {
+            found(m);
+        }
+        if status == 127 {
+            return Err(SshError::NotFound(
+                "grep is not available on the server".to_string(),
+                None,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Connect a new session with the credentials of the connection
+    fn open_session(&self) -> Result<Session, SshError> {
+        let mut conn =
+            SSHConnection::new(&self.hostname, &self.username, &self.password, self.port);
+        conn.set_algorithm_prefs(self.algorithms.clone());
+        conn.set_identity(self.identity.clone());
+        conn.set_timeout(self.timeout);
+        conn.connect()?;
+        conn.session.take().ok_or(SshError::NotConnected)
+    }
+
+    /// Run an interactive login shell in a pseudo-terminal of `cols` x `rows`
+    /// on a new session, passing its output to `output` as it arrives.
+    ///
+    /// Keystrokes and size changes are read from `input`. The shell ends when
+    /// it exits, returning its exit status, or when the sender of `input` is
+    /// dropped, returning [`SshError::Cancelled`].
+    pub fn shell(
+        &self,
+        cols: u32,
+        rows: u32,
+        input: &Receiver<TerminalInput>,
+        output: &mut dyn FnMut(String),
+    ) -> Result<i32, SshError> {
+        let session = self.open_session()?;
+        let mut channel = session
+            .channel_session()
+            .map_err(|e| SshError::from_ssh2("Failed to open channel", e))?;
+        channel
+            .request_pty(TERMINAL_TYPE, None, Some((cols, rows, 0, 0)))
+            .map_err(|e| SshError::from_ssh2("Failed to request a terminal", e))?;
+        channel
+            .shell()
+            .map_err(|e| SshError::from_ssh2("Failed to start a shell", e))?;
+
+        session.set_blocking(false);
+        let mut buffer = [0u8; 8192];
+        let mut pending_output = Vec::new();
+        let mut pending_input: Vec<u8> = Vec::new();
+        loop {
+            loop {
+                match input.try_recv() {
+                    Ok(TerminalInput::Data(data)) => pending_input.extend_from_slice(&data),
+                    Ok(TerminalInput::Resize(cols, rows)) => {
+                        session.set_blocking(true);
+                        let resized = channel.request_pty_size(cols, rows, None, None);
+                        session.set_blocking(false);
+                        resized
+                            .map_err(|e| SshError::from_ssh2("Failed to resize the terminal", e))?;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        session.set_blocking(true);
+                        let _ = channel.close();
+                        return Err(SshError::Cancelled("Terminal closed".to_string(), None));
+                    }
+                }
+            }
+            let mut busy = false;
+            if !pending_input.is_empty() {
+                match channel.write(&pending_input) {
+                    Ok(n) => {
+                        busy = true;
+                        pending_input.drain(..n);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                    Err(e) => return Err(SshError::from_io("Failed to send input", e)),
+                }
+            }
+            match channel.read(&mut buffer) {
+                Ok(0) => {}
+                Ok(n) => {
+                    busy = true;
+                    pending_output.extend_from_slice(&buffer[..n]);
+                    let text = take_complete_utf8(&mut pending_output);
+                    if !text.is_empty() {
+                        output(text);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(SshError::from_io("Failed to read terminal output", e)),
+            }
+            if !busy {
+                if channel.eof() {
+                    break;
+                }
+                std::thread::sleep(TERMINAL_POLL_INTERVAL);
+            }
+        }
+        session.set_blocking(true);
+        channel
+            .wait_close()
+            .map_err(|e| SshError::from_ssh2("Failed to close channel", e))?;
+        channel
+            .exit_status()
+            .map_err(|e| SshError::from_ssh2("Failed to read exit status", e))
+    }
+}
+
+/// Input to an interactive shell started with [`CommandRunner::shell`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminalInput {
+    /// Bytes typed or pasted into the terminal
+    Data(Vec<u8>),
+    /// The terminal now has this many (columns, rows)
+    Resize(u32, u32),
+}
+
+/// A light snapshot of server health for the header badges.
+/// Each value is `None` when the server's output could not be parsed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuickStats {
+    /// Load averages over 1, 5 and 15 minutes
+    pub load: Option<[f32; 3]>,
+    /// Number of CPUs, to judge the load against
+    pub cpus: Option<u32>,
+    /// Used and total memory in bytes
+    pub memory: Option<(u64, u64)>,
+    /// Used and total space of the root filesystem in bytes
+    pub disk: Option<(u64, u64)>,
 }
+
 impl SSHConnection {
     pub fn new(hostname: &str, username: &str, password: &str, port: u16) -> Self {
         Self {
@@ -28,259 +874,2980 @@ impl SSHConnection {
             username: username.to_string(),
             password: password.to_string(),
             port,
+            algorithms: AlgorithmPrefs::default(),
+            identity: None,
+            sudo_password: None,
+            timeout: None,
+            transfer_retries: DEFAULT_TRANSFER_RETRIES,
+            speed_limit: 0,
             session: None,
             sftp: None,
+            remote_os: RemoteOs::Unknown,
+        }
+    }
+
+    /// Set the algorithms to prefer during the handshake of the next `connect`.
+    pub fn set_algorithm_prefs(&mut self, algorithms: AlgorithmPrefs) {
+        self.algorithms = algorithms;
+    }
+
+    /// Offer the private key in `path` on the next `connect`, before the keys
+    /// of the SSH agent and of `~/.ssh`. An encrypted key is opened with the
+    /// login password.
+    pub fn set_identity(&mut self, path: Option<PathBuf>) {
+        self.identity = path;
+    }
+
+    /// Run writes, deletions, renames and file or directory creation through
+    /// `sudo` on a shell channel instead of SFTP, or go back to SFTP with `None`.
+    ///
+    /// A blank password answers the sudo prompt with the login password.
+    pub fn set_sudo(&mut self, password: Option<&str>) {
+        self.sudo_password = password.map(|password| {
+            if password.is_empty() {
+                self.password.clone()
+            } else {
+                password.to_string()
+            }
+        });
+    }
+
+    /// The server of this connection, as `user@host:port`
+    pub fn target(&self) -> String {
+        format!("{}@{}:{}", self.username, self.hostname, self.port)
+    }
+
+    /// The credentials of this connection, to run long commands on sessions of their own
+    pub fn command_runner(&self) -> CommandRunner {
+        CommandRunner {
+            hostname: self.hostname.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+            port: self.port,
+            algorithms: self.algorithms.clone(),
+            identity: self.identity.clone(),
+            timeout: self.timeout,
+        }
+    }
+
+    /// Limit how long any single blocking call (connecting, reading a
+    /// directory, a chunk of a transfer, ...) waits for the server, so a server
+    /// that stops answering fails the operation with [`SshError::Timeout`]
+    /// instead of blocking forever. `None` waits indefinitely.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+        if let Some(session) = &self.session {
+            session.set_timeout(timeout_millis(timeout));
+        }
+    }
+
+    /// Retry a chunk of a transfer that failed with a transient error (a
+    /// timeout or a generic SFTP failure) up to `retries` times, pausing
+    /// longer before each retry. 0 fails on the first error.
+    pub fn set_transfer_retries(&mut self, retries: u32) {
+        self.transfer_retries = retries;
+    }
+
+    /// Hold file transfers to at most `bytes_per_sec` bytes per second, by
+    /// pausing between chunks. 0 transfers as fast as the connection allows.
+    pub fn set_speed_limit(&mut self, bytes_per_sec: u64) {
+        self.speed_limit = bytes_per_sec;
+    }
+
+    /// Run one chunk of a transfer, retrying transient failures as set by
+    /// [`Self::set_transfer_retries`]. `chunk` gets the number of the attempt,
+    /// 0 for the first, and must seek back to where the chunk starts on retries.
+    fn retry_chunk<T>(
+        &self,
+        cancel: &AtomicBool,
+        mut chunk: impl FnMut(u32) -> io::Result<T>,
+    ) -> io::Result<T> {
+        let mut attempt = 0;
+        loop {
+            match chunk(attempt) {
+                Err(e)
+                    if attempt < self.transfer_retries
+                        && is_transient(&e)
+                        && !cancel.load(Ordering::Relaxed) =>
+                {
+                    let pause = RETRY_BASE_DELAY * 2u32.pow(attempt.min(5));
+                    attempt += 1;
+                    log::warn!(
+                        "Transfer chunk failed: {}; retry {} of {} in {:?}",
+                        e,
+                        attempt,
+                        self.transfer_retries,
+                        pause
+                    );
+                    thread::sleep(pause);
+                }
+                result => return result,
+            }
         }
     }
 
-    pub fn connect(&mut self) -> Result<(), String> {
+    /// Whether changes currently run through `sudo`
+    pub fn is_elevated(&self) -> bool {
+        self.sudo_password.is_some()
+    }
+
+    /// Connect without asking anything: questions other than for the password fail the login
+    pub fn connect(&mut self) -> Result<(), SshError> {
+        self.connect_cancellable(&AtomicBool::new(false), &mut |_| {}, &mut |_| None)
+    }
+
+    /// Like [`Self::connect`], but give up with [`SshError::Cancelled`] as
+    /// soon as `cancel` is set. Looking up the host and opening the TCP
+    /// connection run on a thread of their own that is abandoned on cancel;
+    /// the handshake and authentication are interrupted by shutting the socket.
+    ///
+    /// `step` is told each authentication attempt, and `answer` is asked the
+    /// server's questions, such as for a one-time code; `None` cancels.
+    pub fn connect_cancellable(
+        &mut self,
+        cancel: &AtomicBool,
+        step: &mut dyn FnMut(AuthStep),
+        answer: &mut dyn FnMut(&AuthQuestions) -> Option<Vec<String>>,
+    ) -> Result<(), SshError> {
         let addr = format!("{}:{}", self.hostname, self.port);
-        let tcp = TcpStream::connect(addr).map_err(|e| format!("Connection error: {}", e))?;
-        let mut session = Session::new().map_err(|e| format!("Session creation error: {}", e))?;
+        log::info!("Connecting to {}@{}", self.username, addr);
+        let cancelled = || SshError::Cancelled(format!("Connection to {} cancelled", addr), None);
+
+        let (sender, receiver) = mpsc::channel();
+        let timeout = self.timeout;
+        let target = addr.clone();
+        thread::spawn(move || {
+            let tcp = match timeout {
+                Some(timeout) => connect_with_timeout(&target, timeout),
+                None => TcpStream::connect(&target),
+            };
+            let _ = sender.send(tcp);
+        });
+        let tcp = loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(cancelled());
+            }
+            match receiver.recv_timeout(CONNECT_POLL_INTERVAL) {
+                Ok(tcp) => break tcp.map_err(|e| SshError::from_io("Connection error", e))?,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(SshError::Network("Connection error".to_string(), None))
+                }
+            }
+        };
+
+        let socket = tcp
+            .try_clone()
+            .map_err(|e| SshError::from_io("Connection error", e))?;
+        let finished = AtomicBool::new(false);
+        let result = thread::scope(|scope| {
+            scope.spawn(|| {
+                while !finished.load(Ordering::Relaxed) {
+                    if cancel.load(Ordering::Relaxed) {
+                        let _ = socket.shutdown(Shutdown::Both);
+                        return;
+                    }
+                    thread::sleep(CONNECT_POLL_INTERVAL);
+                }
+            });
+            let result = self.start_session(tcp, &addr, step, answer);
+            finished.store(true, Ordering::Relaxed);
+            result
+        });
+        if cancel.load(Ordering::Relaxed) {
+            self.disconnect();
+            return Err(cancelled());
+        }
+        result
+    }
+
+    /// Handshake, authenticate and open SFTP over a connected socket
+    fn start_session(
+        &mut self,
+        tcp: TcpStream,
+        addr: &str,
+        step: &mut dyn FnMut(AuthStep),
+        answer: &mut dyn FnMut(&AuthQuestions) -> Option<Vec<String>>,
+    ) -> Result<(), SshError> {
+        let mut session =
+            Session::new().map_err(|e| SshError::from_ssh2("Session creation error", e))?;
+        session.set_timeout(timeout_millis(self.timeout));
         session.set_tcp_stream(tcp);
+        self.algorithms.apply(&session)?;
         session
             .handshake()
-            .map_err(|e| format!("Handshake error: {}", e))?;
-        session
-            .userauth_password(&self.username, &self.password)
-            .map_err(|e| format!("Authentication error: {}", e))?;
-
-        if !session.authenticated() {
-            return Err("Authentication failed. Check your username and password.".to_string());
-        }
+            .map_err(|e| SshError::from_ssh2("Handshake error", e))?;
+        log::debug!(
+            "Handshake done: kex {}, host key {}, cipher {}, MAC {}",
+            session.methods(MethodType::Kex).unwrap_or("?"),
+            session.methods(MethodType::HostKey).unwrap_or("?"),
+            session.methods(MethodType::CryptCs).unwrap_or("?"),
+            session.methods(MethodType::MacCs).unwrap_or("?")
+        );
+        self.authenticate(&session, step, answer)?;
+        log::debug!("Authenticated as {}", self.username);
 
         let sftp = session
             .sftp()
-            .map_err(|e| format!("SFTP initialization error: {}", e))?;
+            .map_err(|e| SshError::from_ssh2("SFTP initialization error", e))?;
+        log::info!("Connected to {}", addr);
+        // Only needed for statistics: servers without a shell connect all the same
+        self.remote_os = Self::run_command(&session, "uname -s")
+            .map(|output| RemoteOs::from_uname(&output.stdout))
+            .unwrap_or_default();
+        log::debug!("Server OS: {:?}", self.remote_os);
         self.session = Some(session);
         self.sftp = Some(sftp);
 
         Ok(())
     }
 
+    /// Log in with the methods the server offers, in turn: public keys from
+    /// the SSH agent and `~/.ssh`, keyboard-interactive, then the password.
+    ///
+    /// A server may require several in a row, e.g. a key and then a one-time
+    /// code; after each method that did not log in, the server is asked
+    /// which methods it still wants, and a changed list means the method was
+    /// accepted as one of several.
+    fn authenticate(
+        &self,
+        session: &Session,
+        step: &mut dyn FnMut(AuthStep),
+        answer: &mut dyn FnMut(&AuthQuestions) -> Option<Vec<String>>,
+    ) -> Result<(), SshError> {
+        let username = self.username.as_str();
+        let mut methods = match session.auth_methods(username) {
+            Ok(methods) => methods.to_string(),
+            // The server let us in without any authentication
+            Err(_) if session.authenticated() => return Ok(()),
+            Err(e) => return Err(SshError::from_ssh2("Authentication error", e)),
+        };
+        log::debug!("Server offers authentication methods: {}", methods);
+        // With a password at hand, a server that takes it on its own logs in
+        // before any key can use up its attempts
+        let order = if self.password.is_empty() {
+            ["publickey", "keyboard-interactive", "password"]
+        } else {
+            ["password", "keyboard-interactive", "publickey"]
+        };
+        let mut tried = Vec::new();
+        let mut hung_up = false;
+        while !session.authenticated() {
+            let offered = |method: &str| methods.split(',').any(|offer| offer == method);
+            let Some(method) = order
+                .into_iter()
+                .find(|method| offered(method) && !tried.contains(method))
+            else {
+                break;
+            };
+            tried.push(method);
+            let result = match method {
+                "publickey" => self.authenticate_with_keys(session, step),
+                "keyboard-interactive" => {
+                    log::info!("Trying keyboard-interactive authentication");
+                    step(AuthStep::KeyboardInteractive);
+                    let mut prompter = InteractivePrompter {
+                        password: &self.password,
+                        password_used: false,
+                        answer: &mut *answer,
+                        cancelled: false,
+                    };
+                    let result = session.userauth_keyboard_interactive(username, &mut prompter);
+                    if prompter.cancelled {
+                        return Err(SshError::Cancelled(
+                            "Authentication cancelled".to_string(),
+                            None,
+                        ));
+                    }
+                    result
+                }
+                _ => {
+                    log::info!("Trying password authentication");
+                    step(AuthStep::Password);
+                    session.userauth_password(username, &self.password)
+                }
+            };
+            if session.authenticated() {
+                break;
+            }
+            if let Err(e) = result {
+                log::debug!("{} authentication failed: {}", method, e);
+            }
+            // The server hangs up after too many failures
+            let Ok(remaining) = session.auth_methods(username) else {
+                hung_up = true;
+                break;
+            };
+            if remaining != methods {
+                log::info!(
+                    "{} accepted, the server also requires {}",
+                    method,
+                    remaining
+                );
+                step(AuthStep::Partial(remaining.to_string()));
+                methods = remaining.to_string();
+                tried.clear();
+                tried.push(method);
+            }
+        }
+        if session.authenticated() {
+            return Ok(());
+        }
+        let tried = if tried.is_empty() {
+            "nothing".to_string()
+        } else {
+            tried.join(", ")
+        };
+        if hung_up {
+            return Err(SshError::Auth(
+                format!(
+                    "Too many authentication failures: the server closed the connection \
+                 (tried {}). Check your username and password, or remove keys \
+                 from the SSH agent that this server does not accept.",
+                    tried
+                ),
+                None,
+            ));
+        }
+        Err(SshError::Auth(
+            format!(
+                "Authentication failed (tried {}; the server accepts {}). \
+             Check your username and password.",
+                tried, methods
+            ),
+            None,
+        ))
+    }
+
+    /// Offer the identity file if one is set, the keys of the SSH agent, then
+    /// the usual key files of `~/.ssh`, until one is accepted or
+    /// [`MAX_OFFERED_KEYS`] were refused. Encrypted key files are opened with
+    /// the login password.
+    fn authenticate_with_keys(
+        &self,
+        session: &Session,
+        step: &mut dyn FnMut(AuthStep),
+    ) -> Result<(), ssh2::Error> {
+        let username = self.username.as_str();
+        let mut result = Err(ssh2::Error::from_errno(ErrorCode::Session(
+            LIBSSH2_ERROR_AUTHENTICATION_FAILED,
+        )));
+        // A refused signature may be a partial success: the caller asks the server
+        let done = |result: &Result<(), ssh2::Error>| {
+            session.authenticated()
+                || matches!(result, Err(e)
+                    if e.code() == ErrorCode::Session(LIBSSH2_ERROR_PUBLICKEY_UNVERIFIED))
+        };
+        let mut offered = 0;
+        let passphrase = Some(self.password.as_str()).filter(|password| !password.is_empty());
+
+        if let Some(key) = &self.identity {
+            offered += 1;
+            log::info!("Offering public key {}", key.display());
+            step(AuthStep::PublicKey(key.display().to_string()));
+            result = session.userauth_pubkey_file(username, None, key, passphrase);
+            if done(&result) {
+                return result;
+            }
+        }
+
+        if let Ok(mut agent) = session.agent() {
+            if agent.connect().is_ok() && agent.list_identities().is_ok() {
+                for identity in agent.identities().unwrap_or_default() {
+                    if offered == MAX_OFFERED_KEYS {
+                        log::info!("Not offering more than {} keys", MAX_OFFERED_KEYS);
+                        break;
+                    }
+                    offered += 1;
+                    log::info!("Offering public key {} from the agent", identity.comment());
+                    step(AuthStep::PublicKey(identity.comment().to_string()));
+                    result = agent.userauth(username, &identity);
+                    if done(&result) {
+                        break;
+                    }
+                }
+                let _ = agent.disconnect();
+                if done(&result) {
+                    return result;
+                }
+            }
+        }
+
+        let Some(ssh_dir) = directories::BaseDirs::new().map(|dirs| dirs.home_dir().join(".ssh"))
+        else {
+            return result;
+        };
+        for name in DEFAULT_KEY_FILES {
+            let key = ssh_dir.join(name);
+            if !key.is_file() || self.identity.as_ref() == Some(&key) {
+                continue;
+            }
+            if offered == MAX_OFFERED_KEYS {
+                log::info!("Not offering more than {} keys", MAX_OFFERED_KEYS);
+                break;
+            }
+            offered += 1;
+            log::info!("Offering public key {}", key.display());
+            step(AuthStep::PublicKey(key.display().to_string()));
+            result = session.userauth_pubkey_file(username, None, &key, passphrase);
+            if done(&result) {
+                break;
+            }
+        }
+        result
+    }
+
+    /// The server's banner and the algorithms negotiated during the handshake,
+    /// if connected
+    pub fn session_info(&self) -> Option<SessionInfo> {
+        let session = self.session.as_ref()?;
+        let methods = [
+            ("Key exchange", MethodType::Kex),
+            ("Host key", MethodType::HostKey),
+            ("Cipher (client to server)", MethodType::CryptCs),
+            ("Cipher (server to client)", MethodType::CryptSc),
+            ("MAC (client to server)", MethodType::MacCs),
+            ("MAC (server to client)", MethodType::MacSc),
+            ("Compression (client to server)", MethodType::CompCs),
+            ("Compression (server to client)", MethodType::CompSc),
+        ]
+        .into_iter()
+        .filter_map(|(purpose, method)| Some((purpose, session.methods(method)?.to_string())))
+        .collect();
+        Some(SessionInfo {
+            banner: session.banner().map(str::to_string),
+            methods,
+        })
+    }
+
     pub fn disconnect(&mut self) {
+        if self.session.is_some() {
+            log::info!("Disconnecting from {}:{}", self.hostname, self.port);
+        }
         self.sftp = None;
         self.session = None;
     }
 
-    pub fn delete_file(&self, remote_path: &str) -> Result<(), String> {
+    pub fn delete_file(&self, remote_path: &str) -> Result<(), SshError> {
+        if self.is_elevated() {
+            return self.run_sudo(
+                &format!("rm -- {}", shell_quote(remote_path)),
+                "Failed to delete file",
+            );
+        }
         if let Some(sftp) = &self.sftp {
-            sftp.unlink(Path::new(remote_path))
-                .map_err(|e| format!("Failed to delete file: {}", e))
+            sftp.unlink(&os_path(remote_path))
+                .map_err(|e| SshError::from_ssh2("Failed to delete file", e))
         } else {
-            Err("SFTP subsystem not initialized.".to_string())
+            Err(SshError::NotConnected)
         }
     }
 
-    pub fn list_directory(&self, path: &str) -> Result<Vec<(String, bool)>, String> {
-        let sftp = self
-            .sftp
-            .as_ref()
-            .ok_or_else(|| "SFTP subsystem not initialized.".to_string())?;
-
-        let entries = sftp
-            .readdir(Path::new(path))
-            .map_err(|e| format!("Failed to read directory: {}", e))?;
+    /// List the contents of a directory.
+    ///
+    /// Some servers deny `readdir` over SFTP for directories the shell can still
+    /// list (e.g. because of unusual ACLs), so a permission error falls back to
+    /// parsing `ls -la` output. The returned listing records which method was used,
+    /// along with the resolved path of the directory.
+    ///
+    /// At most [`MAX_LISTING_ENTRIES`] entries are read, in the order the server
+    /// sends them; the listing says whether more were left out.
+    pub fn list_directory(&self, path: &str) -> Result<DirectoryListing, SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
 
-        let mut result = Vec::new();
-        for (entry_path, stat) in entries {
-            if let Some(name) = entry_path.file_name() {
-                let name_str = name.to_string_lossy().to_string();
-                result.push((name_str, stat.is_dir()));
+        let (mut entries, source) = match read_directory(sftp, path, MAX_LISTING_ENTRIES + 1) {
+            Ok(entries) => (entries, ListingSource::Sftp),
+            Err(e) => {
+                let error = SshError::from_ssh2("Failed to read directory", e);
+                if !matches!(error, SshError::PermissionDenied(..)) {
+                    return Err(error);
+                }
+                match self.list_directory_with_ls(path) {
+                    Ok(entries) => (entries, ListingSource::ShellFallback),
+                    // Such as a wrong sudo password, which the user has to know about
+                    Err(e) if self.is_elevated() => return Err(e),
+                    Err(_) => return Err(error),
+                }
             }
-        }
+        };
 
-        result.sort_by(|a, b| {
-            if a.1 && !b.1 {
+        let truncated = entries.len() > MAX_LISTING_ENTRIES;
+        entries.truncate(MAX_LISTING_ENTRIES);
+        entries.sort_by(|a, b| {
+            if a.is_dir && !b.is_dir {
                 std::cmp::Ordering::Less
-            } else if !a.1 && b.1 {
+            } else if !a.is_dir && b.is_dir {
                 std::cmp::Ordering::Greater
             } else {
-                a.0.cmp(&b.0)
+                a.name.cmp(&b.name)
             }
         });
 
-        Ok(result)
+        let canonical_path = sftp
+            .realpath(&os_path(path))
+            .ok()
+            .map(|real| name_from_os(real.as_os_str()));
+
+        Ok(DirectoryListing {
+            entries,
+            source,
+            canonical_path,
+            truncated,
+        })
+    }
+
+    /// List a directory by running `ls -la` over a shell channel.
+    fn list_directory_with_ls(&self, path: &str) -> Result<Vec<FileEntry>, SshError> {
+        let session = self.session.as_ref().ok_or(SshError::NotConnected)?;
+
+        let quoted = shell_quote(path);
+        // In sudo mode, directories the login user may not read are listed as root
+        let ls = |command: String| -> Result<Vec<u8>, SshError> {
+            if self.is_elevated() {
+                return self.sudo_output(&command, "Failed to list directory");
+            }
+            let (stdout, stderr, exit_code) = Self::run_command_raw(session, &command, &[])?;
+            if exit_code == 0 {
+                return Ok(stdout);
+            }
+            let stderr = stderr.trim();
+            let msg = format!("Failed to list {} with ls: {}", path, stderr);
+            let details = Some(format!("exit status {}", exit_code));
+            Err(if stderr.contains("Permission denied") {
+                SshError::PermissionDenied(msg, details)
+            } else if stderr.contains("No such file") {
+                SshError::NotFound(msg, details)
+            } else {
+                SshError::Sftp(msg, details)
+            })
+        };
+        // GNU ls can print the mtime as a Unix timestamp; other implementations
+        // reject the option and only support the default three-field date,
+        // which we can't parse reliably.
+        let (output, epoch_time) =
+            match ls(format!("LC_ALL=C ls -la --time-style=+%s -- {}", quoted)) {
+                Ok(output) => (output, true),
+                Err(SshError::Sftp(..)) => (ls(format!("LC_ALL=C ls -la -- {}", quoted))?, false),
+                Err(e) => return Err(e),
+            };
+        // Names that are not valid UTF-8 are kept as for SFTP listings
+        Ok(parse_ls_output(&name_from_bytes(&output), epoch_time))
     }
 
-    pub fn read_file(&self, remote_path: &str) -> Result<String, String> {
+    /// Read the whole content of a remote file.
+    pub fn read_file(&self, remote_path: &str) -> Result<Vec<u8>, SshError> {
         if let Some(sftp) = &self.sftp {
             let mut file = sftp
-                .open(Path::new(remote_path))
-                .map_err(|e| format!("Failed to open file: {}", e))?;
-            let mut content = String::new();
-            file.read_to_string(&mut content)
-                .map_err(|e| format!("Failed to read file: {}", e))?;
+                .open(os_path(remote_path))
+                .map_err(|e| SshError::from_ssh2("Failed to open file", e))?;
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)
+                .map_err(|e| SshError::from_io("Failed to read file", e))?;
             Ok(content)
         } else {
-            Err("SFTP subsystem not initialized.".to_string())
+            Err(SshError::NotConnected)
         }
     }
 
-    pub fn write_file(&self, remote_path: &str, content: &str) -> Result<(), String> {
-        if let Some(sftp) = &self.sftp {
-            let mut file = sftp
-                .create(Path::new(remote_path))
-                .map_err(|e| format!("Failed to create file: {}", e))?;
-            file.write_all(content.as_bytes())
-                .map_err(|e| format!("Failed to write file: {}", e))?;
-            Ok(())
-        } else {
-            Err("SFTP subsystem not initialized.".to_string())
+    /// Read a remote file through sudo, for files the login user may not read:
+    /// all of it, or only its first `max_bytes`. Needs sudo mode.
+    pub fn sudo_read_file(
+        &self,
+        remote_path: &str,
+        max_bytes: Option<u64>,
+    ) -> Result<Vec<u8>, SshError> {
+        if !self.is_elevated() {
+            return Err(SshError::PermissionDenied(
+                "Failed to read file: sudo mode is off".to_string(),
+                None,
+            ));
         }
+        let script = match max_bytes {
+            Some(max_bytes) => format!("head -c {} -- {}", max_bytes, shell_quote(remote_path)),
+            None => format!("cat -- {}", shell_quote(remote_path)),
+        };
+        self.sudo_output(&script, "Failed to read file")
     }
 
-    pub fn download_file(&self, remote_path: &str, local_path: &str) -> Result<(), String> {
-        let sftp = self
-            .sftp
-            .as_ref()
-            .ok_or_else(|| "SFTP subsystem not initialized.".to_string())?;
+    /// The size and modification time of a remote file the login user may not
+    /// reach, asked for through sudo with GNU or BSD `stat`. Needs sudo mode.
+    pub fn sudo_file_version(&self, remote_path: &str) -> Result<FileVersion, SshError> {
+        if !self.is_elevated() {
+            return Err(SshError::PermissionDenied(
+                "Failed to stat file: sudo mode is off".to_string(),
+                None,
+            ));
+        }
+        let quoted = shell_quote(remote_path);
+        let output = self.sudo_output(
+            &format!(
+                "stat -L -c '%s %Y' -- {0} 2>/dev/null || stat -L -f '%z %m' -- {0}",
+                quoted
+            ),
+            "Failed to stat file",
+        )?;
+        let output = String::from_utf8_lossy(&output);
+        let mut fields = output.split_whitespace().map(str::parse::<u64>);
+        match (fields.next(), fields.next()) {
+            (Some(Ok(size)), mtime) => Ok(FileVersion {
+                size,
+                mtime: mtime.and_then(Result::ok),
+            }),
+            _ => Err(SshError::Sftp(
+                format!(
+                    "Failed to stat file: unexpected output of stat: {}",
+                    output.trim()
+                ),
+                None,
+            )),
+        }
+    }
+
+    /// Check whether a remote path exists.
+    pub fn exists(&self, remote_path: &str) -> Result<bool, SshError> {
+        self.path_type(remote_path).map(|kind| kind.is_some())
+    }
+
+    /// Tell what a remote path names, or `None` if nothing exists there.
+    pub fn path_type(&self, remote_path: &str) -> Result<Option<EntryKind>, SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        match sftp.stat(&os_path(remote_path)) {
+            Ok(stat) if stat.is_dir() => Ok(Some(EntryKind::Directory)),
+            Ok(stat) if stat.is_file() => Ok(Some(EntryKind::File)),
+            Ok(_) => Ok(Some(EntryKind::Other)),
+            Err(e) => match SshError::from_ssh2("Failed to stat file", e) {
+                SshError::NotFound(..) => Ok(None),
+                error => Err(error),
+            },
+        }
+    }
+
+    /// Return the size and modification time of a remote file.
+    pub fn file_version(&self, remote_path: &str) -> Result<FileVersion, SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let stat = sftp
+            .stat(&os_path(remote_path))
+            .map_err(|e| SshError::from_ssh2("Failed to stat file", e))?;
+        Ok(FileVersion {
+            size: stat.size.unwrap_or(0),
+            mtime: stat.mtime,
+        })
+    }
+
+    /// Collect the metadata of a remote path without following a final
+    /// symbolic link, whose target is read and looked at separately.
+    pub fn properties(&self, remote_path: &str) -> Result<FileProperties, SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let stat = sftp
+            .lstat(&os_path(remote_path))
+            .map_err(|e| SshError::from_ssh2("Failed to stat file", e))?;
+        let link_target = if stat.file_type().is_symlink() {
+            let target = sftp
+                .readlink(&os_path(remote_path))
+                .map_err(|e| SshError::from_ssh2("Failed to read link", e))?;
+            Some(name_from_os(target.as_os_str()))
+        } else {
+            None
+        };
+        let kind = if link_target.is_some() {
+            self.path_type(remote_path).ok().flatten()
+        } else if stat.is_dir() {
+            Some(EntryKind::Directory)
+        } else if stat.is_file() {
+            Some(EntryKind::File)
+        } else {
+            Some(EntryKind::Other)
+        };
+        // SFTP only reports numeric ids and no link count; `ls` knows both on
+        // Unix servers
+        let (links, (owner, group)) = self
+            .execute_command(&format!("LC_ALL=C ls -ld -- {}", shell_quote(remote_path)))
+            .ok()
+            .filter(|output| output.exit_code == 0)
+            .and_then(|output| {
+                let (fields, _) = split_fields(output.stdout.lines().next()?, 4)?;
+                Some((
+                    fields[1].parse().ok(),
+                    (fields[2].to_string(), fields[3].to_string()),
+                ))
+            })
+            .map_or((None, (None, None)), |(links, (owner, group))| {
+                (links, (Some(owner), Some(group)))
+            });
+        Ok(FileProperties {
+            path: remote_path.to_string(),
+            kind,
+            size: stat.size,
+            perm: stat.perm,
+            uid: stat.uid,
+            gid: stat.gid,
+            owner,
+            group,
+            links,
+            mtime: stat.mtime,
+            atime: stat.atime,
+            link_target,
+        })
+    }
+
+    /// Read at most `max_bytes` from the start of a remote file.
+    ///
+    /// The raw bytes are returned, possibly ending inside a character; the
+    /// caller decodes them with the file's encoding.
+    pub fn read_file_head(&self, remote_path: &str, max_bytes: u64) -> Result<Vec<u8>, SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let file = sftp
+            .open(os_path(remote_path))
+            .map_err(|e| SshError::from_ssh2("Failed to open file", e))?;
+        let mut content = Vec::new();
+        file.take(max_bytes)
+            .read_to_end(&mut content)
+            .map_err(|e| SshError::from_io("Failed to read file", e))?;
+        Ok(content)
+    }
+
+    /// Replace a remote file's content in place, creating it if it does not
+    /// exist. An existing file keeps its permissions, see [`restore_mode`].
+    pub fn write_file(&self, remote_path: &str, content: &[u8]) -> Result<(), SshError> {
+        if self.is_elevated() {
+            return self.sudo_write(remote_path, content, WritePosition::Replace);
+        }
+        if let Some(sftp) = &self.sftp {
+            let perm = sftp
+                .stat(&os_path(remote_path))
+                .ok()
+                .and_then(|stat| stat.perm);
+            let mut file = sftp
+                .create(&os_path(remote_path))
+                .map_err(|e| SshError::from_ssh2("Failed to create file", e))?;
+            file.write_all(content).map_err(|e| {
+                check_disk_full(&mut file, SshError::from_io("Failed to write file", e))
+            })?;
+            drop(file);
+            restore_mode(sftp, remote_path, perm);
+            Ok(())
+        } else {
+            Err(SshError::NotConnected)
+        }
+    }
+
+    /// Replace a remote file with `content` without ever leaving it half written.
+    ///
+    /// The content goes to a hidden temporary file next to the target, created
+    /// with the target's mode, and flushed to disk with [`fsync`] so that a crash
+    /// of the server cannot leave the renamed file empty. It is then renamed over
+    /// the target with [`Self::posix_rename`], and given the target's exact mode
+    /// again, see [`restore_mode`]. If anything fails before the rename, the
+    /// original is untouched and the temporary file is removed. A symbolic link
+    /// is followed so that its target is replaced.
+    pub fn write_file_atomic(&self, remote_path: &str, content: &[u8]) -> Result<(), SshError> {
+        if self.is_elevated() {
+            return self.sudo_write(remote_path, content, WritePosition::Replace);
+        }
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let stat = match sftp.stat(&os_path(remote_path)) {
+            Ok(stat) => stat,
+            Err(e) => match SshError::from_ssh2("Failed to stat file", e) {
+                // Nothing to protect yet
+                SshError::NotFound(..) => return self.write_file(remote_path, content),
+                error => return Err(error),
+            },
+        };
+        let target = sftp
+            .realpath(&os_path(remote_path))
+            .map(|path| name_from_os(path.as_os_str()))
+            .map_err(|e| SshError::from_ssh2("Failed to resolve path", e))?;
+        let mode = stat.perm.map_or(0o644, |perm| (perm & 0o7777) as i32);
+
+        let (dir, name) = target.rsplit_once('/').unwrap_or((".", &target));
+        let mut temp = format!("{}/.{}.tmp", dir, name);
+        let mut attempt = 1;
+        let mut file = loop {
+            match sftp.open_mode(
+                os_path(&temp),
+                OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::EXCLUSIVE,
+                mode,
+                OpenType::File,
+            ) {
+                Ok(file) => break file,
+                // A file of that name exists already; never overwrite it
+                Err(_) if attempt < 10 && sftp.lstat(&os_path(&temp)).is_ok() => {
+                    temp = format!("{}/.{}.{}.tmp", dir, name, attempt);
+                    attempt += 1;
+                }
+                Err(e) => return Err(SshError::from_ssh2("Failed to create temporary file", e)),
+            }
+        };
+        let written = file
+            .write_all(content)
+            .map_err(|e| {
+                check_disk_full(
+                    &mut file,
+                    SshError::from_io("Failed to write temporary file", e),
+                )
+            })
+            .and_then(|()| fsync(&mut file));
+        drop(file);
+        let result = written.and_then(|()| self.posix_rename(sftp, &temp, &target));
+        if result.is_err() {
+            let _ = sftp.unlink(&os_path(&temp));
+        } else {
+            restore_mode(sftp, &target, stat.perm);
+        }
+        result
+    }
+
+    /// Rename `temp` over the existing file `target` in one step, so that the
+    /// target is always either the old or the new file.
+    ///
+    /// This is what the `posix-rename@openssh.com` extension is for, but the
+    /// `ssh2` crate does not expose it. A plain SFTP rename with the overwrite
+    /// flags is tried first, for SFTP version 5 and later servers; OpenSSH
+    /// speaks version 3, ignores the flags and refuses to overwrite, so then
+    /// the rename is done with `mv -f` over a shell channel, which is atomic
+    /// within one filesystem and needs shell access.
+    fn posix_rename(&self, sftp: &Sftp, temp: &str, target: &str) -> Result<(), SshError> {
+        let flags = RenameFlags::OVERWRITE | RenameFlags::ATOMIC | RenameFlags::NATIVE;
+        if sftp
+            .rename(&os_path(temp), &os_path(target), Some(flags))
+            .is_ok()
+        {
+            return Ok(());
+        }
+        let output = self
+            .execute_command(&format!(
+                "mv -f -- {} {}",
+                shell_quote(temp),
+                shell_quote(target)
+            ))
+            .map_err(|e| e.with_context("Failed to replace the file; the original is unchanged"))?;
+        if output.exit_code == 0 {
+            Ok(())
+        } else {
+            Err(SshError::Sftp(
+                format!(
+                    "Failed to replace the file; the original is unchanged: {}",
+                    output.stderr.trim()
+                ),
+                None,
+            ))
+        }
+    }
+
+    /// Append `content` to the end of a remote file, creating it if it does not exist.
+    pub fn append_file(&self, remote_path: &str, content: &[u8]) -> Result<(), SshError> {
+        if self.is_elevated() {
+            return self.sudo_write(remote_path, content, WritePosition::Append);
+        }
+        if let Some(sftp) = &self.sftp {
+            let mut file = sftp
+                .open_mode(
+                    os_path(remote_path),
+                    OpenFlags::WRITE | OpenFlags::APPEND | OpenFlags::CREATE,
+                    0o644,
+                    OpenType::File,
+                )
+                .map_err(|e| SshError::from_ssh2("Failed to open file for appending", e))?;
+            file.write_all(content)
+                .map_err(|e| SshError::from_io("Failed to append to file", e))?;
+            Ok(())
+        } else {
+            Err(SshError::NotConnected)
+        }
+    }
+
+    /// Overwrite the bytes of a remote file from `offset` on with `content`,
+    /// leaving the rest of it untouched, so that a large file need not be
+    /// rewritten. The offset may be at most the size of the file; at the size
+    /// the content is appended.
+    pub fn write_at(&self, remote_path: &str, offset: u64, content: &[u8]) -> Result<(), SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let size = sftp
+            .stat(&os_path(remote_path))
+            .map_err(|e| SshError::from_ssh2("Failed to stat file", e))?
+            .size
+            .unwrap_or(0);
+        if offset > size {
+            return Err(SshError::Sftp(
+                format!(
+                    "Failed to write at byte {}: the file has only {} bytes",
+                    offset, size
+                ),
+                None,
+            ));
+        }
+        if self.is_elevated() {
+            return self.sudo_write(remote_path, content, WritePosition::At(offset));
+        }
+        let mut file = sftp
+            .open_mode(
+                os_path(remote_path),
+                OpenFlags::WRITE,
+                0o644,
+                OpenType::File,
+            )
+            .map_err(|e| SshError::from_ssh2("Failed to open file for writing", e))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| SshError::from_io("Failed to seek in file", e))?;
+        file.write_all(content).map_err(|e| {
+            check_disk_full(&mut file, SshError::from_io("Failed to write to file", e))
+        })
+    }
+
+    /// Write `content` to a private temporary file over SFTP, then copy it onto
+    /// `remote_path` with `sudo`. Copying with `cat` or `dd` keeps the owner
+    /// and permissions of an existing target.
+    fn sudo_write(
+        &self,
+        remote_path: &str,
+        content: &[u8],
+        position: WritePosition,
+    ) -> Result<(), SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let output = self.execute_command("mktemp")?;
+        let temp = output.stdout.trim();
+        if output.exit_code != 0 || temp.is_empty() {
+            return Err(SshError::Sftp(
+                format!(
+                    "Failed to create a temporary file: {}",
+                    output.stderr.trim()
+                ),
+                None,
+            ));
+        }
+        let upload = sftp
+            .create(&os_path(temp))
+            .map_err(|e| SshError::from_ssh2("Failed to create temporary file", e))
+            .and_then(|mut file| {
+                file.write_all(content)
+                    .map_err(|e| SshError::from_io("Failed to write temporary file", e))
+            });
+        let (temp_arg, target) = (shell_quote(temp), shell_quote(remote_path));
+        let command = match position {
+            WritePosition::Replace => format!("cat {} > {}", temp_arg, target),
+            WritePosition::Append => format!("cat {} >> {}", temp_arg, target),
+            // One-byte blocks make `seek` count bytes with any `dd`
+            WritePosition::At(offset) => format!(
+                "dd if={} of={} bs=1 seek={} conv=notrunc 2>/dev/null",
+                temp_arg, target, offset
+            ),
+        };
+        let result = upload.and_then(|()| self.run_sudo(&command, "Failed to write file"));
+        let _ = sftp.unlink(&os_path(temp));
+        result
+    }
+
+    /// Download a remote file, checking `cancel` between buffer writes.
+    pub fn download_file(
+        &self,
+        remote_path: &str,
+        local_path: &str,
+        cancel: &AtomicBool,
+    ) -> Result<(), SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
         let mut remote_file = sftp
-            .open(Path::new(remote_path))
-            .map_err(|e| format!("Failed to open remote file: {}", e))?;
-        let mut local_file = std::fs::File::create(local_path)
-            .map_err(|e| format!("Failed to create local file: {}", e))?;
+            .open(os_path(remote_path))
+            .map_err(|e| SshError::from_ssh2("Failed to open remote file", e))?;
+        let mut local_file = std::fs::File::create(os_path(local_path))
+            .map_err(|e| SshError::from_io("Failed to create local file", e))?;
 
         let mut buffer = [0; 8192];
+        // Bytes written to the local file so far, where a retried read resumes
+        let mut offset = 0;
+        let mut throttle = Throttle::new(self.speed_limit);
         loop {
-            let bytes_read = remote_file
-                .read(&mut buffer)
-                .map_err(|e| format!("Error reading from remote file: {}", e))?;
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SshError::Cancelled(
+                    format!("Download of {} cancelled", remote_path),
+                    None,
+                ));
+            }
+            let bytes_read = self
+                .retry_chunk(cancel, |attempt| {
+                    if attempt > 0 {
+                        remote_file.seek(SeekFrom::Start(offset))?;
+                    }
+                    remote_file.read(&mut buffer)
+                })
+                .map_err(|e| SshError::from_io("Error reading from remote file", e))?;
             if bytes_read == 0 {
                 break;
             }
             local_file
                 .write_all(&buffer[..bytes_read])
-                .map_err(|e| format!("Error writing to local file: {}", e))?;
+                .map_err(|e| SshError::from_io("Error writing to local file", e))?;
+            offset += bytes_read as u64;
+            throttle.pace(bytes_read, cancel);
         }
         Ok(())
     }
 
-    pub fn upload_file(&self, local_path: &str, remote_path: &str) -> Result<(), String> {
-        let sftp = self
-            .sftp
-            .as_ref()
-            .ok_or_else(|| "SFTP subsystem not initialized.".to_string())?;
-        let mut local_file = std::fs::File::open(local_path)
-            .map_err(|e| format!("Failed to open local file: {}", e))?;
+    /// Upload a local file, checking `cancel` between buffer writes.
+    pub fn upload_file(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        cancel: &AtomicBool,
+    ) -> Result<(), SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let mut local_file = std::fs::File::open(os_path(local_path))
+            .map_err(|e| SshError::from_io("Failed to open local file", e))?;
         let mut remote_file = sftp
             .open_mode(
-                Path::new(remote_path),
+                os_path(remote_path),
                 OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
                 0o644,
                 OpenType::File,
             )
-            .map_err(|e| format!("Failed to open remote file: {}", e))?;
+            .map_err(|e| SshError::from_ssh2("Failed to open remote file", e))?;
 
         let mut buffer = [0; 8192];
+        // Bytes known to be written to the remote file, where a retried write starts over
+        let mut offset = 0;
+        let mut throttle = Throttle::new(self.speed_limit);
         loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SshError::Cancelled(
+                    format!("Upload of {} cancelled", local_path),
+                    None,
+                ));
+            }
             let bytes_read = local_file
                 .read(&mut buffer)
-                .map_err(|e| format!("Error reading from local file: {}", e))?;
+                .map_err(|e| SshError::from_io("Error reading from local file", e))?;
             if bytes_read == 0 {
                 break;
             }
-            remote_file
-                .write_all(&buffer[..bytes_read])
-                .map_err(|e| format!("Error writing to remote file: {}", e))?;
+            let written = self
+                .retry_chunk(cancel, |attempt| {
+                    if attempt > 0 {
+                        remote_file.seek(SeekFrom::Start(offset))?;
+                    }
+                    remote_file.write_all(&buffer[..bytes_read])
+                })
+                .map_err(|e| {
+                    check_disk_full(
+                        &mut remote_file,
+                        SshError::from_io("Error writing to remote file", e),
+                    )
+                });
+            if let Err(error @ SshError::DiskFull(..)) = written {
+                // A partial file only takes up what little space is left
+                drop(remote_file);
+                if let Err(e) = sftp.unlink(&os_path(remote_path)) {
+                    log::warn!("Failed to remove partial upload {}: {}", remote_path, e);
+                }
+                return Err(error);
+            }
+            written?;
+            offset += bytes_read as u64;
+            throttle.pace(bytes_read, cancel);
+        }
+        Ok(())
+    }
+
+    /// Recursively collect the entries below `root` as (full path, is_dir),
+    /// with every directory listed before its contents.
+    fn walk_remote(
+        &self,
+        root: &str,
+        progress: &mut dyn FnMut(RecursiveProgress),
+    ) -> Result<Vec<(String, bool)>, SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let mut result = Vec::new();
+        let mut pending = vec![root.to_string()];
+        while let Some(dir) = pending.pop() {
+            progress(RecursiveProgress::Enumerating {
+                found: result.len(),
+                current: dir.clone(),
+            });
+            let entries = sftp
+                .readdir(os_path(&dir))
+                .map_err(|e| SshError::from_ssh2("Failed to read directory", e))?;
+            for (entry_path, stat) in entries {
+                let entry_path = name_from_os(entry_path.as_os_str());
+                if stat.is_dir() {
+                    pending.push(entry_path.clone());
+                }
+                result.push((entry_path, stat.is_dir()));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Size and modification time (seconds since the epoch) of a remote file,
+    /// or `None` if it cannot be read.
+    fn remote_size_and_mtime(&self, path: &str) -> Option<(u64, Option<u64>)> {
+        let stat = self.sftp.as_ref()?.stat(&os_path(path)).ok()?;
+        Some((stat.size.unwrap_or(0), stat.mtime))
+    }
+
+    /// Whether `path` is an existing remote directory
+    fn is_remote_directory(&self, path: &str) -> bool {
+        matches!(self.path_type(path), Ok(Some(EntryKind::Directory)))
+    }
+
+    /// Create a remote directory unless it already exists. A directory that
+    /// another process creates at the same time counts as created.
+    fn ensure_remote_directory(&self, path: &str) -> Result<(), SshError> {
+        if self.sftp.is_none() {
+            return Err(SshError::NotConnected);
+        }
+        if self.is_remote_directory(path) {
+            return Ok(());
+        }
+        match self.create_directory(path, DEFAULT_DIRECTORY_MODE) {
+            Err(_) if self.is_remote_directory(path) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Create `path` and every missing directory above it, like `mkdir -p`.
+    pub fn create_directories(&self, path: &str) -> Result<(), SshError> {
+        let path = normalize_remote_path(path);
+        let mut current = if path.starts_with('/') {
+            "/".to_string()
+        } else {
+            String::new()
+        };
+        for part in path
+            .split('/')
+            .filter(|part| !part.is_empty() && *part != ".")
+        {
+            if !current.is_empty() && !current.ends_with('/') {
+                current.push('/');
+            }
+            current.push_str(part);
+            if part != ".." {
+                self.ensure_remote_directory(&current)?;
+            }
         }
         Ok(())
     }
 
-    pub fn rename(&self, old_path: &str, new_path: &str) -> Result<(), String> {
+    /// Create the missing directories above the remote path `path`, so that
+    /// uploading to it cannot fail for lack of a parent directory.
+    pub fn create_parent_directories(&self, path: &str) -> Result<(), SshError> {
+        match remote_parent(path) {
+            Some(parent) => self
+                .create_directories(parent)
+                .map_err(|e| e.with_context("Failed to create parent directories")),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the directory above the remote path `path` does not exist
+    pub fn is_parent_missing(&self, path: &str) -> bool {
+        remote_parent(path).is_some_and(|parent| !self.is_remote_directory(parent))
+    }
+
+    /// Recursively download `remote_dir` into `local_dir`.
+    ///
+    /// With [`SyncMode::SkipUnchanged`], files whose local copy has the same
+    /// size and is at least as new are skipped, so running it again only
+    /// transfers what changed. [`SyncMode::SkipExisting`] skips every file
+    /// that exists locally.
+    ///
+    /// `progress` is called while the remote tree is enumerated, then with
+    /// (file number, total files, remote path) before each file. Cancelling stops before the
+    /// next file and keeps the files already downloaded.
+    pub fn download_directory(
+        &self,
+        remote_dir: &str,
+        local_dir: &str,
+        sync: SyncMode,
+        cancel: &AtomicBool,
+        progress: &mut dyn FnMut(RecursiveProgress),
+    ) -> Result<(), SshError> {
+        let entries = self.walk_remote(remote_dir, progress)?;
+        let total = entries.iter().filter(|(_, is_dir)| !is_dir).count();
+        std::fs::create_dir_all(os_path(local_dir))
+            .map_err(|e| SshError::from_io("Failed to create local directory", e))?;
+
+        let mut done = 0;
+        for (remote_path, is_dir) in &entries {
+            let relative = remote_relative(remote_dir, remote_path);
+            let local_path = os_path(local_dir).join(os_path(relative));
+            if *is_dir {
+                std::fs::create_dir_all(&local_path)
+                    .map_err(|e| SshError::from_io("Failed to create local directory", e))?;
+                continue;
+            }
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SshError::Cancelled(
+                    format!("Download cancelled after {} of {} files", done, total),
+                    None,
+                ));
+            }
+            progress(RecursiveProgress::Item {
+                done: done + 1,
+                total,
+                current: remote_path.clone(),
+            });
+            done += 1;
+            let skip = match sync {
+                SyncMode::Always => false,
+                SyncMode::SkipUnchanged => is_unchanged(
+                    self.remote_size_and_mtime(remote_path),
+                    local_size_and_mtime(&local_path),
+                ),
+                SyncMode::SkipExisting => local_path.exists(),
+            };
+            if skip {
+                continue;
+            }
+            self.download_file(remote_path, &name_from_os(local_path.as_os_str()), cancel)?;
+        }
+        Ok(())
+    }
+
+    /// Recursively upload `local_dir` into `remote_dir`.
+    ///
+    /// With [`SyncMode::SkipUnchanged`], files whose remote copy has the same
+    /// size and is at least as new are skipped; [`SyncMode::SkipExisting`]
+    /// skips every file that exists remotely.
+    ///
+    /// `progress` is called while the local tree is enumerated, then with
+    /// (file number, total files, local path) before each file. Cancelling stops before the
+    /// next file and keeps the files already uploaded.
+    pub fn upload_directory(
+        &self,
+        local_dir: &str,
+        remote_dir: &str,
+        sync: SyncMode,
+        cancel: &AtomicBool,
+        progress: &mut dyn FnMut(RecursiveProgress),
+    ) -> Result<(), SshError> {
+        let entries = walk_local(&os_path(local_dir), progress)?;
+        let total = entries.iter().filter(|(_, is_dir)| !is_dir).count();
+        self.ensure_remote_directory(remote_dir)?;
+
+        let mut done = 0;
+        for (local_path, is_dir) in &entries {
+            let relative = local_relative(local_dir, local_path);
+            let remote_path = format!("{}/{}", remote_dir.trim_end_matches('/'), relative);
+            if *is_dir {
+                self.ensure_remote_directory(&remote_path)?;
+                continue;
+            }
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SshError::Cancelled(
+                    format!("Upload cancelled after {} of {} files", done, total),
+                    None,
+                ));
+            }
+            progress(RecursiveProgress::Item {
+                done: done + 1,
+                total,
+                current: local_path.to_string_lossy().to_string(),
+            });
+            done += 1;
+            let skip = match sync {
+                SyncMode::Always => false,
+                SyncMode::SkipUnchanged => is_unchanged(
+                    local_size_and_mtime(local_path),
+                    self.remote_size_and_mtime(&remote_path),
+                ),
+                SyncMode::SkipExisting => self.remote_size_and_mtime(&remote_path).is_some(),
+            };
+            if skip {
+                continue;
+            }
+            self.upload_file(&name_from_os(local_path.as_os_str()), &remote_path, cancel)?;
+        }
+        Ok(())
+    }
+
+    /// List the files that uploading `local_dir` into `remote_dir` with `sync`
+    /// would overwrite, relative to `remote_dir`.
+    pub fn find_upload_conflicts(
+        &self,
+        local_dir: &str,
+        remote_dir: &str,
+        sync: SyncMode,
+    ) -> Result<Vec<String>, SshError> {
+        if sync == SyncMode::SkipExisting {
+            return Ok(Vec::new());
+        }
+        let existing: HashSet<String> = match self.walk_remote(remote_dir, &mut |_| {}) {
+            Ok(entries) => entries
+                .iter()
+                .filter(|(_, is_dir)| !is_dir)
+                .map(|(path, _)| remote_relative(remote_dir, path).to_string())
+                .collect(),
+            // Nothing can be overwritten in a directory that does not exist yet
+            Err(SshError::NotFound(..)) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut conflicts = Vec::new();
+        for (local_path, is_dir) in walk_local(&os_path(local_dir), &mut |_| {})? {
+            let relative = local_relative(local_dir, &local_path);
+            if is_dir || !existing.contains(&relative) {
+                continue;
+            }
+            if sync == SyncMode::SkipUnchanged {
+                let remote_path = format!("{}/{}", remote_dir.trim_end_matches('/'), relative);
+                if is_unchanged(
+                    local_size_and_mtime(&local_path),
+                    self.remote_size_and_mtime(&remote_path),
+                ) {
+                    continue;
+                }
+            }
+            conflicts.push(relative);
+        }
+        conflicts.sort();
+        Ok(conflicts)
+    }
+
+    /// Find the entries of `remote_dir` that do not exist in `local_dir`,
+    /// after uploading `local_dir` into it.
+    pub fn plan_remote_mirror(
+        &self,
+        local_dir: &str,
+        remote_dir: &str,
+    ) -> Result<MirrorPlan, SshError> {
+        let source: HashSet<String> = walk_local(Path::new(local_dir), &mut |_| {})?
+            .iter()
+            .map(|(path, _)| local_relative(local_dir, path))
+            .collect();
+        let destination = self.walk_remote(remote_dir, &mut |_| {})?;
+        let destination_total = destination.len();
+        let entries = destination
+            .into_iter()
+            .filter(|(path, _)| !source.contains(remote_relative(remote_dir, path)))
+            .collect();
+        Ok(MirrorPlan {
+            side: MirrorSide::Remote,
+            root: remote_dir.to_string(),
+            entries,
+            destination_total,
+        })
+    }
+
+    /// Find the entries of `local_dir` that do not exist in `remote_dir`,
+    /// after downloading `remote_dir` into it.
+    pub fn plan_local_mirror(
+        &self,
+        remote_dir: &str,
+        local_dir: &str,
+    ) -> Result<MirrorPlan, SshError> {
+        let source: HashSet<String> = self
+            .walk_remote(remote_dir, &mut |_| {})?
+            .iter()
+            .map(|(path, _)| remote_relative(remote_dir, path).to_string())
+            .collect();
+        let destination = walk_local(&os_path(local_dir), &mut |_| {})?;
+        let destination_total = destination.len();
+        let entries = destination
+            .iter()
+            .filter(|(path, _)| !source.contains(&local_relative(local_dir, path)))
+            .map(|(path, is_dir)| (name_from_os(path.as_os_str()), *is_dir))
+            .collect();
+        Ok(MirrorPlan {
+            side: MirrorSide::Local,
+            root: local_dir.to_string(),
+            entries,
+            destination_total,
+        })
+    }
+
+    /// Delete the entries of a mirror plan: files first, then directories
+    /// deepest-first.
+    ///
+    /// Refuses the whole plan if any entry is not strictly below its root.
+    pub fn apply_mirror_plan(
+        &self,
+        plan: &MirrorPlan,
+        cancel: &AtomicBool,
+        progress: &mut dyn FnMut(RecursiveProgress),
+    ) -> Result<(), SshError> {
+        let root = plan.root.trim_end_matches(['/', '\\']);
+        let inside = |path: &str| {
+            path.len() > root.len() + 1
+                && path.starts_with(root)
+                && path[root.len()..].starts_with(['/', '\\'])
+        };
+        if root.is_empty() || !plan.entries.iter().all(|(path, _)| inside(path)) {
+            return Err(SshError::Io(
+                format!("Refusing to mirror: some entries are outside {}", plan.root),
+                None,
+            ));
+        }
+
+        let total = plan.entries.len();
+        let files = plan.entries.iter().filter(|(_, is_dir)| !is_dir);
+        let dirs = plan.entries.iter().rev().filter(|(_, is_dir)| *is_dir);
+        for (done, (path, is_dir)) in files.chain(dirs).enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SshError::Cancelled(
+                    format!("Delete cancelled after {} of {} items", done, total),
+                    None,
+                ));
+            }
+            progress(RecursiveProgress::Item {
+                done: done + 1,
+                total,
+                current: path.clone(),
+            });
+            match (plan.side, is_dir) {
+                (MirrorSide::Remote, _) => {
+                    let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+                    if *is_dir {
+                        sftp.rmdir(&os_path(path))
+                            .map_err(|e| SshError::from_ssh2("Failed to delete directory", e))?;
+                    } else {
+                        sftp.unlink(&os_path(path))
+                            .map_err(|e| SshError::from_ssh2("Failed to delete file", e))?;
+                    }
+                }
+                (MirrorSide::Local, true) => std::fs::remove_dir(os_path(path))
+                    .map_err(|e| SshError::from_io("Failed to delete local directory", e))?,
+                (MirrorSide::Local, false) => std::fs::remove_file(os_path(path))
+                    .map_err(|e| SshError::from_io("Failed to delete local file", e))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// List what [`Self::delete_directory`] would remove, as (full path,
+    /// is_dir) in the order of deletion: files first, then directories
+    /// deepest-first, then `remote_dir` itself.
+    pub fn plan_delete_directory(
+        &self,
+        remote_dir: &str,
+        progress: &mut dyn FnMut(RecursiveProgress),
+    ) -> Result<Vec<(String, bool)>, SshError> {
+        let entries = self.walk_remote(remote_dir, progress)?;
+        let files = entries.iter().filter(|(_, is_dir)| !is_dir);
+        let dirs = entries.iter().rev().filter(|(_, is_dir)| *is_dir);
+        let mut plan: Vec<(String, bool)> = files.chain(dirs).cloned().collect();
+        plan.push((remote_dir.to_string(), true));
+        Ok(plan)
+    }
+
+    /// List what renaming `old_path` to `new_path` would change: the file
+    /// replaced at `new_path` if `replace` is set, then every moved entry,
+    /// a directory before its contents.
+    pub fn plan_rename(
+        &self,
+        old_path: &str,
+        new_path: &str,
+        replace: bool,
+        progress: &mut dyn FnMut(RecursiveProgress),
+    ) -> Result<Vec<PlannedChange>, SshError> {
+        let mut changes = Vec::new();
+        if replace && self.path_type(new_path)?.is_some() {
+            changes.push(PlannedChange::Delete(new_path.to_string()));
+        }
+        let kind = self.path_type(old_path)?.ok_or_else(|| {
+            SshError::NotFound(format!("No such file or directory: {}", old_path), None)
+        })?;
+        changes.push(PlannedChange::Move {
+            from: old_path.to_string(),
+            to: new_path.to_string(),
+        });
+        if kind == EntryKind::Directory {
+            let mut entries = self.walk_remote(old_path, progress)?;
+            entries.sort();
+            for (path, _) in entries {
+                let to = format!(
+                    "{}/{}",
+                    new_path.trim_end_matches('/'),
+                    remote_relative(old_path, &path)
+                );
+                changes.push(PlannedChange::Move { from: path, to });
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Recursively delete `remote_dir` and everything below it.
+    ///
+    /// `progress` is called while the remote tree is enumerated, then with
+    /// (item number, total items, remote path) before each deletion.
+    /// Cancelling stops before the next item; whatever was already deleted
+    /// stays deleted.
+    pub fn delete_directory(
+        &self,
+        remote_dir: &str,
+        cancel: &AtomicBool,
+        progress: &mut dyn FnMut(RecursiveProgress),
+    ) -> Result<(), SshError> {
+        if self.is_elevated() {
+            // `rm -r` cannot report progress or stop halfway, so the tree is removed in one step
+            let root = normalize_remote_path(remote_dir);
+            if root == "/" {
+                return Err(SshError::PermissionDenied(
+                    "Refusing to delete the root directory".to_string(),
+                    None,
+                ));
+            }
+            progress(RecursiveProgress::Item {
+                done: 1,
+                total: 1,
+                current: root.clone(),
+            });
+            return self.run_sudo(
+                &format!("rm -rf -- {}", shell_quote(&root)),
+                "Failed to delete directory",
+            );
+        }
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let entries = self.plan_delete_directory(remote_dir, progress)?;
+        let total = entries.len();
+        for (done, (path, is_dir)) in entries.iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SshError::Cancelled(
+                    format!("Delete cancelled after {} of {} items", done, total),
+                    None,
+                ));
+            }
+            progress(RecursiveProgress::Item {
+                done: done + 1,
+                total,
+                current: path.clone(),
+            });
+            if *is_dir {
+                sftp.rmdir(&os_path(path))
+                    .map_err(|e| SshError::from_ssh2("Failed to delete directory", e))?;
+            } else {
+                sftp.unlink(&os_path(path))
+                    .map_err(|e| SshError::from_ssh2("Failed to delete file", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rename or move `old_path` to `new_path`. Nothing is sent to the server
+    /// when both name the same path, see [`is_same_remote_path`].
+    pub fn rename(&self, old_path: &str, new_path: &str) -> Result<(), SshError> {
+        if is_same_remote_path(old_path, new_path) {
+            log::info!("Not renaming {} onto itself", old_path);
+            return Ok(());
+        }
+        if self.is_elevated() {
+            return self.run_sudo(
+                &format!("mv -- {} {}", shell_quote(old_path), shell_quote(new_path)),
+                "Failed to rename",
+            );
+        }
         if let Some(sftp) = &self.sftp {
-            let old_path = Path::new(old_path);
-            let new_path = Path::new(new_path);
+            let old_path = os_path(old_path);
+            let new_path = os_path(new_path);
 
-            sftp.rename(old_path, new_path, None)
-                .map_err(|e| format!("Failed to rename: {}", e))
+            sftp.rename(&old_path, &new_path, None)
+                .map_err(|e| SshError::from_ssh2("Failed to rename", e))
         } else {
-            Err("SFTP session not initialized.".to_string())
+            Err(SshError::NotConnected)
+        }
+    }
+
+    /// Rename `old_path` to `new_path`, replacing the file already there.
+    /// Renaming a file onto itself is skipped like in [`Self::rename`]; it
+    /// would otherwise delete it on servers that remove the target first.
+    pub fn rename_replacing(&self, old_path: &str, new_path: &str) -> Result<(), SshError> {
+        if is_same_remote_path(old_path, new_path) {
+            log::info!("Not renaming {} onto itself", old_path);
+            return Ok(());
+        }
+        if self.is_elevated() {
+            return self.run_sudo(
+                &format!(
+                    "mv -f -- {} {}",
+                    shell_quote(old_path),
+                    shell_quote(new_path)
+                ),
+                "Failed to rename",
+            );
+        }
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        self.posix_rename(sftp, old_path, new_path)
+    }
+
+    /// Create `link` as a hard link to the existing file `target`.
+    ///
+    /// libssh2 does not expose OpenSSH's `hardlink@openssh.com` extension, so
+    /// the link is made with `ln` over a shell channel. Servers that only allow
+    /// SFTP, or filesystems without hard links, report an error explaining why.
+    pub fn create_hardlink(&self, target: &str, link: &str) -> Result<(), SshError> {
+        let command = format!("ln -- {} {}", shell_quote(target), shell_quote(link));
+        if self.is_elevated() {
+            return self.run_sudo(&command, "Failed to create hard link");
+        }
+        let output = self
+            .execute_command(&format!("LC_ALL=C {}", command))
+            .map_err(|e| e.with_context("Hard links need shell access"))?;
+        if output.exit_code == 0 {
+            return Ok(());
         }
+        let stderr = output.stderr.trim();
+        let msg = format!("Failed to create hard link: {}", stderr);
+        let details = Some(format!("exit status {}", output.exit_code));
+        Err(if stderr.contains("Invalid cross-device link") {
+            SshError::Sftp(
+                "Failed to create hard link: the link must be on the same filesystem as its target"
+                    .to_string(),
+                Some(stderr.to_string()),
+            )
+        } else if stderr.contains("Permission denied") || stderr.contains("not permitted") {
+            SshError::PermissionDenied(msg, details)
+        } else if stderr.contains("No such file") {
+            SshError::NotFound(msg, details)
+        } else if output.exit_code == 127 {
+            SshError::Sftp(
+                "Failed to create hard link: the server has no `ln` command".to_string(),
+                Some(stderr.to_string()),
+            )
+        } else {
+            SshError::Sftp(msg, details)
+        })
     }
 
-    pub fn create_directory(&self, path: &str) -> Result<(), String> {
+    pub fn create_directory(&self, path: &str, mode: i32) -> Result<(), SshError> {
+        if self.is_elevated() {
+            return self.run_sudo(
+                &format!("mkdir -m {:o} -- {}", mode, shell_quote(path)),
+                "Failed to create directory",
+            );
+        }
         if let Some(sftp) = &self.sftp {
-            sftp.mkdir(Path::new(path), 0o755)
-                .map_err(|e| format!("Failed to create directory: {}", e))
+            sftp.mkdir(&os_path(path), mode)
+                .map_err(|e| SshError::from_ssh2("Failed to create directory", e))
         } else {
-            Err("SFTP subsystem not initialized.".to_string())
+            Err(SshError::NotConnected)
         }
     }
 
-    pub fn create_file(&self, path: &str) -> Result<(), String> {
+    pub fn create_file(&self, path: &str, mode: i32) -> Result<(), SshError> {
+        if self.is_elevated() {
+            let quoted = shell_quote(path);
+            return self.run_sudo(
+                &format!(": > {} && chmod {:o} {}", quoted, mode, quoted),
+                "Failed to create file",
+            );
+        }
         if let Some(sftp) = &self.sftp {
             let mut file = sftp
-                .create(Path::new(path))
-                .map_err(|e| format!("Failed to create file: {}", e))?;
+                .open_mode(
+                    os_path(path),
+                    OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+                    mode,
+                    OpenType::File,
+                )
+                .map_err(|e| SshError::from_ssh2("Failed to create file", e))?;
             file.write_all(b"")
-                .map_err(|e| format!("Failed to initialize file: {}", e))?;
+                .map_err(|e| SshError::from_io("Failed to initialize file", e))?;
             Ok(())
         } else {
-            Err("SFTP subsystem not initialized.".to_string())
+            Err(SshError::NotConnected)
         }
     }
 
-    fn run_command(session: &Session, cmd: &str) -> Result<String, String> {
+    /// Set the access and modification times of `path` to now.
+    ///
+    /// With `create`, a missing file is created empty instead of reporting an error.
+    pub fn touch(&self, path: &str, create: bool) -> Result<(), SshError> {
+        if self.is_elevated() {
+            let flag = if create { "" } else { "-c " };
+            return self.run_sudo(
+                &format!("touch {}-- {}", flag, shell_quote(path)),
+                "Failed to touch",
+            );
+        }
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let times = FileStat {
+            size: None,
+            uid: None,
+            gid: None,
+            perm: None,
+            atime: Some(now),
+            mtime: Some(now),
+        };
+        match sftp.setstat(&os_path(path), times) {
+            Ok(()) => Ok(()),
+            Err(e) => match SshError::from_ssh2("Failed to touch", e) {
+                SshError::NotFound(..) if create => self.create_file(path, DEFAULT_FILE_MODE),
+                error => Err(error),
+            },
+        }
+    }
+
+    fn run_command(session: &Session, cmd: &str) -> Result<CommandOutput, SshError> {
+        Self::run_command_with_input(session, cmd, &[])
+    }
+
+    /// Run a command, feeding `input` to its standard input before closing it.
+    fn run_command_with_input(
+        session: &Session,
+        cmd: &str,
+        input: &[u8],
+    ) -> Result<CommandOutput, SshError> {
+        let (stdout, stderr, exit_code) = Self::run_command_raw(session, cmd, input)?;
+        Ok(CommandOutput {
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr,
+            exit_code,
+        })
+    }
+
+    /// Like [`Self::run_command_with_input`], with the standard output as
+    /// bytes: (stdout, stderr, exit status)
+    fn run_command_raw(
+        session: &Session,
+        cmd: &str,
+        input: &[u8],
+    ) -> Result<(Vec<u8>, String, i32), SshError> {
         let mut channel = session
             .channel_session()
-            .map_err(|e| format!("Failed to open channel: {}", e))?;
+            .map_err(|e| SshError::from_ssh2("Failed to open channel", e))?;
         channel
             .exec(cmd)
-            .map_err(|e| format!("Failed to exec command {}: {}", cmd, e))?;
+            .map_err(|e| SshError::from_ssh2(&format!("Failed to exec command {}", cmd), e))?;
+        if !input.is_empty() {
+            channel
+                .write_all(input)
+                .map_err(|e| SshError::from_io("Failed to send command input", e))?;
+            channel
+                .send_eof()
+                .map_err(|e| SshError::from_ssh2("Failed to send command input", e))?;
+        }
+
+        let mut stdout = Vec::new();
+        channel
+            .read_to_end(&mut stdout)
+            .map_err(|e| SshError::from_io("Failed to read command output", e))?;
 
-        let mut stdout = String::new();
+        let mut stderr = String::new();
         channel
-            .read_to_string(&mut stdout)
-            .map_err(|e| format!("Failed to read command output: {}", e))?;
+            .stderr()
+            .read_to_string(&mut stderr)
+            .map_err(|e| SshError::from_io("Failed to read command error output", e))?;
 
         channel
             .wait_close()
-            .map_err(|e| format!("Failed to close channel: {}", e))?;
+            .map_err(|e| SshError::from_ssh2("Failed to close channel", e))?;
+        let exit_code = channel
+            .exit_status()
+            .map_err(|e| SshError::from_ssh2("Failed to read exit status", e))?;
+
+        Ok((stdout, stderr, exit_code))
+    }
+
+    /// Run a shell command on the server and collect its output.
+    pub fn execute_command(&self, cmd: &str) -> Result<CommandOutput, SshError> {
+        let session = self.session.as_ref().ok_or(SshError::NotConnected)?;
+        Self::run_command(session, cmd)
+    }
+
+    /// Run `script` with `sh` as root through `sudo`, answering the password prompt.
+    ///
+    /// `-k` makes sudo ask for the password every time so the prompt is always
+    /// answered, even when a previous credential is still cached.
+    fn run_sudo(&self, script: &str, context: &str) -> Result<(), SshError> {
+        self.sudo_output(script, context).map(drop)
+    }
+
+    /// Run `script` like [`Self::run_sudo`] and return what it prints, as bytes
+    fn sudo_output(&self, script: &str, context: &str) -> Result<Vec<u8>, SshError> {
+        let session = self.session.as_ref().ok_or(SshError::NotConnected)?;
+        let password = self.sudo_password.as_deref().unwrap_or_default();
+        let command = format!("sudo -S -k -p '' -- sh -c {}", shell_quote(script));
+        let (stdout, stderr, exit_code) =
+            Self::run_command_raw(session, &command, format!("{}\n", password).as_bytes())?;
+        if exit_code == 0 {
+            return Ok(stdout);
+        }
+        let stderr = stderr.trim();
+        let msg = format!("{} (sudo): {}", context, stderr);
+        let details = Some(format!("exit status {}", exit_code));
+        // A rejected sudo password is not a login failure: the session stays usable
+        Err(
+            if stderr.contains("Permission denied")
+                || stderr.contains("incorrect password")
+                || stderr.contains("try again")
+                || stderr.contains("not in the sudoers")
+            {
+                SshError::PermissionDenied(msg, details)
+            } else if stderr.contains("Read-only file system") {
+                SshError::ReadOnly(msg, details)
+            } else if stderr.contains("No space left") || stderr.contains("quota exceeded") {
+                SshError::DiskFull(msg, details)
+            } else if stderr.contains("No such file") {
+                SshError::NotFound(msg, details)
+            } else {
+                SshError::Sftp(msg, details)
+            },
+        )
+    }
 
-        Ok(stdout)
+    /// Search the tree below `root` for entries whose name contains
+    /// `pattern`, ignoring case, passing each match to `found` as soon as it
+    /// is seen.
+    ///
+    /// The tree is walked over SFTP breadth first, so that shallow matches
+    /// come first, and works on servers without a shell. It stops at the
+    /// `limits`, with what it found so far, or with [`SshError::Cancelled`]
+    /// when `cancel` is set. Directories that cannot be read are skipped and
+    /// symbolic links are not followed.
+    pub fn find(
+        &self,
+        root: &str,
+        pattern: &str,
+        limits: FindLimits,
+        cancel: &AtomicBool,
+        progress: &mut dyn FnMut(RecursiveProgress),
+        found: &mut dyn FnMut(String),
+    ) -> Result<(), SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let pattern = pattern.to_lowercase();
+        let start = Instant::now();
+        let mut matches = 0;
+        let mut pending = VecDeque::from([(root.to_string(), 1)]);
+        while let Some((dir, depth)) = pending.pop_front() {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SshError::Cancelled("Search cancelled".to_string(), None));
+            }
+            if start.elapsed() >= limits.time_limit {
+                break;
+            }
+            progress(RecursiveProgress::Enumerating {
+                found: matches,
+                current: dir.clone(),
+            });
+            let Ok(entries) = sftp.readdir(os_path(&dir)) else {
+                continue;
+            };
+            for (entry_path, stat) in entries {
+                let entry_path = name_from_os(entry_path.as_os_str());
+                let name = entry_path.rsplit('/').next().unwrap_or(&entry_path);
+                if name.to_lowercase().contains(&pattern) {
+                    found(entry_path.clone());
+                    matches += 1;
+                    if matches >= limits.max_results {
+                        return Ok(());
+                    }
+                }
+                if stat.is_dir() && depth < limits.max_depth {
+                    pending.push_back((entry_path, depth + 1));
+                }
+            }
+        }
+        Ok(())
     }
 
-    pub fn fetch_stats(&self) -> Result<ServerStats, String> {
-        let session = self
-            .session
-            .as_ref()
-            .ok_or_else(|| "Session not initialized.".to_string())?;
+    /// Whether the session still works, checked with one cheap SFTP request.
+    ///
+    /// Only a broken connection counts as dead: a server that is slow to
+    /// answer or refuses the request is still there.
+    pub fn is_alive(&self) -> bool {
+        let Some(sftp) = &self.sftp else {
+            return false;
+        };
+        match sftp.realpath(&os_path(".")) {
+            Ok(_) => true,
+            Err(e) => match SshError::from_ssh2("Connection check failed", e) {
+                error @ SshError::Network(..) => {
+                    log::info!("{}", error);
+                    false
+                }
+                _ => true,
+            },
+        }
+    }
+
+    /// Measure the round-trip time of a single SFTP request.
+    ///
+    /// `realpath(".")` needs one request and one reply and no disk access on
+    /// the server, so the time is dominated by the network and the SSH stack.
+    pub fn measure_latency(&self) -> Result<Duration, SshError> {
+        let sftp = self.sftp.as_ref().ok_or(SshError::NotConnected)?;
+        let start = Instant::now();
+        sftp.realpath(&os_path("."))
+            .map_err(|e| SshError::from_ssh2("Failed to measure latency", e))?;
+        Ok(start.elapsed())
+    }
+
+    pub fn fetch_stats(&self) -> Result<ServerStats, SshError> {
+        let session = self.session.as_ref().ok_or(SshError::NotConnected)?;
 
-        let cpu_cmd = r#"top -bn1 | grep "Cpu(s)""#;
-        let mem_cmd = r#"free -h | grep "Mem:""#;
+        let (cpu_cmd, mem_cmd) = match self.remote_os {
+            RemoteOs::Linux | RemoteOs::Unknown => {
+                (r#"top -bn1 | grep "Cpu(s)""#, r#"free -h | grep "Mem:""#)
+            }
+            RemoteOs::MacOs => (
+                "top -l 1 -n 0 | grep 'CPU usage'",
+                "sysctl -n hw.pagesize hw.memsize; vm_stat",
+            ),
+            RemoteOs::Bsd => (
+                "top -b | grep '^CPU' | head -1",
+                "sysctl -n hw.pagesize hw.physmem vm.stats.vm.v_free_count \
+                 vm.stats.vm.v_inactive_count",
+            ),
+        };
         let disk_cmd = r#"df -h / | tail -1"#;
 
-        let raw_cpu = Self::run_command(session, cpu_cmd)?;
-        let raw_mem = Self::run_command(session, mem_cmd)?;
-        let raw_disk = Self::run_command(session, disk_cmd)?;
+        let raw_cpu = Self::run_command(session, cpu_cmd)?.stdout;
+        let raw_mem = Self::run_command(session, mem_cmd)?.stdout;
+        let raw_disk = Self::run_command(session, disk_cmd)?.stdout;
 
-        Ok(Self::process_stats(&raw_cpu, &raw_mem, &raw_disk))
+        Ok(Self::process_stats(
+            self.remote_os,
+            &raw_cpu,
+            &raw_mem,
+            &raw_disk,
+        ))
     }
 
-    fn process_stats(raw_cpu: &str, raw_mem: &str, raw_disk: &str) -> ServerStats {
-        let cpu_parts: Vec<&str> = raw_cpu.split_whitespace().collect();
-        let cpu_usage = format!(
-            "User: {}%, System: {}%, Idle: {}%, Steal: {}%",
-            cpu_parts[1], cpu_parts[3], cpu_parts[7], cpu_parts[15]
-        );
+    /// Fetch load average, memory and root disk usage with a single command.
+    pub fn fetch_quick_stats(&self) -> Result<QuickStats, SshError> {
+        let output = self.execute_command(
+            "LC_ALL=C uptime; echo ---; LC_ALL=C free -b; echo ---; \
+             LC_ALL=C df -Pk /; echo ---; nproc 2>/dev/null || getconf _NPROCESSORS_ONLN",
+        )?;
+        Ok(parse_quick_stats(&output.stdout))
+    }
 
-        let mem_parts: Vec<&str> = raw_mem.split_whitespace().collect();
-        let memory_usage = format!(
-            "Total: {}, Used: {}, Free: {}, Buffers/Cache: {}",
-            mem_parts[1], mem_parts[2], mem_parts[3], mem_parts[5]
-        );
+    /// Summarize the output of the statistics commands for `os`. Output that
+    /// cannot be parsed, e.g. from an unknown system, is shown as it is.
+    fn process_stats(os: RemoteOs, raw_cpu: &str, raw_mem: &str, raw_disk: &str) -> ServerStats {
+        let (cpu_usage, cpu_percent) = match parse_cpu_usage(raw_cpu) {
+            Some((usage, busy)) => (usage, Some(busy)),
+            None => (raw_output(raw_cpu), None),
+        };
+
+        let memory = match os {
+            RemoteOs::Linux | RemoteOs::Unknown => parse_free_memory(raw_mem),
+            RemoteOs::MacOs => parse_vm_stat_memory(raw_mem),
+            RemoteOs::Bsd => parse_sysctl_memory(raw_mem),
+        };
+        let (memory_usage, memory_percent) = match memory {
+            Some((usage, percent)) => (usage, percent),
+            None => (raw_output(raw_mem), None),
+        };
 
         let disk_parts: Vec<&str> = raw_disk.split_whitespace().collect();
-        let disk_usage = format!(
-            "Filesystem: {}, Total: {}, Used: {}, Available: {}, Usage: {}",
-            disk_parts[0], disk_parts[1], disk_parts[2], disk_parts[3], disk_parts[4]
-        );
+        let disk_usage = match disk_parts.get(..5) {
+            Some(&[filesystem, total, used, available, usage]) => format!(
+                "Filesystem: {}, Total: {}, Used: {}, Available: {}, Usage: {}",
+                filesystem, total, used, available, usage
+            ),
+            _ => raw_output(raw_disk),
+        };
 
         ServerStats {
             cpu_usage,
             memory_usage,
             disk_usage,
+            cpu_percent,
+            memory_percent,
         }
     }
 }
+
+/// Command output shown as it is when it could not be parsed
+fn raw_output(output: &str) -> String {
+    match output.trim() {
+        "" => "unavailable".to_string(),
+        output => output.to_string(),
+    }
+}
+
+/// Summarize a CPU line of `top` as text and the busy percentage (100 - idle).
+///
+/// Linux prints `%Cpu(s):  1.2 us,  0.5 sy, ... 98.1 id, ...` (older versions
+/// `1.2%us`), BSD `CPU:  0.4% user, ... 99.2% idle` and macOS
+/// `CPU usage: 5.12% user, 10.25% sys, 84.62% idle`.
+fn parse_cpu_usage(line: &str) -> Option<(String, f32)> {
+    let body = line.split_once(':').map_or(line, |(_, body)| body);
+    let fields: Vec<(&str, f32)> = body
+        .split(',')
+        .filter_map(|field| {
+            let field = field.trim();
+            let (value, name) = match field.split_once('%') {
+                Some((value, name)) => (value, name),
+                None => field.split_once(' ')?,
+            };
+            Some((name.trim(), value.trim().parse().ok()?))
+        })
+        .collect();
+    let find = |names: &[&str]| {
+        fields
+            .iter()
+            .find(|(name, _)| names.contains(name))
+            .map(|(_, value)| *value)
+    };
+
+    let idle = find(&["id", "idle"])?;
+    let mut usage = Vec::new();
+    if let Some(user) = find(&["us", "user"]) {
+        usage.push(format!("User: {}%", user));
+    }
+    if let Some(system) = find(&["sy", "sys", "system"]) {
+        usage.push(format!("System: {}%", system));
+    }
+    usage.push(format!("Idle: {}%", idle));
+    if let Some(steal) = find(&["st"]) {
+        usage.push(format!("Steal: {}%", steal));
+    }
+    Some((usage.join(", "), (100.0 - idle).clamp(0.0, 100.0)))
+}
+
+/// Memory use from the `Mem:` line of Linux `free -h`, as text and used percentage
+fn parse_free_memory(line: &str) -> Option<(String, Option<f32>)> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let &[_, total, used, free, _, cache, ..] = parts.as_slice() else {
+        return None;
+    };
+    let usage = format!(
+        "Total: {}, Used: {}, Free: {}, Buffers/Cache: {}",
+        total, used, free, cache
+    );
+    let percent = match (parse_human_size(total), parse_human_size(used)) {
+        (Some(total), Some(used)) if total > 0.0 => Some((used / total * 100.0) as f32),
+        _ => None,
+    };
+    Some((usage, percent))
+}
+
+/// Memory use on macOS from the page size and memory size printed by
+/// `sysctl`, followed by `vm_stat`. Free, inactive and speculative pages
+/// count as available.
+fn parse_vm_stat_memory(output: &str) -> Option<(String, Option<f32>)> {
+    let mut lines = output.lines();
+    let page_size: u64 = lines.next()?.trim().parse().ok()?;
+    let total: u64 = lines.next()?.trim().parse().ok()?;
+    let mut available_pages = 0;
+    for line in lines {
+        let Some((name, count)) = line.split_once(':') else {
+            continue;
+        };
+        if matches!(
+            name.trim(),
+            "Pages free" | "Pages inactive" | "Pages speculative"
+        ) {
+            available_pages = count
+                .trim()
+                .trim_end_matches('.')
+                .parse::<u64>()
+                .ok()?
+                .saturating_add(available_pages);
+        }
+    }
+    Some(memory_summary(
+        total,
+        available_pages.saturating_mul(page_size),
+    ))
+}
+
+/// Memory use on the BSDs from `sysctl` values, one per line: page size,
+/// physical memory, free pages and inactive pages
+fn parse_sysctl_memory(output: &str) -> Option<(String, Option<f32>)> {
+    let values: Vec<u64> = output
+        .lines()
+        .map(|line| line.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let &[page_size, total, free_pages, inactive_pages] = values.as_slice() else {
+        return None;
+    };
+    Some(memory_summary(
+        total,
+        free_pages
+            .saturating_add(inactive_pages)
+            .saturating_mul(page_size),
+    ))
+}
+
+/// Describe memory use from the total and the available bytes
+fn memory_summary(total: u64, available: u64) -> (String, Option<f32>) {
+    let used = total.saturating_sub(available);
+    let usage = format!(
+        "Total: {}, Used: {}, Free: {}",
+        human_size(total),
+        human_size(used),
+        human_size(available.min(total))
+    );
+    let percent = (total > 0).then(|| (used as f64 / total as f64 * 100.0) as f32);
+    (usage, percent)
+}
+
+/// Format a byte count like `free -h`, e.g. `7.7Gi`
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "Ki", "Mi", "Gi", "Ti"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+/// Parse the sections of the `fetch_quick_stats` command, separated by `---` lines.
+fn parse_quick_stats(output: &str) -> QuickStats {
+    let mut sections = output.split("---\n");
+    let uptime = sections.next().unwrap_or_default();
+    let free = sections.next().unwrap_or_default();
+    let df = sections.next().unwrap_or_default();
+    let nproc = sections.next().unwrap_or_default();
+
+    // Linux prints "load average: 0.10, 0.20, 0.30", BSD and macOS "load averages: 0.10 0.20 0.30"
+    let load = uptime
+        .rfind("load average")
+        .and_then(|start| uptime[start..].split_once(':'))
+        .and_then(|(_, values)| {
+            let values: Vec<f32> = values
+                .split([',', ' '])
+                .filter_map(|value| value.trim().parse().ok())
+                .collect();
+            Some([*values.first()?, *values.get(1)?, *values.get(2)?])
+        });
+
+    let memory = free
+        .lines()
+        .find(|line| line.starts_with("Mem:"))
+        .and_then(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            Some((fields.get(2)?.parse().ok()?, fields.get(1)?.parse().ok()?))
+        });
+
+    // POSIX output: filesystem, 1024-blocks, used, available, capacity, mount point
+    let disk = df.lines().nth(1).and_then(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let total: u64 = fields.get(1)?.parse().ok()?;
+        let used: u64 = fields.get(2)?.parse().ok()?;
+        Some((used.saturating_mul(1024), total.saturating_mul(1024)))
+    });
+
+    QuickStats {
+        load,
+        cpus: nproc.trim().parse().ok(),
+        memory,
+        disk,
+    }
+}
+
+/// Parse a size as printed by `free -h`/`df -h` (e.g. `7.7Gi`, `512M`, `0B`) into bytes.
+fn parse_human_size(value: &str) -> Option<f64> {
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.replace(',', ".").parse().ok()?;
+    let multiplier = match unit.trim_end_matches('B').trim_end_matches('i') {
+        "" => 1.0,
+        "K" | "k" => 1024.0,
+        "M" => 1024.0_f64.powi(2),
+        "G" => 1024.0_f64.powi(3),
+        "T" => 1024.0_f64.powi(4),
+        "P" => 1024.0_f64.powi(5),
+        _ => return None,
+    };
+    Some(number * multiplier)
+}
+
+/// Recursively collect the entries below a local directory as (path, is_dir),
+/// with every directory listed before its contents.
+fn walk_local(
+    root: &Path,
+    progress: &mut dyn FnMut(RecursiveProgress),
+) -> Result<Vec<(PathBuf, bool)>, SshError> {
+    let mut result = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        progress(RecursiveProgress::Enumerating {
+            found: result.len(),
+            current: dir.to_string_lossy().to_string(),
+        });
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| SshError::from_io("Failed to read local directory", e))?;
+        for entry in entries {
+            let entry =
+                entry.map_err(|e| SshError::from_io("Failed to read local directory", e))?;
+            let is_dir = entry
+                .file_type()
+                .map_err(|e| SshError::from_io("Failed to read local directory", e))?
+                .is_dir();
+            if is_dir {
+                pending.push(entry.path());
+            }
+            result.push((entry.path(), is_dir));
+        }
+    }
+    Ok(result)
+}
+
+/// Whether a failed read or write of a remote file may succeed when tried
+/// again. Errors from `ssh2` files only keep a coarse kind: `Other` covers
+/// generic SFTP failures as well as a lost session, which fails again quickly.
+fn is_transient(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::TimedOut
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::Other
+    ) && !is_remote_disk_full(e)
+}
+
+/// Whether an error of writing to a remote file says that the disk or the
+/// quota is full. ssh2 passes only the message of the SFTP status on to `std::io`.
+fn is_remote_disk_full(e: &io::Error) -> bool {
+    let message = e.to_string();
+    message.contains("no space on filesystem") || message.contains("quota exceeded")
+}
+
+/// Turn the error of a failed write to `file` into [`SshError::DiskFull`] if
+/// its filesystem has no space left. OpenSSH reports a full disk as a plain
+/// failure, so the free space is asked for; servers that cannot tell keep the
+/// original error.
+fn check_disk_full(file: &mut ssh2::File, error: SshError) -> SshError {
+    match error {
+        SshError::Sftp(msg, details) | SshError::Io(msg, details)
+            if file.statvfs().is_ok_and(|vfs| vfs.f_bavail == 0) =>
+        {
+            let note = "no space left on the remote filesystem";
+            let details = match details {
+                Some(details) => format!("{}\n{}", details, note),
+                None => note.to_string(),
+            };
+            SshError::DiskFull(msg, Some(details))
+        }
+        error => error,
+    }
+}
+
+/// Give a rewritten remote file back the permission bits `perm` it had before.
+///
+/// A new file is created with the server's umask applied, which can drop group
+/// write or set-id bits, and some servers apply the default mode when an existing
+/// file is truncated, which strips the executable bit of an edited script. The
+/// content is saved by then, so a failure is only logged.
+fn restore_mode(sftp: &Sftp, remote_path: &str, perm: Option<u32>) {
+    let Some(perm) = perm.map(|perm| perm & 0o7777) else {
+        return;
+    };
+    let current = sftp
+        .stat(&os_path(remote_path))
+        .ok()
+        .and_then(|stat| stat.perm);
+    if current.map(|current| current & 0o7777) == Some(perm) {
+        return;
+    }
+    let mode = FileStat {
+        size: None,
+        uid: None,
+        gid: None,
+        perm: Some(perm),
+        atime: None,
+        mtime: None,
+    };
+    if let Err(e) = sftp.setstat(&os_path(remote_path), mode) {
+        log::warn!(
+            "Failed to restore the mode {:04o} of {}: {}",
+            perm,
+            display_name(remote_path),
+            e
+        );
+    }
+}
+
+/// Flush a remote file's written data to disk with the `fsync@openssh.com`
+/// extension. Servers without it cannot be asked, which is only logged.
+fn fsync(file: &mut ssh2::File) -> Result<(), SshError> {
+    match file.fsync() {
+        Ok(()) => Ok(()),
+        Err(e) if e.code() == ErrorCode::SFTP(LIBSSH2_FX_OP_UNSUPPORTED) => {
+            log::debug!("The server does not support fsync@openssh.com; not flushing to disk");
+            Ok(())
+        }
+        Err(e) => Err(check_disk_full(
+            file,
+            SshError::from_ssh2("Failed to flush temporary file to disk", e),
+        )),
+    }
+}
+
+/// Keeps a transfer at a byte rate by pausing once it gets ahead of it
+struct Throttle {
+    /// 0 for no limit
+    bytes_per_sec: u64,
+    start: Instant,
+    /// Bytes moved since `start`
+    sent: u64,
+}
+
+impl Throttle {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            start: Instant::now(),
+            sent: 0,
+        }
+    }
+
+    /// Count `bytes` as transferred and pause until the rate is back within
+    /// the limit, or until `cancel` is set
+    fn pace(&mut self, bytes: usize, cancel: &AtomicBool) {
+        if self.bytes_per_sec == 0 {
+            return;
+        }
+        self.sent += bytes as u64;
+        let due = Duration::from_secs_f64(self.sent as f64 / self.bytes_per_sec as f64);
+        let elapsed = self.start.elapsed();
+        // After a stall, start over instead of making up for the lost time in a burst
+        if elapsed > due + Duration::from_secs(1) {
+            self.start = Instant::now();
+            self.sent = 0;
+            return;
+        }
+        while let Some(wait) = due.checked_sub(self.start.elapsed()) {
+            if wait.is_zero() || cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            thread::sleep(wait.min(THROTTLE_STEP));
+        }
+    }
+}
+
+/// Read the entries of a remote directory one at a time, stopping after `limit`
+/// of them. Unlike `Sftp::readdir`, nothing is buffered beyond the entries kept.
+fn read_directory(sftp: &Sftp, path: &str, limit: usize) -> Result<Vec<FileEntry>, ssh2::Error> {
+    let mut dir = sftp.opendir(os_path(path))?;
+    let mut entries = Vec::new();
+    while entries.len() < limit {
+        let (name, stat) = match dir.readdir() {
+            Ok(entry) => entry,
+            Err(e) if e.code() == ErrorCode::Session(LIBSSH2_ERROR_FILE) => break,
+            Err(e) => return Err(e),
+        };
+        let name = name_from_os(name.as_os_str());
+        if name == "." || name == ".." {
+            continue;
+        }
+        entries.push(FileEntry {
+            name,
+            is_dir: stat.is_dir(),
+            size: stat.size.unwrap_or(0),
+            mtime: stat.mtime,
+        });
+    }
+    Ok(entries)
+}
+
+/// Resolve `.`, `..` and repeated or trailing slashes in a remote path
+/// without contacting the server, e.g. `/home//user/./docs/../` becomes `/home/user`.
+pub fn normalize_remote_path(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.last().is_some_and(|last| *last != "..") {
+                    parts.pop();
+                } else if !path.starts_with('/') {
+                    parts.push("..");
+                }
+            }
+            _ => parts.push(part),
+        }
+    }
+    let joined = parts.join("/");
+    if path.starts_with('/') {
+        format!("/{}", joined)
+    } else if joined.is_empty() {
+        ".".to_string()
+    } else {
+        joined
+    }
+}
+
+/// Whether two remote paths name the same location once normalized, e.g.
+/// `/srv/www/` and `/srv//www`, so that renaming one to the other does nothing
+pub fn is_same_remote_path(a: &str, b: &str) -> bool {
+    normalize_remote_path(a) == normalize_remote_path(b)
+}
+
+/// Resolve a path typed by the user against the directory `base`: absolute
+/// paths are only normalized, others are taken relative to `base`, e.g.
+/// `../etc` in `/home/user` becomes `/home/etc`.
+///
+/// Windows servers running Win32-OpenSSH show `C:\Users` as `/C:/Users`. There,
+/// or when a drive is typed, backslashes are separators and `C:\Users` or
+/// `C:/Users` is the absolute path `/C:/Users`.
+pub fn resolve_remote_path(base: &str, path: &str) -> String {
+    let windows = windows_drive(base).is_some() || windows_drive(path).is_some();
+    let path = if windows {
+        path.replace('\\', "/")
+    } else {
+        path.to_string()
+    };
+    let resolved = if path.starts_with('/') {
+        normalize_remote_path(&path)
+    } else if windows && windows_drive(&path).is_some() {
+        normalize_remote_path(&format!("/{}", path))
+    } else {
+        normalize_remote_path(&format!("{}/{}", base.trim_end_matches('/'), path))
+    };
+    // `/C:` without the slash may name the current directory on that drive
+    if is_drive_root(&resolved) {
+        format!("{}/", resolved.trim_end_matches('/'))
+    } else {
+        resolved
+    }
+}
+
+/// The directory above a remote path, e.g. `/srv/www` for `/srv/www/index.html`,
+/// or `None` for the root and for names without a directory. The parent of
+/// `/C:/Users` on a Windows server is the drive's root `/C:/`.
+pub fn remote_parent(path: &str) -> Option<&str> {
+    match path.trim_end_matches('/').rsplit_once('/') {
+        Some(("", _)) => None,
+        Some((parent, _)) if is_drive_root(parent) => Some(&path[..=parent.len()]),
+        Some((parent, _)) => Some(parent),
+        None => None,
+    }
+}
+
+/// The drive letter and colon that a Windows path starts with, as shown by
+/// Win32-OpenSSH (`/C:/Users`) or as typed on Windows (`C:\Users`)
+fn windows_drive(path: &str) -> Option<&str> {
+    let rest = path.strip_prefix('/').unwrap_or(path);
+    match rest.as_bytes() {
+        [letter, b':', ..]
+            if letter.is_ascii_alphabetic()
+                && matches!(rest.as_bytes().get(2), None | Some(b'/' | b'\\')) =>
+        {
+            Some(&rest[..2])
+        }
+        _ => None,
+    }
+}
+
+/// Whether `path` is the root of a Windows drive, such as `/C:` or `/C:/`
+fn is_drive_root(path: &str) -> bool {
+    path.starts_with('/')
+        && windows_drive(path)
+            .is_some_and(|drive| path.trim_end_matches('/').len() == drive.len() + 1)
+}
+
+/// The path of a remote entry relative to `root`, without a leading slash
+fn remote_relative<'a>(root: &str, path: &'a str) -> &'a str {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .trim_start_matches('/')
+}
+
+/// The path of a local entry relative to `root`, with forward slashes
+fn local_relative(root: &str, path: &Path) -> String {
+    name_from_os(path.strip_prefix(os_path(root)).unwrap_or(path).as_os_str()).replace('\\', "/")
+}
+
+/// Size and modification time (seconds since the epoch) of a local file,
+/// or `None` if it cannot be read.
+fn local_size_and_mtime(path: &Path) -> Option<(u64, Option<u64>)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|age| age.as_secs());
+    Some((metadata.len(), mtime))
+}
+
+/// Whether the destination of a sync, given as (size, mtime) like the source,
+/// already matches it: same size and at least as new.
+fn is_unchanged(
+    source: Option<(u64, Option<u64>)>,
+    destination: Option<(u64, Option<u64>)>,
+) -> bool {
+    match (source, destination) {
+        (Some((source_size, Some(source_mtime))), Some((dest_size, Some(dest_mtime)))) => {
+            source_size == dest_size && dest_mtime >= source_mtime
+        }
+        _ => false,
+    }
+}
+
+/// Convert a name or path read from the server to a `String` without losing
+/// bytes that are not valid UTF-8.
+///
+/// Each such byte becomes a private-use character that [`os_path`] turns
+/// back into the original byte, so the entry can still be opened, renamed or
+/// deleted, and keeps its name when transferred. A name that really contains
+/// one of those characters has its UTF-8 bytes escaped the same way, so it
+/// reads back unchanged. Use [`display_name`] to show it.
+///
+/// Other clients than Unix ones only get the lossy name.
+pub fn name_from_os(name: &OsStr) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        name_from_bytes(name.as_bytes())
+    }
+    #[cfg(not(unix))]
+    {
+        name.to_string_lossy().to_string()
+    }
+}
+
+/// Like [`name_from_os`], for names read as bytes, such as from the output
+/// of `ls`
+fn name_from_bytes(name: &[u8]) -> String {
+    #[cfg(unix)]
+    {
+        let push_raw = |out: &mut String, bytes: &[u8]| {
+            out.extend(
+                bytes
+                    .iter()
+                    .filter_map(|&byte| char::from_u32(RAW_BYTE_BASE + byte as u32)),
+            )
+        };
+        let mut out = String::new();
+        for chunk in name.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                if is_raw_byte(c) {
+                    push_raw(&mut out, c.encode_utf8(&mut [0; 4]).as_bytes());
+                } else {
+                    out.push(c);
+                }
+            }
+            push_raw(&mut out, chunk.invalid());
+        }
+        out
+    }
+    #[cfg(not(unix))]
+    {
+        String::from_utf8_lossy(name).into_owned()
+    }
+}
+
+/// The path to hand to SFTP or to the local file system for `path`, restoring
+/// the raw bytes kept by [`name_from_os`].
+fn os_path(path: &str) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        PathBuf::from(std::ffi::OsString::from_vec(raw_bytes(path)))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(path)
+    }
+}
+
+/// Whether `c` is one of the private-use characters that carry a raw byte
+fn is_raw_byte(c: char) -> bool {
+    (RAW_BYTE_BASE..=RAW_BYTE_BASE + 0xFF).contains(&(c as u32))
+}
+
+/// The bytes of the name kept by [`name_from_os`]
+fn raw_bytes(name: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(name.len());
+    for c in name.chars() {
+        if is_raw_byte(c) {
+            bytes.push((c as u32 - RAW_BYTE_BASE) as u8);
+        } else {
+            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+    }
+    bytes
+}
+
+/// `name` with the raw bytes kept by [`name_from_os`] shown as replacement characters
+pub fn display_name(name: &str) -> Cow<'_, str> {
+    if name.chars().any(is_raw_byte) {
+        Cow::Owned(String::from_utf8_lossy(&raw_bytes(name)).into_owned())
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// A timeout as libssh2 expects it, in milliseconds with 0 for none
+fn timeout_millis(timeout: Option<Duration>) -> u32 {
+    timeout.map_or(0, |timeout| {
+        u32::try_from(timeout.as_millis())
+            .unwrap_or(u32::MAX)
+            .max(1)
+    })
+}
+
+/// Open a TCP connection to `addr`, trying each address it resolves to for at most `timeout`
+fn connect_with_timeout(addr: &str, timeout: Duration) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(tcp) => return Ok(tcp),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error
+        .unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no addresses found for host")))
+}
+
+/// Decode the complete UTF-8 text at the start of `bytes`, leaving a character
+/// cut off at the end of the buffer for the next read. Invalid bytes are replaced.
+fn take_complete_utf8(bytes: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+    let mut consumed = 0;
+    loop {
+        match std::str::from_utf8(&bytes[consumed..]) {
+            Ok(rest) => {
+                text.push_str(rest);
+                consumed = bytes.len();
+                break;
+            }
+            Err(e) => {
+                let valid = consumed + e.valid_up_to();
+                text.push_str(&String::from_utf8_lossy(&bytes[consumed..valid]));
+                consumed = valid;
+                match e.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        consumed += len;
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+    bytes.drain(..consumed);
+    text
+}
+
+/// The SSH library the program is built with, as required in Cargo.toml.
+/// libssh2 itself does not report its version unless linked dynamically.
+pub const SSH_LIBRARY_VERSION: &str = "ssh2 0.9.4";
+
+/// Quote a string for safe use as a single POSIX shell word.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Read a line of `grep -n --null` output: the path, a NUL, then the line
+/// number and the text separated by a colon
+fn parse_grep_line(line: &str) -> Option<GrepMatch> {
+    let (path, rest) = line.split_once('\0')?;
+    let (number, text) = rest.split_once(':')?;
+    let text = text.trim_end_matches('\r');
+    Some(GrepMatch {
+        path: path.to_string(),
+        line: number.parse().ok()?,
+        text: match text.char_indices().nth(MAX_GREP_LINE_CHARS) {
+            Some((cut, _)) => format!("{}…", &text[..cut]),
+            None => text.to_string(),
+        },
+    })
+}
+
+/// Render `st_mode` bits the way `ls -l` does, e.g. `drwxr-xr-x`
+pub fn symbolic_mode(perm: u32) -> String {
+    let kind = match perm & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o020000 => 'c',
+        0o060000 => 'b',
+        0o010000 => 'p',
+        0o140000 => 's',
+        _ => '-',
+    };
+    let mut mode = String::with_capacity(10);
+    mode.push(kind);
+    // Owner, group and others, each with its set-id or sticky bit
+    for (shift, special, special_char) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = (perm >> shift) & 0o7;
+        mode.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        mode.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        mode.push(match (bits & 0o1 != 0, perm & special != 0) {
+            (true, true) => special_char,
+            (false, true) => special_char.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    mode
+}
+
+/// Split off the first `n` whitespace-separated fields of `line`,
+/// returning them and the remainder with its inner spacing preserved.
+fn split_fields(line: &str, n: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(n);
+    let mut rest = line.trim_start();
+    for _ in 0..n {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    Some((fields, rest))
+}
+
+/// Parse `ls -la` output into file entries.
+///
+/// With `epoch_time` the date is expected as a single Unix timestamp field
+/// (`--time-style=+%s`), otherwise as the default three date fields.
+fn parse_ls_output(output: &str, epoch_time: bool) -> Vec<FileEntry> {
+    let field_count = if epoch_time { 6 } else { 8 };
+    output
+        .lines()
+        .filter(|line| !line.starts_with("total "))
+        .filter_map(|line| {
+            let (fields, name) = split_fields(line, field_count)?;
+            // Symlinks are printed as "name -> target"
+            let name = if fields[0].starts_with('l') {
+                name.split(" -> ").next().unwrap_or(name)
+            } else {
+                name
+            };
+            if name.is_empty() || name == "." || name == ".." {
+                return None;
+            }
+            Some(FileEntry {
+                name: name.to_string(),
+                is_dir: fields[0].starts_with('d'),
+                size: fields[4].parse().unwrap_or(0),
+                mtime: if epoch_time {
+                    fields[5].parse().ok()
+                } else {
+                    None
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_disk_sizes_do_not_overflow() {
+        let output = format!(
+            "---\n---\nFilesystem 1024-blocks Used Available Capacity Mounted on\n\
+/dev/sda1 {} 1 0 100% /\n---\n",
+            u64::MAX
+        );
+        assert_eq!(parse_quick_stats(&output).disk, Some((1024, u64::MAX)));
+    }
+
+    #[test]
+    fn huge_memory_counts_do_not_overflow() {
+        let max = u64::MAX.to_string();
+        let vm_stat = format!(
+            "16384\n17179869184\nPages free: {}.\nPages inactive: {}.\n",
+            max, max
+        );
+        let (_, percent) = parse_vm_stat_memory(&vm_stat).unwrap();
+        assert_eq!(percent, Some(0.0));
+        let sysctl = format!("4096\n8589934592\n{}\n{}\n", max, max);
+        let (_, percent) = parse_sysctl_memory(&sysctl).unwrap();
+        assert_eq!(percent, Some(0.0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn raw_names_read_back_unchanged() {
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+        let names: [&[u8]; 4] = [
+            b"plain.txt",
+            b"caf\xe9.txt",
+            // U+10FF41, which is also how the byte 0x41 is carried
+            "x\u{10FF41}y".as_bytes(),
+            b"\xff\xf4\x8f\xbd\x81",
+        ];
+        for name in names {
+            let kept = name_from_os(OsStr::from_bytes(name));
+            assert_eq!(os_path(&kept).into_os_string().into_vec(), name);
+        }
+        assert_eq!(name_from_os(OsStr::new("plain.txt")), "plain.txt");
+    }
+
+    #[test]
+    fn ls_output_keeps_raw_names_and_skips_dot_entries() {
+        let output = name_from_bytes(
+            b"total 8\n\
+drwxr-xr-x 2 root root 4096 1700000000 .\n\
+drwxr-xr-x 3 root root 4096 1700000000 ..\n\
+-rw-r--r-- 1 root root 12 1700000001 caf\xe9.txt\n\
+lrwxrwxrwx 1 root root 4 1700000002 link -> dest\n",
+        );
+        let entries = parse_ls_output(&output, true);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].size, 12);
+        assert_eq!(entries[0].mtime, Some(1_700_000_001));
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            assert_eq!(
+                os_path(&entries[0].name).into_os_string().into_vec(),
+                b"caf\xe9.txt"
+            );
+        }
+        assert_eq!(entries[1].name, "link");
+        // An empty directory lists only `.` and `..`
+        let empty = "total 0\n\
+drwxr-xr-x 2 root root 4096 1700000000 .\n\
+drwxr-xr-x 3 root root 4096 1700000000 ..\n";
+        assert!(parse_ls_output(empty, true).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn display_name_replaces_only_invalid_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+        let kept = name_from_os(OsStr::from_bytes(b"caf\xe9.txt"));
+        assert_eq!(display_name(&kept), "caf\u{FFFD}.txt");
+        let kept = name_from_os(OsStr::new("x\u{10FF41}y"));
+        assert_ne!(kept, "x\u{10FF41}y");
+        assert_eq!(display_name(&kept), "x\u{10FF41}y");
+        assert!(matches!(display_name("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn error_details_stay_apart_from_a_multi_line_message() {
+        let error = SshError::Sftp(
+            "first line\nsecond line".to_string(),
+            Some("exit status 1".to_string()),
+        );
+        assert_eq!(error.message(), "first line\nsecond line");
+        assert_eq!(error.details(), Some("exit status 1"));
+        assert_eq!(error.to_string(), "first line\nsecond line\nexit status 1");
+
+        let plain = SshError::NotFound("line one\nline two".to_string(), None);
+        assert_eq!(plain.message(), "line one\nline two");
+        assert_eq!(plain.details(), None);
+    }
+
+    #[test]
+    fn error_codes_go_to_the_details() {
+        let error = SshError::from_ssh2(
+            "Failed to open file",
+            ssh2::Error::new(ErrorCode::SFTP(LIBSSH2_FX_NO_SUCH_FILE), "no such file"),
+        );
+        assert_eq!(error.message(), "Failed to open file: no such file");
+        assert_eq!(error.details(), Some("SFTP status code 2"));
+        assert!(matches!(error, SshError::NotFound(..)));
+
+        let error = SshError::from_io("Failed to read", io::Error::from_raw_os_error(libc::EACCES));
+        assert!(matches!(error, SshError::PermissionDenied(..)));
+        assert_eq!(
+            error.details(),
+            Some(format!("OS error code {}", libc::EACCES).as_str())
+        );
+    }
+
+    #[test]
+    fn with_context_keeps_the_details() {
+        let error = SshError::Auth("rejected".to_string(), Some("code -18".to_string()))
+            .with_context("Login failed");
+        assert_eq!(error.message(), "Login failed: rejected");
+        assert_eq!(error.details(), Some("code -18"));
+
+        let error = SshError::NotConnected.with_context("Failed to list");
+        assert_eq!(error, SshError::NotConnected);
+        assert_eq!(error.message(), "Not connected");
+    }
+
+    #[test]
+    fn normalize_remote_path_resolves_dots_and_slashes() {
+        assert_eq!(
+            normalize_remote_path("/home//user/./docs/../"),
+            "/home/user"
+        );
+        assert_eq!(normalize_remote_path("/"), "/");
+        assert_eq!(normalize_remote_path("/.."), "/");
+        assert_eq!(normalize_remote_path("/a/b/../../.."), "/");
+        assert_eq!(normalize_remote_path("a/./b/"), "a/b");
+        assert_eq!(normalize_remote_path("../a"), "../a");
+        assert_eq!(normalize_remote_path("a/../.."), "..");
+        assert_eq!(normalize_remote_path(""), ".");
+        assert_eq!(normalize_remote_path("./"), ".");
+    }
+
+    #[test]
+    fn same_remote_path_after_normalization() {
+        assert!(is_same_remote_path("/srv/www", "/srv/www"));
+        assert!(is_same_remote_path("/srv/www/", "/srv//www"));
+        assert!(is_same_remote_path(
+            "/srv/www/index.html",
+            "/srv/./www/x/../index.html"
+        ));
+        assert!(!is_same_remote_path("/srv/www", "/srv/www2"));
+        assert!(!is_same_remote_path("/srv/www", "srv/www"));
+    }
+
+    #[test]
+    fn resolve_relative_paths_against_base() {
+        assert_eq!(
+            resolve_remote_path("/home/user", "subdir"),
+            "/home/user/subdir"
+        );
+        assert_eq!(
+            resolve_remote_path("/home/user/", "subdir/"),
+            "/home/user/subdir"
+        );
+        assert_eq!(resolve_remote_path("/home/user", "../etc"), "/home/etc");
+        assert_eq!(
+            resolve_remote_path("/home/user", "./a/../b"),
+            "/home/user/b"
+        );
+        assert_eq!(resolve_remote_path("/home/user", "."), "/home/user");
+        assert_eq!(resolve_remote_path("/home/user", ""), "/home/user");
+    }
+
+    #[test]
+    fn resolve_absolute_paths_and_root() {
+        assert_eq!(resolve_remote_path("/home/user", "/var//log/"), "/var/log");
+        assert_eq!(resolve_remote_path("/home/user", "/"), "/");
+        assert_eq!(resolve_remote_path("/", "etc"), "/etc");
+        assert_eq!(resolve_remote_path("/", ".."), "/");
+        assert_eq!(resolve_remote_path("/home", "../../.."), "/");
+        // Backslashes are ordinary characters in Unix names
+        assert_eq!(resolve_remote_path("/home/user", "a\\b"), "/home/user/a\\b");
+    }
+
+    #[test]
+    fn resolve_windows_paths() {
+        assert_eq!(resolve_remote_path("/C:/Users/me", "..\\x"), "/C:/Users/x");
+        assert_eq!(
+            resolve_remote_path("/C:/Users", "C:\\Windows\\System32"),
+            "/C:/Windows/System32"
+        );
+        assert_eq!(resolve_remote_path("/", "C:\\Users"), "/C:/Users");
+        assert_eq!(resolve_remote_path("/home/user", "D:/data"), "/D:/data");
+        assert_eq!(resolve_remote_path("/C:/Users", ".."), "/C:/");
+        assert_eq!(resolve_remote_path("/C:/", "D:"), "/D:/");
+        assert_eq!(resolve_remote_path("/C:/", ".."), "/");
+        assert_eq!(resolve_remote_path("/C:/x", "/"), "/");
+    }
+
+    #[test]
+    fn remote_parent_of_paths() {
+        assert_eq!(remote_parent("/srv/www/index.html"), Some("/srv/www"));
+        assert_eq!(remote_parent("/srv/www/"), Some("/srv"));
+        assert_eq!(remote_parent("/srv"), None);
+        assert_eq!(remote_parent("/"), None);
+        assert_eq!(remote_parent("name"), None);
+        assert_eq!(remote_parent("dir/name"), Some("dir"));
+        assert_eq!(remote_parent("/C:/Users"), Some("/C:/"));
+        assert_eq!(remote_parent("/C:/Users/me/"), Some("/C:/Users"));
+        assert_eq!(remote_parent("/C:/"), None);
+    }
+
+    #[test]
+    fn rename_onto_itself_is_skipped() {
+        // Without a session, any rename that reaches the server fails
+        let connection = SSHConnection::new("localhost", "user", "", 22);
+        assert!(connection.rename("/srv/www/a", "/srv//www/./a").is_ok());
+        assert!(connection
+            .rename_replacing("/srv/www/a", "/srv/www/a/")
+            .is_ok());
+        assert!(connection.rename("/srv/www/a", "/srv/www/b").is_err());
+        assert!(connection
+            .rename_replacing("/srv/www/a", "/srv/www/b")
+            .is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+
+/// Lines kept after they scroll off the top of the screen
+const SCROLLBACK_LINES: usize = 1000;
+
+/// The lines of a screen, each `cols` characters wide
+type Lines = Vec<Vec<char>>;
+
+/// Where the parser is within an escape sequence
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParseState {
+    Ground,
+    /// After ESC
+    Escape,
+    /// After ESC and a character set designator such as `(`, which takes one more character
+    Charset,
+    /// Inside a control sequence, `ESC [`
+    Csi,
+    /// Inside an operating system command, `ESC ]`, e.g. setting the window title
+    Osc,
+    /// After ESC inside an operating system command
+    OscEscape,
+}
+
+/// A minimal VT100-style screen for programs run in a pseudo-terminal.
+///
+/// It understands enough to show shells and full-screen programs such as
+/// `top`: printable text, carriage return, line feed, backspace, tab, and the
+/// control sequences that move the cursor, erase, insert and delete. Colors
+/// and other attributes are ignored.
+#[derive(Debug, Clone)]
+pub struct Screen {
+    cols: usize,
+    rows: usize,
+    lines: Lines,
+    row: usize,
+    col: usize,
+    saved_cursor: (usize, usize),
+    /// The main screen and cursor while a full-screen program uses the alternate one
+    main_screen: Option<(Lines, (usize, usize))>,
+    /// Lines scrolled off the top of the main screen, oldest first
+    scrollback: VecDeque<String>,
+    state: ParseState,
+    params: String,
+}
+
+impl Screen {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let cols = cols.max(1);
+        let rows = rows.max(1);
+        Self {
+            cols,
+            rows,
+            lines: vec![vec![' '; cols]; rows],
+            row: 0,
+            col: 0,
+            saved_cursor: (0, 0),
+            main_screen: None,
+            scrollback: VecDeque::new(),
+            state: ParseState::Ground,
+            params: String::new(),
+        }
+    }
+
+    /// The screen size as (columns, rows)
+    pub fn size(&self) -> (usize, usize) {
+        (self.cols, self.rows)
+    }
+
+    /// The cursor position as (row, column) on the screen
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col.min(self.cols - 1))
+    }
+
+    /// Lines that scrolled off the top, oldest first
+    pub fn scrollback(&self) -> impl Iterator<Item = &str> {
+        self.scrollback.iter().map(String::as_str)
+    }
+
+    /// The rows of the screen, top to bottom, each `cols` characters wide
+    pub fn rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.lines.iter().map(|line| line.iter().collect())
+    }
+
+    /// Change the screen size, keeping the text around the cursor
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        let cols = cols.max(1);
+        let rows = rows.max(1);
+        for line in &mut self.lines {
+            line.resize(cols, ' ');
+        }
+        // Drop lines from the top so that the cursor stays on screen
+        while self.lines.len() > rows {
+            if self.row > 0 {
+                self.scroll_up();
+                self.row -= 1;
+            } else {
+                self.lines.pop();
+            }
+        }
+        while self.lines.len() < rows {
+            self.lines.push(vec![' '; cols]);
+        }
+        self.cols = cols;
+        self.rows = rows;
+        self.row = self.row.min(rows - 1);
+        self.col = self.col.min(cols);
+    }
+
+    /// Process output of the program
+    pub fn feed(&mut self, text: &str) {
+        for c in text.chars() {
+            self.feed_char(c);
+        }
+    }
+
+    fn feed_char(&mut self, c: char) {
+        match self.state {
+            ParseState::Ground => match c {
+                '\x1b' => self.state = ParseState::Escape,
+                '\r' => self.col = 0,
+                '\n' | '\x0b' | '\x0c' => self.line_feed(),
+                '\x08' => self.col = self.col.min(self.cols - 1).saturating_sub(1),
+                '\t' => self.col = ((self.col / 8 + 1) * 8).min(self.cols - 1),
+                c if c < ' ' || c == '\x7f' => {}
+                c => self.put(c),
+            },
+            ParseState::Escape => {
+                self.state = ParseState::Ground;
+                match c {
+                    '[' => {
+                        self.params.clear();
+                        self.state = ParseState::Csi;
+                    }
+                    ']' => self.state = ParseState::Osc,
+                    '(' | ')' | '*' | '+' => self.state = ParseState::Charset,
+                    '7' => self.saved_cursor = (self.row, self.col),
+                    '8' => (self.row, self.col) = self.saved_cursor,
+                    'D' => self.line_feed(),
+                    'E' => {
+                        self.col = 0;
+                        self.line_feed();
+                    }
+                    'M' => self.reverse_line_feed(),
+                    'c' => *self = Screen::new(self.cols, self.rows),
+                    _ => {}
+                }
+            }
+            ParseState::Charset => self.state = ParseState::Ground,
+            ParseState::Csi => match c {
+                '0'..='?' => self.params.push(c),
+                ' '..='/' => {}
+                '@'..='~' => {
+                    self.state = ParseState::Ground;
+                    self.control_sequence(c);
+                }
+                // Not a valid sequence: drop it
+                _ => self.state = ParseState::Ground,
+            },
+            ParseState::Osc => match c {
+                '\x07' => self.state = ParseState::Ground,
+                '\x1b' => self.state = ParseState::OscEscape,
+                _ => {}
+            },
+            ParseState::OscEscape => self.state = ParseState::Ground,
+        }
+    }
+
+    /// Write a printable character at the cursor, wrapping at the right margin
+    fn put(&mut self, c: char) {
+        if self.col >= self.cols {
+            self.col = 0;
+            self.line_feed();
+        }
+        self.lines[self.row][self.col] = c;
+        self.col += 1;
+    }
+
+    fn line_feed(&mut self) {
+        if self.row + 1 < self.rows {
+            self.row += 1;
+        } else {
+            self.scroll_up();
+        }
+    }
+
+    fn reverse_line_feed(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+        } else {
+            self.lines.pop();
+            self.lines.insert(0, vec![' '; self.cols]);
+        }
+    }
+
+    /// Move every line up by one, keeping the top line in the scrollback
+    fn scroll_up(&mut self) {
+        let line = self.lines.remove(0);
+        if self.main_screen.is_none() {
+            if self.scrollback.len() == SCROLLBACK_LINES {
+                self.scrollback.pop_front();
+            }
+            self.scrollback
+                .push_back(line.iter().collect::<String>().trim_end().to_string());
+        }
+        self.lines.push(vec![' '; self.cols]);
+    }
+
+    /// Run the control sequence `ESC [ <params> <action>`
+    fn control_sequence(&mut self, action: char) {
+        let private = self.params.starts_with('?');
+        let params: Vec<usize> = self
+            .params
+            .trim_start_matches(['?', '>', '='])
+            .split(';')
+            .map(|param| param.parse().unwrap_or(0))
+            .collect();
+        let param = |index: usize| params.get(index).copied().unwrap_or(0);
+        // Most sequences treat a missing or zero count as one
+        let count = param(0).max(1);
+        let (last_row, last_col) = (self.rows - 1, self.cols - 1);
+        match action {
+            'A' => self.row = self.row.saturating_sub(count),
+            'B' | 'e' => self.row = (self.row + count).min(last_row),
+            'C' | 'a' => self.col = (self.col + count).min(last_col),
+            'D' => self.col = self.col.min(last_col).saturating_sub(count),
+            'E' => {
+                self.row = (self.row + count).min(last_row);
+                self.col = 0;
+            }
+            'F' => {
+                self.row = self.row.saturating_sub(count);
+                self.col = 0;
+            }
+            'G' | '`' => self.col = (count - 1).min(last_col),
+            'd' => self.row = (count - 1).min(last_row),
+            'H' | 'f' => {
+                self.row = (param(0).max(1) - 1).min(last_row);
+                self.col = (param(1).max(1) - 1).min(last_col);
+            }
+            'J' => {
+                let (row, col) = (self.row, self.col.min(self.cols));
+                match param(0) {
+                    0 => {
+                        self.lines[row][col..].fill(' ');
+                        for line in &mut self.lines[row + 1..] {
+                            line.fill(' ');
+                        }
+                    }
+                    1 => {
+                        for line in &mut self.lines[..row] {
+                            line.fill(' ');
+                        }
+                        self.lines[row][..(col + 1).min(self.cols)].fill(' ');
+                    }
+                    _ => {
+                        for line in &mut self.lines {
+                            line.fill(' ');
+                        }
+                    }
+                }
+            }
+            'K' => {
+                let col = self.col.min(self.cols);
+                let line = &mut self.lines[self.row];
+                match param(0) {
+                    0 => line[col..].fill(' '),
+                    1 => line[..(col + 1).min(self.cols)].fill(' '),
+                    _ => line.fill(' '),
+                }
+            }
+            'L' => {
+                for _ in 0..count.min(self.rows - self.row) {
+                    self.lines.pop();
+                    self.lines.insert(self.row, vec![' '; self.cols]);
+                }
+            }
+            'M' => {
+                for _ in 0..count.min(self.rows - self.row) {
+                    self.lines.remove(self.row);
+                    self.lines.push(vec![' '; self.cols]);
+                }
+            }
+            '@' => {
+                let col = self.col.min(last_col);
+                let line = &mut self.lines[self.row];
+                for _ in 0..count.min(self.cols - col) {
+                    line.pop();
+                    line.insert(col, ' ');
+                }
+            }
+            'P' => {
+                let col = self.col.min(last_col);
+                let line = &mut self.lines[self.row];
+                for _ in 0..count.min(self.cols - col) {
+                    line.remove(col);
+                    line.push(' ');
+                }
+            }
+            'X' => {
+                let col = self.col.min(last_col);
+                let end = (col + count).min(self.cols);
+                self.lines[self.row][col..end].fill(' ');
+            }
+            's' => self.saved_cursor = (self.row, self.col),
+            'u' => (self.row, self.col) = self.saved_cursor,
+            // Switch to and from the alternate screen of full-screen programs
+            'h' if private
+                && matches!(param(0), 47 | 1047 | 1049)
+                && self.main_screen.is_none() =>
+            {
+                let blank = vec![vec![' '; self.cols]; self.rows];
+                let main = std::mem::replace(&mut self.lines, blank);
+                self.main_screen = Some((main, (self.row, self.col)));
+            }
+            'l' if private && matches!(param(0), 47 | 1047 | 1049) => {
+                if let Some((mut main, cursor)) = self.main_screen.take() {
+                    for line in &mut main {
+                        line.resize(self.cols, ' ');
+                    }
+                    main.resize(self.rows, vec![' '; self.cols]);
+                    self.lines = main;
+                    self.row = cursor.0.min(last_row);
+                    self.col = cursor.1.min(self.cols);
+                }
+            }
+            // Colors, modes, scrolling regions, reports: not supported
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+
+/// Encodings offered in the editor, besides the detected one
+pub const ENCODINGS: &[&Encoding] = &[
+    encoding_rs::UTF_8,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::ISO_8859_15,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::KOI8_R,
+    encoding_rs::WINDOWS_1256,
+    encoding_rs::GBK,
+    encoding_rs::GB18030,
+    encoding_rs::BIG5,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::EUC_JP,
+    encoding_rs::EUC_KR,
+];
+
+/// A file read for editing, with the encoding used to decode it
+pub struct DecodedText {
+    /// The raw file content, kept to re-interpret it in another encoding
+    pub bytes: Vec<u8>,
+    pub encoding: &'static Encoding,
+    pub text: String,
+}
+
+/// Guess the encoding of `bytes`: a byte order mark wins, then valid UTF-8,
+/// then whatever `chardetng` considers most likely.
+pub fn detect(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return encoding_rs::UTF_8;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
+
+/// Decode `bytes` with the detected encoding
+pub fn decode_detected(bytes: Vec<u8>) -> DecodedText {
+    let encoding = detect(&bytes);
+    let text = decode(&bytes, encoding);
+    DecodedText {
+        bytes,
+        encoding,
+        text,
+    }
+}
+
+/// Decode `bytes` with `encoding` if given, otherwise with the detected one.
+/// A byte order mark always wins.
+pub fn decode_with_default(bytes: Vec<u8>, default: Option<&'static Encoding>) -> DecodedText {
+    match default {
+        Some(encoding) if Encoding::for_bom(&bytes).is_none() => DecodedText {
+            text: decode(&bytes, encoding),
+            bytes,
+            encoding,
+        },
+        _ => decode_detected(bytes),
+    }
+}
+
+/// Decode `bytes` as `encoding`, replacing malformed sequences. A byte order
+/// mark of `encoding` is left out of the text; see [`encode_document`].
+pub fn decode(bytes: &[u8], encoding: &'static Encoding) -> String {
+    encoding.decode_with_bom_removal(bytes).0.into_owned()
+}
+
+/// Encode `text` as `encoding`, failing if some characters cannot be represented.
+///
+/// `encoding_rs` only decodes UTF-16, so it is encoded here instead.
+pub fn encode(text: &str, encoding: &'static Encoding) -> Result<Vec<u8>, String> {
+    if encoding == encoding_rs::UTF_16LE {
+        return Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect());
+    }
+    if encoding == encoding_rs::UTF_16BE {
+        return Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect());
+    }
+    let (bytes, used, had_errors) = encoding.encode(text);
+    if had_errors {
+        return Err(format!(
+            "Some characters cannot be represented in {}",
+            used.name()
+        ));
+    }
+    Ok(bytes.into_owned())
+}
+
+/// Encode the whole text of a file, like [`encode`], starting with the byte
+/// order mark that `original`, the file as loaded, starts with, so that
+/// saving an unchanged file writes back the same bytes
+pub fn encode_document(
+    text: &str,
+    encoding: &'static Encoding,
+    original: &[u8],
+) -> Result<Vec<u8>, String> {
+    let mut bytes = match Encoding::for_bom(original) {
+        Some((bom_encoding, length)) if bom_encoding == encoding => original[..length].to_vec(),
+        _ => Vec::new(),
+    };
+    bytes.extend(encode(text, encoding)?);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode as detected and encode again, as saving an unchanged file does
+    fn round_trip(bytes: &[u8]) -> Vec<u8> {
+        let decoded = decode_detected(bytes.to_vec());
+        encode_document(&decoded.text, decoded.encoding, &decoded.bytes).unwrap()
+    }
+
+    #[test]
+    fn unchanged_files_keep_their_bytes() {
+        let files: [&[u8]; 6] = [
+            b"plain ascii\n",
+            "caf\u{e9} \u{4e2d}\n".as_bytes(),
+            b"\xEF\xBB\xBFwith a UTF-8 BOM\n",
+            b"\xFF\xFEa\x00\xe9\x00\n\x00",
+            b"\xFE\xFF\x00a\x00\xe9\x00\n",
+            b"",
+        ];
+        for bytes in files {
+            assert_eq!(round_trip(bytes), bytes);
+        }
+    }
+
+    #[test]
+    fn byte_order_mark_is_not_in_the_text() {
+        let decoded = decode_detected(b"\xEF\xBB\xBFhi".to_vec());
+        assert_eq!(decoded.encoding, encoding_rs::UTF_8);
+        assert_eq!(decoded.text, "hi");
+        let decoded = decode_detected(b"\xFF\xFEh\x00i\x00".to_vec());
+        assert_eq!(decoded.encoding, encoding_rs::UTF_16LE);
+        assert_eq!(decoded.text, "hi");
+    }
+
+    #[test]
+    fn utf16_is_encoded_as_utf16() {
+        assert_eq!(
+            encode("h\u{e9}", encoding_rs::UTF_16LE).unwrap(),
+            b"h\x00\xe9\x00"
+        );
+        assert_eq!(
+            encode("h\u{e9}", encoding_rs::UTF_16BE).unwrap(),
+            b"\x00h\x00\xe9"
+        );
+        // Outside the BMP: a surrogate pair
+        assert_eq!(
+            encode("\u{1F600}", encoding_rs::UTF_16LE).unwrap(),
+            b"\x3D\xD8\x00\xDE"
+        );
+    }
+
+    #[test]
+    fn other_encoding_keeps_no_foreign_bom() {
+        // Read as Windows-1252, the UTF-8 mark is text and is written back as such
+        let original = b"\xEF\xBB\xBFx";
+        let text = decode(original, encoding_rs::WINDOWS_1252);
+        assert_eq!(text, "\u{ef}\u{bb}\u{bf}x");
+        assert_eq!(
+            encode_document(&text, encoding_rs::WINDOWS_1252, original).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn unrepresentable_characters_are_refused() {
+        assert!(encode("\u{4e2d}", encoding_rs::WINDOWS_1252).is_err());
+    }
+}
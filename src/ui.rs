@@ -1,21 +1,96 @@
 use crate::{
+    history::{self, Direction, HistoryEntry, Outcome},
     localization::{Language, Localizer},
-    ssh::{SSHConnection, ServerStats},
+    logging,
+    queue::{Transfer, TransferQueue, TransferStatus},
+    settings::{
+        config_file, load_settings, save_settings, DirectoryView, Settings, SortKey, ThemeMode,
+        ViewMode,
+    },
+    ssh::{
+        display_name, is_same_remote_path, name_from_os, normalize_remote_path, remote_parent,
+        resolve_remote_path, symbolic_mode, AlgorithmPrefs, AuthQuestions, AuthStep,
+        DirectoryListing, EntryKind, FileEntry, FileProperties, FileVersion, FindLimits, GrepMatch,
+        GrepQuery, ListingSource, MirrorPlan, MirrorSide, OutputStream, PlannedChange, QuickStats,
+        RecursiveProgress, SSHConnection, ServerStats, SessionInfo, SshError, SyncMode,
+        TerminalInput, DEFAULT_DIRECTORY_MODE, DEFAULT_FILE_MODE, DEFAULT_TRANSFER_RETRIES,
+        MAX_LISTING_ENTRIES, SSH_LIBRARY_VERSION,
+    },
+    terminal::Screen,
+    text_encoding::{self, DecodedText, ENCODINGS},
 };
 use eframe::egui;
+use encoding_rs::Encoding;
 use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
 use std::{
-    path::Path,
+    cell::Cell,
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
     sync::{
-        mpsc::{self, Receiver, Sender},
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
         Arc, Mutex,
     },
     thread,
+    time::{Duration, Instant},
 };
 
+/// The title of the application window
+pub const APP_TITLE: &str = "SSH File Manager";
+
 /// The file where connections are stored
 const CONNECTIONS_FILE: &str = "saved_connections.json";
 
+/// How often the worker checks for a cancelled connection while the user
+/// answers the server's login questions
+const AUTH_ANSWER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often server stats are refreshed when auto-refresh is enabled
+const STATS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of stats samples kept for the dashboard graphs
+const STATS_HISTORY_LEN: usize = 60;
+
+/// How often the header badges (load, memory, disk) are refreshed
+const QUICK_STATS_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the connection latency is measured
+const LATENCY_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often an idle connection is checked for having been dropped by the server
+const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long typing in the path box must pause before completions are fetched
+const COMPLETION_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Unchanged lines shown around each change in the diff window
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Height of the terminal panel in rows of text
+const TERMINAL_ROWS: usize = 24;
+
+/// Maximum number of results of a file search
+const MAX_FIND_RESULTS: usize = 500;
+
+/// Lines a content search reports at most
+const MAX_GREP_RESULTS: usize = 500;
+
+/// Maximum number of path completions shown
+const MAX_COMPLETIONS: usize = 10;
+
+/// Number of directories kept in the Back history
+const HISTORY_LEN: usize = 100;
+
+/// Number of directories listed under Recent
+const RECENT_DIRS_LEN: usize = 15;
+
+/// How long a toast notification stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Width reserved for the line number gutter in the file editor
+const EDITOR_GUTTER_WIDTH: f32 = 48.0;
+
 /// Represents a saved SSH connection configuration
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct SSHConnectionData {
@@ -25,49 +100,327 @@ pub struct SSHConnectionData {
     pub username: String,
     /// The port number of the SSH server
     pub port: u16,
+    /// Algorithms to prefer during the handshake
+    #[serde(default)]
+    pub algorithms: AlgorithmPrefs,
+    /// A name shown in a banner while connected, e.g. "PRODUCTION"
+    #[serde(default)]
+    pub label: String,
+    /// The banner's accent color as sRGB
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
 }
 
-/// Load saved SSH connections from a JSON file
-fn load_saved_connections() -> Vec<SSHConnectionData> {
-    if Path::new(CONNECTIONS_FILE).exists() {
-        let content = std::fs::read_to_string(CONNECTIONS_FILE).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        Vec::new()
+/// Load saved SSH connections from a JSON file.
+///
+/// Entries that cannot be read are skipped instead of losing the whole list.
+/// If any were skipped, the file is first copied next to itself with a `.bak`
+/// extension, and the number of skipped entries and the copy are returned.
+fn load_saved_connections() -> (Vec<SSHConnectionData>, Option<(usize, String)>) {
+    let path = config_file(CONNECTIONS_FILE);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return (Vec::new(), None);
+    };
+    let (connections, skipped) = parse_saved_connections(&content);
+    if skipped == 0 {
+        return (connections, None);
+    }
+    let backup = path.with_extension("json.bak");
+    let backup = match std::fs::copy(&path, &backup) {
+        Ok(_) => backup.display().to_string(),
+        Err(e) => e.to_string(),
+    };
+    (connections, Some((skipped, backup)))
+}
+
+/// Read the saved connections leniently, returning the usable entries and how
+/// many were skipped.
+///
+/// Of a damaged file, the entries before the damage are kept. Hostnames and
+/// usernames are trimmed, port 0 becomes 22, entries without a hostname are
+/// skipped and repeated entries are merged into the first.
+fn parse_saved_connections(content: &str) -> (Vec<SSHConnectionData>, usize) {
+    if content.trim().is_empty() {
+        return (Vec::new(), 0);
+    }
+    let (items, mut skipped) = match serde_json::from_str(content) {
+        Ok(serde_json::Value::Array(items)) => (items, 0),
+        _ => (salvage_json_array(content), 1),
+    };
+    let mut connections: Vec<SSHConnectionData> = Vec::new();
+    for item in items {
+        let Ok(mut connection) = serde_json::from_value::<SSHConnectionData>(item) else {
+            skipped += 1;
+            continue;
+        };
+        connection.hostname = connection.hostname.trim().to_string();
+        connection.username = connection.username.trim().to_string();
+        if connection.hostname.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        if connection.port == 0 {
+            connection.port = 22;
+        }
+        if !connections.iter().any(|c| {
+            c.hostname == connection.hostname
+                && c.username == connection.username
+                && c.port == connection.port
+        }) {
+            connections.push(connection);
+        }
+    }
+    (connections, skipped)
+}
+
+/// The complete values at the start of a damaged JSON array, e.g. of a file
+/// cut off while it was written
+fn salvage_json_array(content: &str) -> Vec<serde_json::Value> {
+    let mut rest = content.trim_start().strip_prefix('[').unwrap_or_default();
+    let mut items = Vec::new();
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        let mut values = serde_json::Deserializer::from_str(rest).into_iter();
+        match values.next() {
+            Some(Ok(value)) => {
+                items.push(value);
+                rest = &rest[values.byte_offset()..];
+            }
+            _ => return items,
+        }
     }
 }
 
 /// Save SSH connections to a JSON file
 fn save_connections(connections: &Vec<SSHConnectionData>) {
     let content = serde_json::to_string(connections).unwrap();
-    std::fs::write(CONNECTIONS_FILE, content).unwrap();
+    std::fs::write(config_file(CONNECTIONS_FILE), content).unwrap();
 }
 
 /// Represents tasks that can be performed on the SSH connection.
 enum Task {
-    /// Connect to the SSH server (hostname, username, password, port)
-    Connect(String, String, String, u16),
+    /// Connect to the SSH server (hostname, username, password, port, algorithm preferences)
+    Connect(String, String, String, u16, AlgorithmPrefs),
+    /// Drop the current connection, if any, and connect again (same fields as `Connect`)
+    Reconnect(String, String, String, u16, AlgorithmPrefs),
     /// List the directory contents of the given path
     ListDirectory(String),
-    /// Create a directory at the specified path
-    CreateDirectory(String),
-    /// Create an empty file at the specified path
-    CreateFile(String),
-    /// Download a file from remote to local
-    DownloadFile(String, String),
-    /// Upload a file from local to remote
-    UploadFile(String, String),
+    /// List a directory to complete a path typed in the path box
+    ListCompletions(String),
+    /// Create a directory at the specified path (path, mode)
+    CreateDirectory(String, i32),
+    /// Create an empty file at the specified path (path, mode)
+    CreateFile(String, i32),
+    /// Run the pending transfers of the queue until it is empty or paused
+    ProcessQueue,
+    /// Recursively delete a remote directory
+    DeleteDirectory(String),
+    /// Delete the extra destination entries of a confirmed mirror plan
+    ApplyMirrorPlan(MirrorPlan),
+    /// Find the remote files a folder upload would overwrite, before queueing it
+    CheckUploadConflicts(Transfer),
     /// Delete a file
     DeleteFile(String),
-    /// Rename a file (old_path, new_path)
-    RenameFile(String, String),
-    /// Read a file from the remote server
-    ReadFile(String),
-    /// Write file content to the remote server
-    WriteFile(String, String),
+    /// Rename a file (old_path, new_path, replace a file already at new_path)
+    RenameFile(String, String, bool),
+    /// Find out what is at a path before acting on it
+    CheckPath(String, PathCheck),
+    /// Create a hard link (existing file, link path)
+    CreateHardlink(String, String),
+    /// Read a file from the remote server (path, maximum size in bytes,
+    /// encoding to decode it with instead of detecting one)
+    ReadFile(String, u64, Option<&'static Encoding>),
+    /// Read only the first bytes of a file for a read-only preview (path, byte
+    /// count, encoding as for `ReadFile`)
+    ReadFileHead(String, u64, Option<&'static Encoding>),
+    /// Write file content to the remote server (path, encoded content, only if
+    /// the file still exists, through a temporary file, only if the file is
+    /// still at this version)
+    WriteFile(String, Vec<u8>, bool, bool, Option<FileVersion>),
+    /// Append content to the end of a remote file (path, encoded content)
+    AppendFile(String, Vec<u8>),
+    /// Write content into a file without rewriting the rest of it (path, byte
+    /// offset or `None` for the end, encoded content)
+    WriteAt(String, Option<u64>, Vec<u8>),
+    /// Update a file's modification time to now (path, create if missing)
+    Touch(String, bool),
+    /// Collect a path's metadata for the properties dialog
+    FetchProperties(String),
+    /// Run changes through sudo with the given password (blank: the login password), or stop
+    SetSudo(Option<String>),
+    /// Limit how long one operation may wait for the server, or wait forever with `None`
+    SetTimeout(Option<Duration>),
+    /// Retry a failed chunk of a transfer up to this many times
+    SetTransferRetries(u32),
+    /// Hold transfers without a limit of their own to this many KiB per second; 0 for no limit
+    SetSpeedLimit(u32),
     /// Disconnect the active connection
     Disconnect,
+    /// Report the server banner and negotiated algorithms for the About window
+    FetchSessionInfo,
     FetchStats,
+    /// Fetch the load, memory and disk figures shown in the header
+    FetchQuickStats,
+    /// Measure the round-trip time to the server
+    MeasureLatency,
+    /// Check that the session still works, dropping it if it does not
+    CheckConnection,
+    /// Run a shell command on the server
+    RunCommand(String),
+    /// Start an interactive shell in a pseudo-terminal (columns, rows, keystrokes and resizes)
+    OpenTerminal(u32, u32, Receiver<TerminalInput>),
+    /// Search for files by name (root, pattern, maximum depth, time limit in seconds)
+    FindFiles(String, String, u32, u32),
+    /// Search file contents below a root, on a session of its own
+    Grep(String, GrepQuery),
+    /// List what an operation would change without doing it
+    DryRun(Destructive),
+}
+
+/// A recursive or overwriting operation, which a dry run lists before it is done
+#[derive(Debug, Clone)]
+enum Destructive {
+    DeleteDirectory(String),
+    /// Rename (old path, new path, whether to replace a file at the new path)
+    Rename(String, String, bool),
+}
+
+impl Destructive {
+    /// The task that carries out the operation
+    fn into_task(self) -> Task {
+        match self {
+            Destructive::DeleteDirectory(path) => Task::DeleteDirectory(path),
+            Destructive::Rename(old, new, replace) => Task::RenameFile(old, new, replace),
+        }
+    }
+
+    /// The task to send for the operation: with dry runs on, the one that
+    /// lists its changes for confirmation
+    fn task(self, dry_run: bool) -> Task {
+        if dry_run {
+            Task::DryRun(self)
+        } else {
+            self.into_task()
+        }
+    }
+}
+
+/// What to do with a path once [`Task::CheckPath`] found out what is there
+#[derive(Debug, Clone)]
+enum PathCheck {
+    /// Go to the path typed in the path box: list a directory, edit a file
+    Open,
+    /// Create a directory with this mode, unless something is in the way
+    CreateDirectory(i32),
+    /// Create an empty file with this mode, asking before replacing a file
+    CreateFile(i32),
+    /// Rename this path to the checked one, asking before replacing a file
+    Rename(String),
+}
+
+impl PathCheck {
+    /// The task that carries out the action on `path`
+    fn into_task(self, path: String) -> Task {
+        match self {
+            PathCheck::Open => Task::ListDirectory(path),
+            PathCheck::CreateDirectory(mode) => Task::CreateDirectory(path, mode),
+            PathCheck::CreateFile(mode) => Task::CreateFile(path, mode),
+            PathCheck::Rename(old_path) => Task::RenameFile(old_path, path, false),
+        }
+    }
+
+    /// The task that carries out the action on `path`, replacing the file
+    /// that is there; a rename is listed first while dry runs are on
+    fn into_replacing_task(self, path: String, dry_run: bool) -> Task {
+        match self {
+            PathCheck::Rename(old_path) => Destructive::Rename(old_path, path, true).task(dry_run),
+            check => check.into_task(path),
+        }
+    }
+}
+
+impl Task {
+    /// A one-line description for the log, without passwords or file contents
+    fn describe(&self) -> String {
+        match self {
+            Task::Connect(hostname, username, _, port, _) => {
+                format!("connect to {}@{}:{}", username, hostname, port)
+            }
+            Task::Reconnect(hostname, username, _, port, _) => {
+                format!("reconnect to {}@{}:{}", username, hostname, port)
+            }
+            Task::ListDirectory(path) => format!("list {}", path),
+            Task::ListCompletions(path) => format!("list completions of {}", path),
+            Task::CreateDirectory(path, mode) => format!("create directory {} ({:o})", path, mode),
+            Task::CreateFile(path, mode) => format!("create file {} ({:o})", path, mode),
+            Task::ProcessQueue => "process the transfer queue".to_string(),
+            Task::DeleteDirectory(path) => format!("delete directory {}", path),
+            Task::ApplyMirrorPlan(plan) => format!("mirror deletions in {}", plan.root),
+            Task::CheckUploadConflicts(transfer) => {
+                format!("check conflicts of {}", transfer.describe())
+            }
+            Task::DeleteFile(path) => format!("delete {}", path),
+            Task::RenameFile(old, new, _) => format!("rename {} to {}", old, new),
+            Task::CheckPath(path, check) => format!("check {} to {:?}", path, check),
+            Task::CreateHardlink(target, link) => format!("link {} to {}", link, target),
+            Task::ReadFile(path, ..) => format!("read {}", path),
+            Task::ReadFileHead(path, ..) => format!("read the start of {}", path),
+            Task::WriteFile(path, content, ..) => {
+                format!("write {} bytes to {}", content.len(), path)
+            }
+            Task::AppendFile(path, content) => {
+                format!("append {} bytes to {}", content.len(), path)
+            }
+            Task::WriteAt(path, offset, content) => match offset {
+                Some(offset) => format!("write {} bytes to {} at {}", content.len(), path, offset),
+                None => format!("append {} bytes to {}", content.len(), path),
+            },
+            Task::Touch(path, _) => format!("touch {}", path),
+            Task::FetchProperties(path) => format!("stat {}", path),
+            Task::SetSudo(password) => format!("use sudo: {}", password.is_some()),
+            Task::SetTimeout(timeout) => format!("set timeout to {:?}", timeout),
+            Task::SetTransferRetries(retries) => format!("set transfer retries to {}", retries),
+            Task::SetSpeedLimit(kib) => format!("set speed limit to {} KiB/s", kib),
+            Task::Disconnect => "disconnect".to_string(),
+            Task::FetchSessionInfo => "fetch session info".to_string(),
+            Task::FetchStats => "fetch server stats".to_string(),
+            Task::FetchQuickStats => "fetch header stats".to_string(),
+            Task::MeasureLatency => "measure latency".to_string(),
+            Task::CheckConnection => "check the connection".to_string(),
+            Task::RunCommand(_) => "run a command".to_string(),
+            Task::OpenTerminal(cols, rows, _) => format!("open a {}x{} terminal", cols, rows),
+            Task::FindFiles(root, pattern, ..) => format!("find {:?} in {}", pattern, root),
+            Task::Grep(root, query) => format!("grep {:?} in {}", query.pattern, root),
+            Task::DryRun(operation) => format!("dry run of {:?}", operation),
+        }
+    }
+
+    /// The slot of tasks this one supersedes, if a newer request makes older
+    /// results of the same kind unwanted
+    fn slot(&self) -> Option<ResultSlot> {
+        match self {
+            Task::ListDirectory(_) => Some(ResultSlot::Listing),
+            Task::ListCompletions(_) => Some(ResultSlot::Completions),
+            Task::ReadFile(..) | Task::ReadFileHead(..) => Some(ResultSlot::OpenFile),
+            Task::FetchStats => Some(ResultSlot::Stats),
+            Task::FetchQuickStats => Some(ResultSlot::QuickStats),
+            Task::MeasureLatency => Some(ResultSlot::Latency),
+            Task::FindFiles(..) => Some(ResultSlot::Find),
+            Task::Grep(..) => Some(ResultSlot::Grep),
+            _ => None,
+        }
+    }
+
+    /// Whether the task runs by itself in the background, to log it only at trace level
+    fn is_periodic(&self) -> bool {
+        matches!(
+            self,
+            Task::ListCompletions(_)
+                | Task::FetchQuickStats
+                | Task::MeasureLatency
+                | Task::CheckConnection
+        )
+    }
 }
 
 /// Represents the result of executing a Task.
@@ -75,182 +428,799 @@ enum Task {
 #[allow(clippy::enum_variant_names)]
 enum TaskResult {
     /// The result of the connect attempt
-    ConnectResult(Result<(), String>),
-    /// The result of listing a directory (Vec<(filename, is_dir)> or error)
-    ListDirectoryResult(Result<Vec<(String, bool)>, String>),
+    ConnectResult(Result<(), SshError>),
+    /// The authentication attempt being made while connecting
+    AuthProgress(AuthStep),
+    /// Questions of the server during login, e.g. for a one-time code, and
+    /// where to send the answers; `None` cancels the login
+    AuthQuestionsAsked(AuthQuestions, Sender<Option<Vec<String>>>),
+    /// A connect attempt was ignored because a session is already open
+    AlreadyConnected,
+    /// The result of listing a directory
+    ListDirectoryResult(String, Result<DirectoryListing, SshError>),
+    /// The listing used to complete a typed path (directory, listing or error)
+    CompletionsResult(String, Result<DirectoryListing, SshError>),
     /// Generic success message for directory creation
-    CreateDirectoryResult(Result<(), String>),
+    CreateDirectoryResult(Result<(), SshError>),
     /// Generic success message for file creation
-    CreateFileResult(Result<(), String>),
-    /// Generic success message for file download
-    DownloadFileResult(Result<(), String>),
-    /// Generic success message for file upload
-    UploadFileResult(Result<(), String>),
+    CreateFileResult(Result<(), SshError>),
+    /// The result of a queued transfer
+    TransferResult(Transfer, Result<(), SshError>),
+    /// The result of a recursive directory deletion
+    DeleteDirectoryResult(Result<(), SshError>),
+    /// What mirroring a finished folder transfer would delete, awaiting confirmation
+    MirrorPlanResult(Result<MirrorPlan, SshError>),
+    /// An upload that failed because the remote directory above its target does not exist
+    MissingUploadParent(Transfer),
+    /// The result of deleting the extra entries of a mirror plan
+    ApplyMirrorPlanResult(MirrorSide, Result<(), SshError>),
+    /// The files a folder upload would overwrite, relative to its destination
+    UploadConflictsResult(Transfer, Result<Vec<String>, SshError>),
+    /// Progress of a running recursive operation
+    Progress(RecursiveProgress),
+    /// A change to this path was made through sudo
+    Elevated(String),
     /// Generic success message for file deletion
-    DeleteFileResult(Result<(), String>),
+    DeleteFileResult(Result<(), SshError>),
     /// Generic success message for file renaming
-    RenameFileResult(Result<(), String>),
-    /// The result of reading a file
-    ReadFileResult(Result<String, String>),
+    RenameFileResult(Result<(), SshError>),
+    /// What is at a checked path (path, the action waiting for it, kind or
+    /// `None` if nothing is there)
+    PathChecked(String, PathCheck, Result<Option<EntryKind>, SshError>),
+    /// The result of creating a hard link
+    CreateHardlinkResult(Result<(), SshError>),
+    /// The result of reading a file (path, decoded content and the version
+    /// read, or error)
+    ReadFileResult(String, Result<(DecodedText, FileVersion), SshError>),
+    /// The file is larger than the requested maximum size (path, size in bytes)
+    FileTooLarge(String, u64),
+    /// The result of reading the head of a file (path, decoded content or error)
+    ReadFileHeadResult(String, Result<DecodedText, SshError>),
     /// The result of writing a file
-    WriteFileResult(Result<(), String>),
+    WriteFileResult(Result<Option<FileVersion>, SshError>),
+    /// The file being saved no longer exists on the server
+    WriteTargetMissing(String),
+    /// The file being saved changed on the server since it was opened (path,
+    /// its current version and content)
+    WriteTargetChanged(String, FileVersion, Result<Vec<u8>, SshError>),
+    /// The result of appending to a file: the appended bytes and the file's new version
+    AppendFileResult(Result<(Vec<u8>, Option<FileVersion>), SshError>),
+    /// The result of writing into a file from the Write Into File dialog
+    WriteAtResult(Result<(), SshError>),
+    /// The result of touching a file
+    TouchResult(Result<(), SshError>),
+    /// The metadata of a path, for the properties dialog; boxed, as it is
+    /// much larger than the other results
+    PropertiesResult(Result<Box<FileProperties>, SshError>),
     /// The result of disconnecting
     DisconnectResult,
-    FetchStatsResult(Result<ServerStats, String>),
+    /// The server banner and negotiated algorithms, if connected
+    SessionInfoResult(Option<SessionInfo>),
+    FetchStatsResult(Result<ServerStats, SshError>),
+    /// The figures shown in the header
+    QuickStatsResult(Result<QuickStats, SshError>),
+    /// The measured round-trip time
+    LatencyResult(Result<Duration, SshError>),
+    /// Whether the session was found working; a dead one has been dropped
+    ConnectionChecked(bool),
+    /// Output of the running command, as it arrives
+    CommandChunk(OutputStream, String),
+    /// The exit status of a command run from the command panel
+    RunCommandResult(Result<i32, SshError>),
+    /// Output of the interactive shell, as it arrives
+    TerminalOutput(String),
+    /// The exit status of the interactive shell
+    TerminalClosed(Result<i32, SshError>),
+    /// A path matching the running file search, as soon as it is found
+    FindMatch(String),
+    /// The end of a file search
+    FindFilesResult(Result<(), SshError>),
+    /// A line matching the running content search, as soon as it is found
+    GrepMatch(GrepMatch),
+    /// The end of a content search
+    GrepResult(Result<(), SshError>),
+    /// The changes an operation would make, found by a dry run
+    DryRunResult(Destructive, Result<Vec<PlannedChange>, SshError>),
+}
+
+impl TaskResult {
+    /// The slot of the task this result answers, see [`Task::slot`]
+    fn slot(&self) -> Option<ResultSlot> {
+        match self {
+            TaskResult::ListDirectoryResult(..) => Some(ResultSlot::Listing),
+            TaskResult::CompletionsResult(..) => Some(ResultSlot::Completions),
+            TaskResult::ReadFileResult(..)
+            | TaskResult::ReadFileHeadResult(..)
+            | TaskResult::FileTooLarge(..) => Some(ResultSlot::OpenFile),
+            TaskResult::FetchStatsResult(_) => Some(ResultSlot::Stats),
+            TaskResult::QuickStatsResult(_) => Some(ResultSlot::QuickStats),
+            TaskResult::LatencyResult(_) => Some(ResultSlot::Latency),
+            TaskResult::FindMatch(_) | TaskResult::FindFilesResult(_) => Some(ResultSlot::Find),
+            TaskResult::GrepMatch(_) | TaskResult::GrepResult(_) => Some(ResultSlot::Grep),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies a task sent to the worker, and the results it sends back
+type TaskId = u64;
+
+/// Kinds of tasks where a new request replaces the previous one, so that only
+/// the result of the latest is wanted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ResultSlot {
+    Listing,
+    Completions,
+    OpenFile,
+    Stats,
+    QuickStats,
+    Latency,
+    Find,
+    Grep,
+}
+
+/// Sends the results of one task back to the UI, tagged with the task's id
+#[derive(Clone)]
+struct ResultSender {
+    sender: Sender<(TaskId, TaskResult)>,
+    id: TaskId,
+}
+
+impl ResultSender {
+    /// Fails, dropping the result, once the UI side has gone away
+    fn send(&self, result: TaskResult) -> Result<(), mpsc::SendError<()>> {
+        self.sender
+            .send((self.id, result))
+            .map_err(|_| mpsc::SendError(()))
+    }
 }
 
 /// BackgroundWorker handles asynchronous tasks to avoid blocking the UI.
 /// Communicates with the UI via channels.
 struct BackgroundWorker {
     /// Sender to send tasks from the UI thread to the worker thread
-    task_sender: Sender<Task>,
+    task_sender: Sender<(TaskId, Task)>,
     /// Receiver on the UI side to receive the results from the worker thread
-    result_receiver: Receiver<TaskResult>,
+    result_receiver: Receiver<(TaskId, TaskResult)>,
     /// Holds the active SSH connection if connected
     #[allow(dead_code)]
     connection: Option<SSHConnection>,
+    /// Set to request cancellation of the running transfer
+    cancel_flag: Arc<AtomicBool>,
+    /// Transfers waiting to be run by the worker
+    queue: Arc<Mutex<TransferQueue>>,
+    /// Set to stop the command running from the command panel
+    command_cancel: Arc<AtomicBool>,
+    /// Set to stop the running content search
+    grep_cancel: Arc<AtomicBool>,
+    /// Id given to the next task, see [`TaskId`]
+    next_task_id: Cell<TaskId>,
+    /// The latest task sent for each slot; earlier ones are skipped by the
+    /// worker if they have not started, and their results are stale
+    latest_in_slot: Arc<Mutex<HashMap<ResultSlot, TaskId>>>,
 }
 
 impl BackgroundWorker {
     /// Create a new BackgroundWorker and start the worker thread
     fn new() -> Self {
-        let (task_sender, task_receiver) = mpsc::channel();
-        let (result_sender, result_receiver) = mpsc::channel();
+        let (task_sender, task_receiver) = mpsc::channel::<(TaskId, Task)>();
+        let (results, result_receiver) = mpsc::channel();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let cancel = cancel_flag.clone();
+        let command_cancel = Arc::new(AtomicBool::new(false));
+        let stop_command = command_cancel.clone();
+        let grep_cancel = Arc::new(AtomicBool::new(false));
+        let stop_grep = grep_cancel.clone();
+        let queue = Arc::new(Mutex::new(TransferQueue::default()));
+        let worker_queue = queue.clone();
+        let latest_in_slot = Arc::new(Mutex::new(HashMap::new()));
+        let latest = latest_in_slot.clone();
 
         // Spawn the worker thread
         thread::spawn(move || {
+            let queue = worker_queue;
             let mut connection: Option<SSHConnection> = None;
-            while let Ok(task) = task_receiver.recv() {
+            let mut sudo: Option<String> = None;
+            let mut timeout: Option<Duration> = None;
+            let mut retries = DEFAULT_TRANSFER_RETRIES;
+            let mut speed_limit_kib = 0;
+            while let Ok((id, task)) = task_receiver.recv() {
+                // Rapid navigation can queue many listings behind a slow
+                // server: only the latest of each kind is worth running
+                let superseded = task.slot().is_some_and(|slot| {
+                    latest
+                        .lock()
+                        .unwrap()
+                        .get(&slot)
+                        .is_some_and(|&latest| latest != id)
+                });
+                if superseded {
+                    log::trace!("Skipping superseded task: {}", task.describe());
+                    continue;
+                }
+                let result_sender = ResultSender {
+                    sender: results.clone(),
+                    id,
+                };
+                let level = if task.is_periodic() {
+                    log::Level::Trace
+                } else {
+                    log::Level::Debug
+                };
+                log::log!(level, "Task: {}", task.describe());
                 match task {
-                    Task::Connect(hostname, username, password, port) => {
+                    // Never replace a live session behind the UI's back
+                    Task::Connect(..) if connection.is_some() => {
+                        let _ = result_sender.send(TaskResult::AlreadyConnected);
+                    }
+                    Task::Connect(hostname, username, password, port, algorithms)
+                    | Task::Reconnect(hostname, username, password, port, algorithms) => {
+                        if let Some(mut old) = connection.take() {
+                            old.disconnect();
+                        }
                         let mut conn = SSHConnection::new(&hostname, &username, &password, port);
-                        let connect_result = conn.connect();
+                        conn.set_algorithm_prefs(algorithms);
+                        conn.set_sudo(sudo.as_deref());
+                        conn.set_timeout(timeout);
+                        conn.set_transfer_retries(retries);
+                        conn.set_speed_limit(kib_to_bytes(speed_limit_kib));
+                        cancel.store(false, Ordering::Relaxed);
+                        let connect_result = conn.connect_cancellable(
+                            &cancel,
+                            &mut |step| {
+                                let _ = result_sender.send(TaskResult::AuthProgress(step));
+                            },
+                            // The worker waits for the user, unless the connection is cancelled
+                            &mut |questions| {
+                                let (reply, answers) = mpsc::channel();
+                                let _ = result_sender
+                                    .send(TaskResult::AuthQuestionsAsked(questions.clone(), reply));
+                                loop {
+                                    if cancel.load(Ordering::Relaxed) {
+                                        return None;
+                                    }
+                                    match answers.recv_timeout(AUTH_ANSWER_POLL_INTERVAL) {
+                                        Ok(answers) => return answers,
+                                        Err(RecvTimeoutError::Timeout) => {}
+                                        Err(RecvTimeoutError::Disconnected) => return None,
+                                    }
+                                }
+                            },
+                        );
 
                         let send_result = match connect_result {
                             Ok(_) => {
                                 connection = Some(conn);
                                 Ok(())
                             }
-                            Err(e) => Err(format!("Failed to connect: {}", e)),
+                            Err(e) => Err(e.with_context("Failed to connect")),
                         };
 
                         let _ = result_sender.send(TaskResult::ConnectResult(send_result));
                     }
 
                     Task::ListDirectory(path) => {
-                        if let Some(conn) = connection.as_ref() {
-                            let result = conn.list_directory(&path);
-                            let _ = result_sender.send(TaskResult::ListDirectoryResult(result));
-                        } else {
-                            let _ = result_sender
-                                .send(TaskResult::ListDirectoryResult(Err("Not connected".into())));
-                        }
+                        let result = match connection.as_ref() {
+                            Some(conn) => conn.list_directory(&path),
+                            None => Err(SshError::NotConnected),
+                        };
+                        let _ = result_sender.send(TaskResult::ListDirectoryResult(path, result));
+                    }
+                    Task::ListCompletions(path) => {
+                        let result = match connection.as_ref() {
+                            Some(conn) => conn.list_directory(&path),
+                            None => Err(SshError::NotConnected),
+                        };
+                        let _ = result_sender.send(TaskResult::CompletionsResult(path, result));
                     }
-                    Task::CreateDirectory(path) => {
+                    Task::CreateDirectory(path, mode) => {
                         if let Some(conn) = connection.as_ref() {
                             let result = conn
-                                .create_directory(&path)
-                                .map_err(|e| format!("Failed to create directory: {}", e));
+                                .create_directory(&path, mode)
+                                .map_err(|e| e.with_context("Failed to create directory"));
+                            report_elevated(conn, &result, &path, &result_sender);
                             let _ = result_sender.send(TaskResult::CreateDirectoryResult(result));
                         } else {
                             let _ = result_sender.send(TaskResult::CreateDirectoryResult(Err(
-                                "Not connected".into(),
+                                SshError::NotConnected,
                             )));
                         }
                     }
-                    Task::CreateFile(path) => {
+                    Task::CreateFile(path, mode) => {
                         if let Some(conn) = connection.as_ref() {
                             let result = conn
-                                .create_file(&path)
-                                .map_err(|e| format!("Failed to create file: {}", e));
+                                .create_file(&path, mode)
+                                .map_err(|e| e.with_context("Failed to create file"));
+                            report_elevated(conn, &result, &path, &result_sender);
                             let _ = result_sender.send(TaskResult::CreateFileResult(result));
                         } else {
                             let _ = result_sender
-                                .send(TaskResult::CreateFileResult(Err("Not connected".into())));
+                                .send(TaskResult::CreateFileResult(Err(SshError::NotConnected)));
                         }
                     }
-                    Task::DownloadFile(remote, local) => {
+                    Task::ProcessQueue => loop {
+                        let next = queue.lock().unwrap().start_next();
+                        let Some((id, transfer, own_limit)) = next else {
+                            break;
+                        };
+                        let result = match connection.as_mut() {
+                            Some(conn) => {
+                                conn.set_speed_limit(kib_to_bytes(
+                                    own_limit.unwrap_or(speed_limit_kib),
+                                ));
+                                run_transfer(conn, &transfer, &cancel, &result_sender)
+                            }
+                            None => Err(SshError::NotConnected),
+                        };
+                        match &result {
+                            Ok(()) => log::info!("Transfer done: {}", transfer.describe()),
+                            Err(e) => {
+                                log::warn!("Transfer failed: {}: {}", transfer.describe(), e)
+                            }
+                        }
+                        queue.lock().unwrap().finish(id, &result);
+                        let server = connection
+                            .as_ref()
+                            .map(SSHConnection::target)
+                            .unwrap_or_default();
+                        history::record(&HistoryEntry::new(server, &transfer, &result));
+                        let plan = match (&result, connection.as_ref()) {
+                            (Ok(()), Some(conn)) => plan_mirror(conn, &transfer),
+                            _ => None,
+                        };
+                        let missing_parent = match (&result, connection.as_ref()) {
+                            (Err(SshError::NotFound(..)), Some(conn)) => {
+                                !transfer.creates_parents()
+                                    && transfer
+                                        .upload_target()
+                                        .is_some_and(|remote| conn.is_parent_missing(remote))
+                            }
+                            _ => false,
+                        };
+                        let _ = result_sender
+                            .send(TaskResult::TransferResult(transfer.clone(), result));
+                        if let Some(plan) = plan {
+                            let _ = result_sender.send(TaskResult::MirrorPlanResult(plan));
+                        }
+                        if missing_parent {
+                            let _ = result_sender.send(TaskResult::MissingUploadParent(transfer));
+                        }
+                    },
+                    Task::ApplyMirrorPlan(plan) => {
                         if let Some(conn) = connection.as_ref() {
+                            cancel.store(false, Ordering::Relaxed);
                             let result = conn
-                                .download_file(&remote, &local)
-                                .map_err(|e| format!("Failed to download: {}", e));
-                            let _ = result_sender.send(TaskResult::DownloadFileResult(result));
-                        } else {
+                                .apply_mirror_plan(&plan, &cancel, &mut |progress| {
+                                    let _ = result_sender.send(TaskResult::Progress(progress));
+                                })
+                                .map_err(|e| e.with_context("Failed to mirror"));
                             let _ = result_sender
-                                .send(TaskResult::DownloadFileResult(Err("Not connected".into())));
+                                .send(TaskResult::ApplyMirrorPlanResult(plan.side, result));
+                        } else {
+                            let _ = result_sender.send(TaskResult::ApplyMirrorPlanResult(
+                                plan.side,
+                                Err(SshError::NotConnected),
+                            ));
                         }
                     }
-                    Task::UploadFile(local, remote) => {
+                    Task::CheckUploadConflicts(transfer) => {
+                        let result = match (&transfer, connection.as_ref()) {
+                            (
+                                Transfer::UploadDirectory {
+                                    local,
+                                    remote,
+                                    sync,
+                                    ..
+                                },
+                                Some(conn),
+                            ) => conn
+                                .find_upload_conflicts(local, remote, *sync)
+                                .map_err(|e| e.with_context("Failed to compare folders")),
+                            (_, Some(_)) => Ok(Vec::new()),
+                            (_, None) => Err(SshError::NotConnected),
+                        };
+                        let _ =
+                            result_sender.send(TaskResult::UploadConflictsResult(transfer, result));
+                    }
+                    Task::DryRun(operation) => {
+                        if let Some(conn) = connection.as_ref() {
+                            let mut progress = |progress| {
+                                let _ = result_sender.send(TaskResult::Progress(progress));
+                            };
+                            let result = match &operation {
+                                Destructive::DeleteDirectory(path) => conn
+                                    .plan_delete_directory(path, &mut progress)
+                                    .map(|entries| {
+                                        entries
+                                            .into_iter()
+                                            .map(|(path, _)| PlannedChange::Delete(path))
+                                            .collect()
+                                    }),
+                                Destructive::Rename(old, new, replace) => {
+                                    conn.plan_rename(old, new, *replace, &mut progress)
+                                }
+                            }
+                            .map_err(|e| e.with_context("Failed to list the changes"));
+                            let _ = result_sender.send(TaskResult::DryRunResult(operation, result));
+                        } else {
+                            let _ = result_sender.send(TaskResult::DryRunResult(
+                                operation,
+                                Err(SshError::NotConnected),
+                            ));
+                        }
+                    }
+                    Task::DeleteDirectory(path) => {
                         if let Some(conn) = connection.as_ref() {
+                            cancel.store(false, Ordering::Relaxed);
                             let result = conn
-                                .upload_file(&local, &remote)
-                                .map_err(|e| format!("Failed to upload: {}", e));
-                            let _ = result_sender.send(TaskResult::UploadFileResult(result));
+                                .delete_directory(&path, &cancel, &mut |progress| {
+                                    let _ = result_sender.send(TaskResult::Progress(progress));
+                                })
+                                .map_err(|e| e.with_context("Failed to delete directory"));
+                            report_elevated(conn, &result, &path, &result_sender);
+                            let _ = result_sender.send(TaskResult::DeleteDirectoryResult(result));
                         } else {
-                            let _ = result_sender
-                                .send(TaskResult::UploadFileResult(Err("Not connected".into())));
+                            let _ = result_sender.send(TaskResult::DeleteDirectoryResult(Err(
+                                SshError::NotConnected,
+                            )));
                         }
                     }
                     Task::DeleteFile(path) => {
                         if let Some(conn) = connection.as_ref() {
                             let result = conn
                                 .delete_file(&path)
-                                .map_err(|e| format!("Failed to delete: {}", e));
+                                .map_err(|e| e.with_context("Failed to delete"));
+                            report_elevated(conn, &result, &path, &result_sender);
                             let _ = result_sender.send(TaskResult::DeleteFileResult(result));
                         } else {
                             let _ = result_sender
-                                .send(TaskResult::DeleteFileResult(Err("Not connected".into())));
+                                .send(TaskResult::DeleteFileResult(Err(SshError::NotConnected)));
                         }
                     }
-                    Task::RenameFile(old, new) => {
+                    Task::RenameFile(old, new, replace) => {
                         if let Some(conn) = connection.as_ref() {
-                            let result = conn
-                                .rename(&old, &new)
-                                .map_err(|e| format!("Failed to rename: {}", e));
+                            let result = if replace {
+                                conn.rename_replacing(&old, &new)
+                            } else {
+                                conn.rename(&old, &new)
+                            }
+                            .map_err(|e| e.with_context("Failed to rename"));
+                            report_elevated(conn, &result, &old, &result_sender);
                             let _ = result_sender.send(TaskResult::RenameFileResult(result));
                         } else {
                             let _ = result_sender
-                                .send(TaskResult::RenameFileResult(Err("Not connected".into())));
+                                .send(TaskResult::RenameFileResult(Err(SshError::NotConnected)));
                         }
                     }
-                    Task::ReadFile(path) => {
+                    Task::CheckPath(path, check) => {
+                        let result = match connection.as_ref() {
+                            Some(conn) => conn.path_type(&path),
+                            None => Err(SshError::NotConnected),
+                        };
+                        let _ = result_sender.send(TaskResult::PathChecked(path, check, result));
+                    }
+                    Task::CreateHardlink(target, link) => {
                         if let Some(conn) = connection.as_ref() {
-                            let result = conn
-                                .read_file(&path)
-                                .map_err(|e| format!("Failed to read file: {}", e));
-                            let _ = result_sender.send(TaskResult::ReadFileResult(result));
+                            let result = conn.create_hardlink(&target, &link);
+                            report_elevated(conn, &result, &link, &result_sender);
+                            let _ = result_sender.send(TaskResult::CreateHardlinkResult(result));
+                        } else {
+                            let _ = result_sender.send(TaskResult::CreateHardlinkResult(Err(
+                                SshError::NotConnected,
+                            )));
+                        }
+                    }
+                    Task::ReadFile(path, max_size, encoding) => {
+                        if let Some(conn) = connection.as_ref() {
+                            // Sudo mode reads what SFTP may not, and says so
+                            let (version, elevated) = match conn.file_version(&path) {
+                                Err(SshError::PermissionDenied(..)) if conn.is_elevated() => {
+                                    (conn.sudo_file_version(&path), true)
+                                }
+                                version => (version, false),
+                            };
+                            match version {
+                                Ok(version) if version.size > max_size => {
+                                    let _ = result_sender
+                                        .send(TaskResult::FileTooLarge(path, version.size));
+                                }
+                                Ok(version) => {
+                                    let (bytes, elevated) = if elevated {
+                                        (conn.sudo_read_file(&path, None), true)
+                                    } else {
+                                        match conn.read_file(&path) {
+                                            Err(SshError::PermissionDenied(..))
+                                                if conn.is_elevated() =>
+                                            {
+                                                (conn.sudo_read_file(&path, None), true)
+                                            }
+                                            bytes => (bytes, false),
+                                        }
+                                    };
+                                    if elevated {
+                                        report_elevated(conn, &bytes, &path, &result_sender);
+                                    }
+                                    let result = bytes
+                                        .map(|bytes| {
+                                            let decoded =
+                                                text_encoding::decode_with_default(bytes, encoding);
+                                            (decoded, version)
+                                        })
+                                        .map_err(|e| e.with_context("Failed to read file"));
+                                    let _ = result_sender
+                                        .send(TaskResult::ReadFileResult(path, result));
+                                }
+                                Err(e) => {
+                                    let _ = result_sender.send(TaskResult::ReadFileResult(
+                                        path,
+                                        Err(e.with_context("Failed to read file")),
+                                    ));
+                                }
+                            }
+                        } else {
+                            let _ = result_sender.send(TaskResult::ReadFileResult(
+                                path,
+                                Err(SshError::NotConnected),
+                            ));
+                        }
+                    }
+                    Task::ReadFileHead(path, max_bytes, encoding) => {
+                        if let Some(conn) = connection.as_ref() {
+                            let bytes = match conn.read_file_head(&path, max_bytes) {
+                                Err(SshError::PermissionDenied(..)) if conn.is_elevated() => {
+                                    let bytes = conn.sudo_read_file(&path, Some(max_bytes));
+                                    report_elevated(conn, &bytes, &path, &result_sender);
+                                    bytes
+                                }
+                                bytes => bytes,
+                            };
+                            let result = bytes
+                                .map(|bytes| text_encoding::decode_with_default(bytes, encoding))
+                                .map_err(|e| e.with_context("Failed to read file"));
+                            let _ =
+                                result_sender.send(TaskResult::ReadFileHeadResult(path, result));
+                        } else {
+                            let _ = result_sender.send(TaskResult::ReadFileHeadResult(
+                                path,
+                                Err(SshError::NotConnected),
+                            ));
+                        }
+                    }
+                    Task::WriteFile(path, content, must_exist, atomic, expected) => {
+                        if let Some(conn) = connection.as_ref() {
+                            // Don't silently resurrect a file deleted or renamed on the server
+                            if must_exist && matches!(conn.exists(&path), Ok(false)) {
+                                let _ = result_sender.send(TaskResult::WriteTargetMissing(path));
+                                continue;
+                            }
+                            // Nor overwrite what someone else saved since the file was opened.
+                            // If the file cannot be inspected, saving goes ahead as before.
+                            if let Some(current) = conn
+                                .file_version(&path)
+                                .ok()
+                                .filter(|current| expected.is_some_and(|v| v != *current))
+                            {
+                                let content = conn.read_file(&path);
+                                let _ = result_sender
+                                    .send(TaskResult::WriteTargetChanged(path, current, content));
+                                continue;
+                            }
+                            let result = if atomic {
+                                conn.write_file_atomic(&path, &content)
+                            } else {
+                                conn.write_file(&path, &content)
+                            }
+                            .map_err(|e| e.with_context("Failed to write file"));
+                            report_elevated(conn, &result, &path, &result_sender);
+                            // The editor stays open, so the next save checks against this version
+                            let result = result.map(|()| conn.file_version(&path).ok());
+                            let _ = result_sender.send(TaskResult::WriteFileResult(result));
                         } else {
                             let _ = result_sender
-                                .send(TaskResult::ReadFileResult(Err("Not connected".into())));
+                                .send(TaskResult::WriteFileResult(Err(SshError::NotConnected)));
                         }
                     }
-                    Task::WriteFile(path, content) => {
+                    Task::AppendFile(path, content) => {
                         if let Some(conn) = connection.as_ref() {
                             let result = conn
-                                .write_file(&path, &content)
-                                .map_err(|e| format!("Failed to write file: {}", e));
-                            let _ = result_sender.send(TaskResult::WriteFileResult(result));
+                                .append_file(&path, &content)
+                                .map_err(|e| e.with_context("Failed to append to file"));
+                            report_elevated(conn, &result, &path, &result_sender);
+                            let result = result.map(|()| (content, conn.file_version(&path).ok()));
+                            let _ = result_sender.send(TaskResult::AppendFileResult(result));
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::AppendFileResult(Err(SshError::NotConnected)));
+                        }
+                    }
+                    Task::WriteAt(path, offset, content) => {
+                        if let Some(conn) = connection.as_ref() {
+                            let result = match offset {
+                                Some(offset) => conn.write_at(&path, offset, &content),
+                                None => conn.append_file(&path, &content),
+                            }
+                            .map_err(|e| e.with_context("Failed to write into file"));
+                            report_elevated(conn, &result, &path, &result_sender);
+                            let _ = result_sender.send(TaskResult::WriteAtResult(result));
                         } else {
                             let _ = result_sender
-                                .send(TaskResult::WriteFileResult(Err("Not connected".into())));
+                                .send(TaskResult::WriteAtResult(Err(SshError::NotConnected)));
                         }
                     }
+                    Task::Touch(path, create) => {
+                        if let Some(conn) = connection.as_ref() {
+                            let result = conn.touch(&path, create);
+                            report_elevated(conn, &result, &path, &result_sender);
+                            let _ = result_sender.send(TaskResult::TouchResult(result));
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::TouchResult(Err(SshError::NotConnected)));
+                        }
+                    }
+                    Task::FetchProperties(path) => {
+                        let result = match connection.as_ref() {
+                            Some(conn) => conn.properties(&path).map(Box::new),
+                            None => Err(SshError::NotConnected),
+                        };
+                        let _ = result_sender.send(TaskResult::PropertiesResult(result));
+                    }
+                    Task::SetTimeout(limit) => {
+                        if let Some(conn) = connection.as_mut() {
+                            conn.set_timeout(limit);
+                        }
+                        timeout = limit;
+                    }
+                    Task::SetTransferRetries(count) => {
+                        if let Some(conn) = connection.as_mut() {
+                            conn.set_transfer_retries(count);
+                        }
+                        retries = count;
+                    }
+                    Task::SetSpeedLimit(kib) => {
+                        if let Some(conn) = connection.as_mut() {
+                            conn.set_speed_limit(kib_to_bytes(kib));
+                        }
+                        speed_limit_kib = kib;
+                    }
+                    Task::SetSudo(password) => {
+                        if let Some(conn) = connection.as_mut() {
+                            conn.set_sudo(password.as_deref());
+                        }
+                        sudo = password;
+                    }
                     Task::Disconnect => {
+                        stop_command.store(true, Ordering::Relaxed);
+                        stop_grep.store(true, Ordering::Relaxed);
                         if let Some(mut conn) = connection.take() {
                             conn.disconnect();
                         }
                         let _ = result_sender.send(TaskResult::DisconnectResult);
                     }
 
+                    // Commands may run for a long time, so they get a session and
+                    // a thread of their own and stream their output back
+                    Task::RunCommand(command) => {
+                        if let Some(conn) = connection.as_ref() {
+                            let runner = conn.command_runner();
+                            let sender = result_sender.clone();
+                            let cancel = stop_command.clone();
+                            cancel.store(false, Ordering::Relaxed);
+                            thread::spawn(move || {
+                                let result = runner.run(&command, &cancel, &mut |stream, text| {
+                                    let _ = sender.send(TaskResult::CommandChunk(stream, text));
+                                });
+                                let _ = sender.send(TaskResult::RunCommandResult(result));
+                            });
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::RunCommandResult(Err(SshError::NotConnected)));
+                        }
+                    }
+                    Task::Grep(root, query) => {
+                        if let Some(conn) = connection.as_ref() {
+                            let runner = conn.command_runner();
+                            let sender = result_sender.clone();
+                            let cancel = stop_grep.clone();
+                            cancel.store(false, Ordering::Relaxed);
+                            thread::spawn(move || {
+                                let result = runner
+                                    .grep(&root, &query, MAX_GREP_RESULTS, &cancel, &mut |m| {
+                                        let _ = sender.send(TaskResult::GrepMatch(m));
+                                    })
+                                    .map_err(|e| e.with_context("Failed to search file contents"));
+                                let _ = sender.send(TaskResult::GrepResult(result));
+                            });
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::GrepResult(Err(SshError::NotConnected)));
+                        }
+                    }
+
+                    Task::OpenTerminal(cols, rows, input) => {
+                        if let Some(conn) = connection.as_ref() {
+                            let runner = conn.command_runner();
+                            let sender = result_sender.clone();
+                            thread::spawn(move || {
+                                let result = runner.shell(cols, rows, &input, &mut |text| {
+                                    let _ = sender.send(TaskResult::TerminalOutput(text));
+                                });
+                                let _ = sender.send(TaskResult::TerminalClosed(result));
+                            });
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::TerminalClosed(Err(SshError::NotConnected)));
+                        }
+                    }
+                    Task::FetchSessionInfo => {
+                        let info = connection.as_ref().and_then(SSHConnection::session_info);
+                        let _ = result_sender.send(TaskResult::SessionInfoResult(info));
+                    }
                     Task::FetchStats => {
                         if let Some(conn) = connection.as_ref() {
                             let result = conn.fetch_stats();
                             let _ = result_sender.send(TaskResult::FetchStatsResult(result));
                         } else {
                             let _ = result_sender
-                                .send(TaskResult::FetchStatsResult(Err("Not connected".into())));
+                                .send(TaskResult::FetchStatsResult(Err(SshError::NotConnected)));
+                        }
+                    }
+                    Task::FetchQuickStats => {
+                        if let Some(conn) = connection.as_ref() {
+                            let result = conn.fetch_quick_stats();
+                            let _ = result_sender.send(TaskResult::QuickStatsResult(result));
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::QuickStatsResult(Err(SshError::NotConnected)));
+                        }
+                    }
+                    Task::FindFiles(root, pattern, max_depth, time_limit) => {
+                        if let Some(conn) = connection.as_ref() {
+                            cancel.store(false, Ordering::Relaxed);
+                            let limits = FindLimits {
+                                max_depth,
+                                time_limit: Duration::from_secs(u64::from(time_limit)),
+                                max_results: MAX_FIND_RESULTS,
+                            };
+                            let result = conn
+                                .find(
+                                    &root,
+                                    &pattern,
+                                    limits,
+                                    &cancel,
+                                    &mut |progress| {
+                                        let _ = result_sender.send(TaskResult::Progress(progress));
+                                    },
+                                    &mut |path| {
+                                        let _ = result_sender.send(TaskResult::FindMatch(path));
+                                    },
+                                )
+                                .map_err(|e| e.with_context("Failed to search"));
+                            let _ = result_sender.send(TaskResult::FindFilesResult(result));
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::FindFilesResult(Err(SshError::NotConnected)));
+                        }
+                    }
+                    Task::MeasureLatency => {
+                        if let Some(conn) = connection.as_ref() {
+                            let result = conn.measure_latency();
+                            let _ = result_sender.send(TaskResult::LatencyResult(result));
+                        } else {
+                            let _ = result_sender
+                                .send(TaskResult::LatencyResult(Err(SshError::NotConnected)));
                         }
                     }
+                    Task::CheckConnection => {
+                        let alive = connection.as_ref().is_some_and(SSHConnection::is_alive);
+                        if !alive {
+                            stop_command.store(true, Ordering::Relaxed);
+                            stop_grep.store(true, Ordering::Relaxed);
+                            if let Some(mut conn) = connection.take() {
+                                conn.disconnect();
+                            }
+                        }
+                        let _ = result_sender.send(TaskResult::ConnectionChecked(alive));
+                    }
                 }
             }
         });
@@ -259,15 +1229,128 @@ impl BackgroundWorker {
             task_sender,
             result_receiver,
             connection: None,
+            cancel_flag,
+            queue,
+            command_cancel,
+            grep_cancel,
+            next_task_id: Cell::new(0),
+            latest_in_slot,
         }
     }
 
     /// Send a task to the worker thread
     fn send_task(&self, task: Task) {
-        let _ = self.task_sender.send(task);
+        let id = self.next_task_id.get();
+        self.next_task_id.set(id + 1);
+        if let Some(slot) = task.slot() {
+            self.latest_in_slot.lock().unwrap().insert(slot, id);
+        }
+        let _ = self.task_sender.send((id, task));
+    }
+
+    /// Whether a result answers a task that a later one of the same slot
+    /// superseded, e.g. a listing of a folder the user already left
+    fn is_superseded(&self, id: TaskId, result: &TaskResult) -> bool {
+        result
+            .slot()
+            .and_then(|slot| self.latest_in_slot.lock().unwrap().get(&slot).copied())
+            .is_some_and(|latest| latest != id)
+    }
+
+    /// Ask the worker to stop the running transfer
+    fn cancel(&self) {
+        self.cancel_flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Stop the command running from the command panel
+    fn stop_command(&self) {
+        self.command_cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Stop the running content search
+    fn stop_grep(&self) {
+        self.grep_cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// For a successful folder transfer in mirror mode, find what mirroring would delete
+fn plan_mirror(conn: &SSHConnection, transfer: &Transfer) -> Option<Result<MirrorPlan, SshError>> {
+    let plan = match transfer {
+        Transfer::DownloadDirectory {
+            remote,
+            local,
+            mirror: true,
+            ..
+        } => conn.plan_local_mirror(remote, local),
+        Transfer::UploadDirectory {
+            local,
+            remote,
+            mirror: true,
+            ..
+        } => conn.plan_remote_mirror(local, remote),
+        _ => return None,
+    };
+    Some(plan.map_err(|e| e.with_context("Failed to compare folders for mirroring")))
+}
+
+/// Convert a speed limit in KiB per second, as shown to the user, to bytes per second
+fn kib_to_bytes(kib: u32) -> u64 {
+    u64::from(kib) * 1024
+}
+
+/// Run a single queued transfer on the worker thread, reporting progress of
+/// directory transfers through `result_sender`
+fn run_transfer(
+    conn: &SSHConnection,
+    transfer: &Transfer,
+    cancel: &AtomicBool,
+    result_sender: &ResultSender,
+) -> Result<(), SshError> {
+    cancel.store(false, Ordering::Relaxed);
+    let mut progress = |progress| {
+        let _ = result_sender.send(TaskResult::Progress(progress));
+    };
+    if let Some(remote) = transfer
+        .upload_target()
+        .filter(|_| transfer.creates_parents())
+    {
+        conn.create_parent_directories(remote)?;
+    }
+    match transfer {
+        Transfer::DownloadFile { remote, local } => conn
+            .download_file(remote, local, cancel)
+            .map_err(|e| e.with_context("Failed to download")),
+        Transfer::UploadFile { local, remote, .. } => conn
+            .upload_file(local, remote, cancel)
+            .map_err(|e| e.with_context("Failed to upload")),
+        Transfer::DownloadDirectory {
+            remote,
+            local,
+            sync,
+            ..
+        } => conn
+            .download_directory(remote, local, *sync, cancel, &mut progress)
+            .map_err(|e| e.with_context("Failed to download directory")),
+        Transfer::UploadDirectory {
+            local,
+            remote,
+            sync,
+            ..
+        } => conn
+            .upload_directory(local, remote, *sync, cancel, &mut progress)
+            .map_err(|e| e.with_context("Failed to upload directory")),
     }
 }
 
+/// An action that would interrupt running transfers and awaits confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingExit {
+    /// The Disconnect button was clicked
+    Disconnect,
+    /// The application window is being closed
+    CloseWindow,
+}
+
 /// Represents the UI state
 pub struct UIState {
     /// The SSH hostname
@@ -276,24 +1359,101 @@ pub struct UIState {
     pub username: String,
     /// The SSH password
     pub password: String,
+    /// Whether the last connect attempt failed validation, so invalid fields are highlighted
+    pub validate_connect_form: bool,
+    /// Whether the password field shows its content in clear text
+    pub show_password: bool,
     /// The SSH port
     pub port: u16,
+    /// Algorithms to prefer during the handshake (blank fields use the defaults)
+    pub algorithms: AlgorithmPrefs,
     /// Whether currently connected or not
     pub connected: bool,
     /// The current remote directory path
     pub current_path: String,
-    /// List of files in the current directory
-    pub files: Vec<(String, bool)>,
+    /// The last directory that was listed successfully
+    pub listed_path: String,
+    /// When the path box was last edited, to debounce completion requests
+    pub path_edited_at: Option<Instant>,
+    /// The directory whose subdirectories were last requested for completion
+    pub completion_dir: Option<String>,
+    /// Subdirectory names of the directories listed this session, for completion
+    pub completion_cache: HashMap<String, Vec<String>>,
+    /// Previously visited directories, most recent last
+    pub back_history: Vec<String>,
+    /// Directories left with Back, most recent last
+    pub forward_history: Vec<String>,
+    /// A Back/Forward move waiting for its listing to succeed
+    pub history_move: Option<HistoryMove>,
+    /// Directories opened on this server, most recent first, without duplicates
+    pub recent_dirs: VecDeque<String>,
+    /// The entries of the current directory as listed
+    listed_files: Vec<FileEntry>,
+    /// The entries of the current directory that are shown, filtered and sorted by `view`
+    pub files: Vec<FileEntry>,
+    /// How the current directory listing was obtained
+    pub listing_source: ListingSource,
+    /// Whether the directory had too many entries and only the first ones are shown
+    pub listing_truncated: bool,
+    /// The resolved path of the listed directory, when the server reported it
+    pub canonical_path: Option<String>,
     /// Any error or status message to display
     pub error_message: Option<String>,
-    /// Whether dark mode is enabled
-    pub dark_mode: bool,
+    /// Diagnostic details (error codes) of the error in `error_message`
+    pub error_details: Option<String>,
     /// A list of saved connections
     pub saved_connections: Vec<SSHConnectionData>,
     /// If we are editing a file, store its remote path
     pub editing_file: Option<String>,
     /// The content of the file currently being edited
     pub file_content: String,
+    /// The raw bytes the edited file was loaded from, or last saved
+    pub file_bytes: Vec<u8>,
+    /// The bytes being saved, which become `file_bytes` once the save succeeds
+    pub saving_bytes: Option<Vec<u8>>,
+    /// The encoding the edited file is decoded from and saved in
+    pub file_encoding: &'static Encoding,
+    /// Text to append to the end of the file being edited
+    pub append_content: String,
+    /// The "go to line" input of the editor
+    pub goto_line_input: String,
+    /// A line (1-based) the editor should jump to on the next frame
+    pub goto_line_request: Option<usize>,
+    /// A file being opened and the line to show once it is loaded
+    open_at_line: Option<(String, usize)>,
+    /// Whether the editor shows a truncated, read-only preview of a large file
+    pub editing_preview: bool,
+    /// A file that was too large to edit (path, size in bytes), awaiting a decision
+    pub large_file_prompt: Option<(String, u64)>,
+    /// The edited file disappeared from the server before saving, awaiting a decision
+    pub missing_edit_target: bool,
+    /// Whether the About window is open
+    pub show_about: bool,
+    /// The recorded transfers, newest first, while the history window is open
+    transfer_history: Option<Vec<HistoryEntry>>,
+    /// The metadata shown in the properties dialog
+    properties: Option<FileProperties>,
+    /// What the About window shows about the current session
+    pub session_info: Option<SessionInfo>,
+    /// Size and modification time of the edited file when it was read
+    pub edit_version: Option<FileVersion>,
+    /// Set when saving found the edited file changed on the server: its
+    /// current version and content
+    pub changed_edit_target: Option<(FileVersion, Vec<u8>)>,
+    /// Set when the server refused to save the edited file; the edits stay in the editor
+    save_refused: Option<SaveRefusal>,
+    /// Where to save the edited file instead, in the refused save dialog
+    save_as_path: String,
+    /// The changes shown in the diff window, as (change, line); `None` separates hunks
+    pub diff_lines: Option<Vec<(Option<ChangeTag>, String)>>,
+    /// The editor text when the diff was last shown, for the review-before-save option
+    pub reviewed_content: Option<String>,
+    /// Whether the file being edited was just created from the file list
+    pub editing_new_file: bool,
+    /// Whether "Create" opens the new file in the editor
+    pub edit_after_create: bool,
+    /// The file being created, to open in the editor once it exists
+    pub created_file_to_edit: Option<String>,
     /// If we are renaming a file, store its name
     pub renaming_file: Option<String>,
     /// The new name for the file/directory being renamed
@@ -302,74 +1462,674 @@ pub struct UIState {
     pub new_directory_name: String,
     /// The name for new files
     pub new_file_name: String,
+    /// The octal permission mode for new directories
+    pub new_directory_mode: String,
+    /// The octal permission mode for new files
+    pub new_file_mode: String,
     /// The background worker to run tasks asynchronously
     worker: Arc<Mutex<BackgroundWorker>>,
     /// Shows if an operation is in progress to provide feedback to the user
     pub operation_in_progress: bool,
+    /// Whether a network error occurred and a reconnect should be offered
+    pub offer_reconnect: bool,
+    /// Progress of the running recursive operation
+    pub progress: Option<RecursiveProgress>,
+    /// Whether folder transfers skip files that are unchanged at the destination
+    pub skip_unchanged: bool,
+    /// Whether folder transfers offer to delete destination files missing from the source
+    pub mirror: bool,
+    /// Extra destination entries awaiting confirmation before being deleted
+    pub mirror_plan: Option<MirrorPlan>,
+    /// Whether the user acknowledged a large mirror deletion
+    pub mirror_acknowledged: bool,
+    /// A folder upload awaiting a decision about the remote files it would overwrite
+    pub upload_conflicts: Option<(Transfer, Vec<String>)>,
+    /// Whether uploads create missing directories above their target
+    pub create_parents: bool,
+    /// An upload that failed because the remote directory it goes to does not exist
+    pub missing_upload_parent: Option<Transfer>,
+    /// A directory awaiting confirmation before being deleted recursively
+    pub confirm_delete_directory: Option<String>,
+    /// An operation and the changes its dry run listed, waiting for confirmation
+    dry_run_plan: Option<(Destructive, Vec<PlannedChange>)>,
+    /// An action that would replace the file at this path, awaiting confirmation
+    confirm_replace: Option<(String, PathCheck)>,
+    /// A disconnect or window close waiting for the user to confirm interrupting transfers
+    pub pending_exit: Option<PendingExit>,
+    /// Set once the user confirmed closing the window so the close is not intercepted again
+    pub allow_close: bool,
 
     /// The current chosen language
     pub language: Language,
     /// The localizer that holds translations
     pub localizer: Localizer,
+    /// Persisted user preferences
+    pub settings: Settings,
     pub server_stats: Option<ServerStats>,
+    /// Whether server stats are fetched periodically
+    pub stats_auto_refresh: bool,
+    /// The figures shown in the header
+    pub quick_stats: Option<QuickStats>,
+    /// When the header figures were last requested
+    pub last_quick_stats_refresh: Option<Instant>,
+    /// When server stats were last requested
+    pub last_stats_refresh: Option<Instant>,
+    /// Recent (cpu %, memory %) samples, oldest first
+    pub stats_history: VecDeque<(f32, f32)>,
+    /// The command typed into the command panel
+    pub command_input: String,
+    /// The output of the last command run from the command panel
+    pub command_output: Option<CommandTranscript>,
+    /// Whether that command is still running
+    pub command_running: bool,
+    /// Whether that command was stopped before it finished
+    pub command_stopped: bool,
+    /// The interactive shell of the terminal panel, if one was opened
+    pub terminal: Option<TerminalSession>,
+    /// The name fragment to search for
+    pub find_pattern: String,
+    /// How many directory levels a search descends
+    pub find_max_depth: u32,
+    /// How many seconds a search may run on the server
+    pub find_time_limit: u32,
+    /// The paths found by the last search
+    pub find_results: Option<Vec<String>>,
+    /// The pattern and options of the content search panel
+    pub grep_query: GrepQuery,
+    /// The lines found by the last content search
+    pub grep_results: Option<Vec<GrepMatch>>,
+    /// Whether a content search is running
+    pub grep_running: bool,
+    /// Set while a lost connection is re-established, to keep the navigation history
+    pub reconnecting: bool,
+    /// The authentication attempt of the connection being made
+    auth_step: Option<AuthStep>,
+    /// Questions of the server waiting for the user's answers, see [`show_auth_questions`]
+    auth_questions: Option<PendingAuthQuestions>,
+    /// Set until the directory opened right after connecting has been listed
+    pub restoring_path: bool,
+    /// The file to hard link and the path of the new link, while the link dialog is open
+    pub hardlink: Option<(String, String)>,
+    /// Text waiting to be written into a file without opening it, see [`show_write_into_dialog`]
+    write_into: Option<WriteInto>,
+    /// The layout and order of the current directory
+    pub view: DirectoryView,
+    /// Names of the entries of the current directory selected for a batch action
+    pub selected: HashSet<String>,
+    /// The entry last ticked or unticked, where a shift-click range starts
+    selection_anchor: Option<String>,
+    /// Scroll the file list back to the top, after moving to another directory
+    pub scroll_to_top: bool,
+    /// The label of the connection, shown in a banner while connected
+    pub connection_label: String,
+    /// The accent color of the connection banner as sRGB
+    pub connection_color: Option<[u8; 3]>,
+    /// The window title last set, to change it only when needed
+    pub window_title: String,
+    /// The directory to open after connecting, taken from a pasted URL
+    pub initial_path: Option<String>,
+    /// Whether changes run through sudo
+    pub use_sudo: bool,
+    /// The sudo password; blank uses the login password
+    pub sudo_password: String,
+    /// The last measured round-trip time to the server
+    pub latency: Option<Duration>,
+    /// When the latency was last measured
+    pub last_latency_check: Option<Instant>,
+    /// When the connection was last checked for being alive
+    pub last_connection_check: Option<Instant>,
+    /// Short-lived notifications shown in the corner of the window
+    pub toasts: Vec<Toast>,
+}
+
+/// Direction of a move through the navigation history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryMove {
+    Back,
+    Forward,
+}
+
+/// A short notification that disappears on its own
+pub struct Toast {
+    pub message: String,
+    pub shown_at: Instant,
+}
+
+/// An interactive shell shown in the terminal panel
+pub struct TerminalSession {
+    /// Keystrokes and size changes for the shell; dropping it ends the shell
+    pub input: Sender<TerminalInput>,
+    pub screen: Screen,
+    /// Whether the shell is still running
+    pub running: bool,
+}
+
+/// The output of a command run from the command panel, with stdout and
+/// stderr interleaved in the order they arrived
+#[derive(Debug, Default)]
+pub struct CommandTranscript {
+    /// Consecutive output of one stream is kept in a single chunk
+    pub chunks: Vec<(OutputStream, String)>,
+    /// The exit status, once the command has finished
+    pub exit_code: Option<i32>,
+}
+
+impl CommandTranscript {
+    /// Add output that just arrived
+    fn push(&mut self, stream: OutputStream, text: &str) {
+        match self.chunks.last_mut() {
+            Some((last, chunk)) if *last == stream => chunk.push_str(text),
+            _ => self.chunks.push((stream, text.to_string())),
+        }
+    }
+
+    /// The output as plain text, stdout and stderr in arrival order
+    fn text(&self) -> String {
+        self.chunks.iter().map(|(_, text)| text.as_str()).collect()
+    }
+
+    /// The output as one text in a single color per stream
+    fn layout(&self, ui: &egui::Ui) -> egui::text::LayoutJob {
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let mut job = egui::text::LayoutJob::default();
+        for (stream, text) in &self.chunks {
+            let color = match stream {
+                OutputStream::Stdout => ui.visuals().text_color(),
+                OutputStream::Stderr => egui::Color32::from_rgb(255, 140, 0),
+            };
+            job.append(text, 0.0, egui::TextFormat::simple(font.clone(), color));
+        }
+        job
+    }
 }
 
 impl Default for UIState {
     fn default() -> Self {
-        Self {
+        let settings = load_settings();
+        let view = settings.view_for("");
+        let (saved_connections, skipped_connections) = load_saved_connections();
+        let mut state = Self {
             hostname: String::new(),
-            username: String::new(),
+            username: settings.default_username.clone(),
             password: String::new(),
-            port: 22,
+            validate_connect_form: false,
+            show_password: false,
+            port: settings.default_port,
+            algorithms: AlgorithmPrefs::default(),
             connected: false,
             current_path: "/".to_string(),
+            listed_path: "/".to_string(),
+            path_edited_at: None,
+            completion_dir: None,
+            completion_cache: HashMap::new(),
+            back_history: Vec::new(),
+            recent_dirs: VecDeque::new(),
+            forward_history: Vec::new(),
+            history_move: None,
+            listed_files: Vec::new(),
             files: Vec::new(),
+            listing_source: ListingSource::Sftp,
+            listing_truncated: false,
+            canonical_path: None,
             error_message: None,
-            dark_mode: true,
-            saved_connections: load_saved_connections(),
+            error_details: None,
+            saved_connections,
             editing_file: None,
             file_content: String::new(),
+            file_bytes: Vec::new(),
+            saving_bytes: None,
+            file_encoding: encoding_rs::UTF_8,
+            append_content: String::new(),
+            goto_line_input: String::new(),
+            goto_line_request: None,
+            open_at_line: None,
+            editing_preview: false,
+            large_file_prompt: None,
+            missing_edit_target: false,
+            show_about: false,
+            transfer_history: None,
+            properties: None,
+            session_info: None,
+            edit_version: None,
+            changed_edit_target: None,
+            save_refused: None,
+            save_as_path: String::new(),
+            editing_new_file: false,
+            diff_lines: None,
+            reviewed_content: None,
+            edit_after_create: false,
+            created_file_to_edit: None,
             renaming_file: None,
             new_name: String::new(),
             new_directory_name: String::new(),
             new_file_name: String::new(),
+            new_directory_mode: format!("{:o}", DEFAULT_DIRECTORY_MODE),
+            new_file_mode: format!("{:o}", DEFAULT_FILE_MODE),
             worker: Arc::new(Mutex::new(BackgroundWorker::new())),
             operation_in_progress: false,
+            offer_reconnect: false,
+            progress: None,
+            skip_unchanged: false,
+            mirror: false,
+            mirror_plan: None,
+            upload_conflicts: None,
+            create_parents: false,
+            missing_upload_parent: None,
+            mirror_acknowledged: false,
+            confirm_delete_directory: None,
+            dry_run_plan: None,
+            confirm_replace: None,
+            pending_exit: None,
+            allow_close: false,
             language: Language::English,
 
             localizer: Localizer::new(),
+            settings,
             server_stats: None,
+            stats_auto_refresh: false,
+            quick_stats: None,
+            last_quick_stats_refresh: None,
+            last_stats_refresh: None,
+            stats_history: VecDeque::with_capacity(STATS_HISTORY_LEN),
+            command_input: String::new(),
+            command_output: None,
+            command_running: false,
+            command_stopped: false,
+            terminal: None,
+            find_pattern: String::new(),
+            find_max_depth: 10,
+            find_time_limit: 30,
+            find_results: None,
+            grep_query: GrepQuery::default(),
+            grep_results: None,
+            grep_running: false,
+            connection_label: String::new(),
+            connection_color: None,
+            window_title: APP_TITLE.to_string(),
+            initial_path: None,
+            hardlink: None,
+            write_into: None,
+            selected: HashSet::new(),
+            selection_anchor: None,
+            view,
+            scroll_to_top: false,
+            reconnecting: false,
+            auth_step: None,
+            auth_questions: None,
+            restoring_path: false,
+            use_sudo: false,
+            sudo_password: String::new(),
+            latency: None,
+            last_latency_check: None,
+            last_connection_check: None,
+            toasts: Vec::new(),
+        };
+        if let Err(e) = logging::init(state.settings.log_level, &state.settings.log_file) {
+            state.error_message = Some(e);
         }
+        let timeout = state.operation_timeout();
+        let worker = state.worker.lock().unwrap();
+        worker.send_task(Task::SetTimeout(timeout));
+        worker.send_task(Task::SetTransferRetries(state.settings.transfer_retries));
+        worker.send_task(Task::SetSpeedLimit(state.settings.speed_limit_kib));
+        drop(worker);
+        if let Some((skipped, backup)) = skipped_connections {
+            state.error_message = Some(format!(
+                "{} {} ({})",
+                state
+                    .localizer
+                    .t(state.language, "saved_connections_skipped"),
+                skipped,
+                backup
+            ));
+        }
+        state
     }
 }
 
-/// Render the UI and handle events
-pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Option<SSHConnection>) {
-    let ctx = ui.ctx();
-    apply_theme(ctx, state.dark_mode);
+impl UIState {
+    /// Whether transfers are running or queued, paused or not, that
+    /// disconnecting or quitting would drop
+    fn busy(&self) -> bool {
+        self.connected && self.worker.lock().unwrap().queue.lock().unwrap().is_busy()
+    }
 
-    poll_worker(state);
+    /// The largest file size, in bytes, that is loaded fully into the editor
+    fn max_edit_size(&self) -> u64 {
+        self.settings.max_edit_size_mb * 1024 * 1024
+    }
 
-    ui.horizontal(|ui| {
-        ui.label(state.localizer.t(state.language, "theme_label"));
+    /// The sync mode for new folder uploads and downloads
+    fn sync_mode(&self) -> SyncMode {
+        if self.skip_unchanged {
+            SyncMode::SkipUnchanged
+        } else {
+            SyncMode::Always
+        }
+    }
 
-        if ui
-            .button(if state.dark_mode {
-                state.localizer.t(state.language, "switch_light_mode")
-            } else {
-                state.localizer.t(state.language, "switch_dark_mode")
-            })
-            .clicked()
-        {
-            state.dark_mode = !state.dark_mode;
+    /// Add a transfer to the queue and wake the worker up unless the queue is paused
+    fn enqueue(&mut self, transfer: Transfer) {
+        let worker = self.worker.lock().unwrap();
+        let mut queue = worker.queue.lock().unwrap();
+        queue.push(transfer);
+        if !queue.paused {
+            self.operation_in_progress = true;
+            worker.send_task(Task::ProcessQueue);
         }
+    }
 
-        ui.label("Language:");
-        egui::ComboBox::from_label("")
-            .selected_text(format!("{:?}", state.language))
-            .show_ui(ui, |ui| {
-                if ui.button("English").clicked() {
-                    state.language = Language::English;
+    /// Pause or resume the transfer queue
+    fn set_queue_paused(&mut self, paused: bool) {
+        let worker = self.worker.lock().unwrap();
+        let mut queue = worker.queue.lock().unwrap();
+        queue.paused = paused;
+        if !paused && queue.has_pending() {
+            self.operation_in_progress = true;
+            worker.send_task(Task::ProcessQueue);
+        }
+    }
+
+    /// Go to the previous or next directory of the navigation history.
+    /// The history itself only changes once the listing succeeds.
+    fn move_in_history(&mut self, direction: HistoryMove) {
+        let target = match direction {
+            HistoryMove::Back => self.back_history.last(),
+            HistoryMove::Forward => self.forward_history.last(),
+        };
+        let Some(path) = target.cloned() else {
+            return;
+        };
+        self.history_move = Some(direction);
+        self.current_path = path.clone();
+        self.operation_in_progress = true;
+        self.worker
+            .lock()
+            .unwrap()
+            .send_task(Task::ListDirectory(path));
+    }
+
+    /// Record a successful listing of `path` in the navigation history
+    fn record_history(&mut self, path: &str) {
+        match self.history_move.take() {
+            Some(HistoryMove::Back) => {
+                self.back_history.pop();
+                self.forward_history.push(self.listed_path.clone());
+            }
+            Some(HistoryMove::Forward) => {
+                self.forward_history.pop();
+                self.back_history.push(self.listed_path.clone());
+            }
+            None if path != self.listed_path => {
+                self.back_history.push(self.listed_path.clone());
+                self.forward_history.clear();
+            }
+            None => {}
+        }
+        if self.back_history.len() > HISTORY_LEN {
+            self.back_history.remove(0);
+        }
+    }
+
+    /// Put a directory at the top of the Recent list and remember the list for this server
+    fn record_recent_dir(&mut self, path: &str) {
+        if self
+            .recent_dirs
+            .front()
+            .is_some_and(|recent| recent == path)
+        {
+            return;
+        }
+        self.recent_dirs.retain(|recent| recent != path);
+        self.recent_dirs.push_front(path.to_string());
+        self.recent_dirs.truncate(RECENT_DIRS_LEN);
+        let key = self.connection_key();
+        self.settings
+            .recent_dirs
+            .insert(key, self.recent_dirs.iter().cloned().collect());
+        save_settings(&self.settings);
+    }
+
+    /// Go back to the login form after the session ended
+    fn end_session(&mut self) {
+        self.connected = false;
+        // Dropping its input ends the shell
+        self.terminal = None;
+        self.offer_reconnect = false;
+        self.files.clear();
+        self.current_path = "/".to_string();
+        self.canonical_path = None;
+        self.latency = None;
+        self.last_latency_check = None;
+        self.last_connection_check = None;
+        self.quick_stats = None;
+        self.last_quick_stats_refresh = None;
+        self.recent_dirs.clear();
+        self.completion_cache.clear();
+        self.clear_history();
+    }
+
+    /// Forget the navigation history, e.g. when the connection changes
+    fn clear_history(&mut self) {
+        self.back_history.clear();
+        self.forward_history.clear();
+        self.history_move = None;
+    }
+
+    /// Encode the whole text of the edited file in its encoding, with the byte
+    /// order mark it was loaded with, reporting characters it cannot represent
+    fn encode_for_file(&mut self, text: &str) -> Option<Vec<u8>> {
+        let encoded = text_encoding::encode_document(text, self.file_encoding, &self.file_bytes);
+        self.report_unencodable(encoded)
+    }
+
+    /// Encode text added to the edited file, which has no byte order mark of its own
+    fn encode_fragment(&mut self, text: &str) -> Option<Vec<u8>> {
+        let encoded = text_encoding::encode(text, self.file_encoding);
+        self.report_unencodable(encoded)
+    }
+
+    /// The encoded text, or `None` after showing why it could not be encoded
+    fn report_unencodable(&mut self, encoded: Result<Vec<u8>, String>) -> Option<Vec<u8>> {
+        match encoded {
+            Ok(bytes) => Some(bytes),
+            Err(message) => {
+                self.error_message = Some(message);
+                None
+            }
+        }
+    }
+
+    /// How long one operation may wait for the server, from the settings
+    fn operation_timeout(&self) -> Option<Duration> {
+        Some(self.settings.operation_timeout_secs)
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// The encoding chosen for opening files, if detection is turned off
+    fn default_encoding(&self) -> Option<&'static Encoding> {
+        self.settings
+            .default_encoding
+            .as_deref()
+            .and_then(|label| Encoding::for_label(label.as_bytes()))
+    }
+
+    /// Whether the editor holds edits that were not saved
+    fn editor_modified(&self) -> bool {
+        text_encoding::decode(&self.file_bytes, self.file_encoding) != self.file_content
+    }
+
+    /// Load a decoded file into the editor
+    fn open_in_editor(&mut self, path: String, decoded: DecodedText, preview: bool) {
+        self.editing_file = Some(path);
+        self.editing_new_file = false;
+        self.edit_version = None;
+        self.changed_edit_target = None;
+        self.save_refused = None;
+        self.diff_lines = None;
+        self.reviewed_content = None;
+        self.editing_preview = preview;
+        self.file_content = decoded.text;
+        self.file_bytes = decoded.bytes;
+        self.saving_bytes = None;
+        self.file_encoding = decoded.encoding;
+        self.goto_line_request = self
+            .open_at_line
+            .take()
+            .filter(|(target, _)| Some(target) == self.editing_file.as_ref())
+            .map(|(_, line)| line);
+    }
+
+    /// Identifies a directory of the current server in the remembered views
+    fn directory_key(&self, path: &str) -> String {
+        format!("{}{}", self.connection_key(), path)
+    }
+
+    /// Identifies the current server in remembered settings, as `user@host:port`
+    fn connection_key(&self) -> String {
+        format!("{}@{}:{}", self.username, self.hostname, self.port)
+    }
+
+    /// Show the current directory with `view` and remember it for this
+    /// directory, writing the settings file if `save` is set
+    fn set_view(&mut self, view: DirectoryView, save: bool) {
+        let key = self.directory_key(&self.listed_path);
+        self.settings.set_view(key, view.clone());
+        if save {
+            save_settings(&self.settings);
+        }
+        self.view = view;
+        self.show_files();
+    }
+
+    /// Pick the listed entries the view shows and order them by its sort key, directories first
+    fn show_files(&mut self) {
+        let view = &self.view;
+        let filter = view.filter.to_lowercase();
+        self.files = self
+            .listed_files
+            .iter()
+            .filter(|entry| view.show_hidden || !entry.name.starts_with('.'))
+            .filter(|entry| filter.is_empty() || entry.name.to_lowercase().contains(&filter))
+            .cloned()
+            .collect();
+        self.files.sort_by(|a, b| {
+            let order = match view.sort {
+                SortKey::Name => a.name.cmp(&b.name),
+                SortKey::Modified => a.mtime.cmp(&b.mtime).then_with(|| a.name.cmp(&b.name)),
+                SortKey::Size => a.size.cmp(&b.size).then_with(|| a.name.cmp(&b.name)),
+            };
+            let order = if view.descending {
+                order.reverse()
+            } else {
+                order
+            };
+            b.is_dir.cmp(&a.is_dir).then(order)
+        });
+    }
+
+    /// Compare the editor's text with the file as loaded (or last saved),
+    /// i.e. `file_bytes` decoded with the chosen encoding, and open the diff window
+    fn show_diff(&mut self) {
+        let original = text_encoding::decode(&self.file_bytes, self.file_encoding);
+        self.show_diff_from(&original);
+    }
+
+    /// Show the edits as a diff from `original`
+    fn show_diff_from(&mut self, original: &str) {
+        let diff = TextDiff::from_lines(original, &self.file_content);
+        let mut lines = Vec::new();
+        for (index, group) in diff.grouped_ops(DIFF_CONTEXT_LINES).iter().enumerate() {
+            if index > 0 {
+                lines.push((None, "…".to_string()));
+            }
+            for op in group {
+                for change in diff.iter_changes(op) {
+                    let text = change.value().trim_end_matches(['\r', '\n']).to_string();
+                    lines.push((Some(change.tag()), text));
+                }
+            }
+        }
+        self.diff_lines = Some(lines);
+        self.reviewed_content = Some(self.file_content.clone());
+    }
+
+    /// Remember the port and username of the current connection as defaults for new ones
+    fn remember_connection_defaults(&mut self) {
+        if self.settings.default_port != self.port
+            || self.settings.default_username != self.username
+        {
+            self.settings.default_port = self.port;
+            self.settings.default_username = self.username.clone();
+            save_settings(&self.settings);
+        }
+    }
+
+    /// Show a toast notification
+    fn toast(&mut self, message: String) {
+        self.toasts.push(Toast {
+            message,
+            shown_at: Instant::now(),
+        });
+    }
+}
+
+/// Render the UI and handle events
+pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Option<SSHConnection>) {
+    let ctx = ui.ctx().clone();
+    apply_theme(&ctx, state.settings.theme);
+
+    poll_worker(state);
+    update_window_title(&ctx, state);
+    show_about_window(&ctx, state);
+    show_transfer_history(&ctx, state);
+    show_properties_window(&ctx, state);
+
+    if ctx.input(|i| i.viewport().close_requested())
+        && !state.allow_close
+        && state.busy()
+        && state.settings.confirmations.exit_with_transfers
+    {
+        ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        state.pending_exit = Some(PendingExit::CloseWindow);
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(state.localizer.t(state.language, "theme_label"));
+
+        let theme_key = |theme: ThemeMode| match theme {
+            ThemeMode::Dark => "theme_dark",
+            ThemeMode::Light => "theme_light",
+            ThemeMode::System => "theme_system",
+        };
+        egui::ComboBox::from_id_salt("theme_mode")
+            .selected_text(
+                state
+                    .localizer
+                    .t(state.language, theme_key(state.settings.theme)),
+            )
+            .show_ui(ui, |ui| {
+                for theme in [ThemeMode::Dark, ThemeMode::Light, ThemeMode::System] {
+                    if ui
+                        .selectable_value(
+                            &mut state.settings.theme,
+                            theme,
+                            state.localizer.t(state.language, theme_key(theme)),
+                        )
+                        .changed()
+                    {
+                        save_settings(&state.settings);
+                    }
+                }
+            });
+
+        ui.label("Language:");
+        egui::ComboBox::from_label("")
+            .selected_text(format!("{:?}", state.language))
+            .show_ui(ui, |ui| {
+                if ui.button("English").clicked() {
+                    state.language = Language::English;
                 }
                 if ui.button("Arabic").clicked() {
                     state.language = Language::Arabic;
@@ -381,10 +2141,216 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
                     state.language = Language::Chinese;
                 }
             });
+
+        ui.label(state.localizer.t(state.language, "default_encoding_label"));
+        let auto = state
+            .localizer
+            .t(state.language, "encoding_auto")
+            .to_string();
+        let mut default = state.default_encoding();
+        egui::ComboBox::from_id_salt("default_encoding")
+            .selected_text(default.map_or(auto.as_str(), |encoding| encoding.name()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut default, None, &auto);
+                for &encoding in ENCODINGS {
+                    ui.selectable_value(&mut default, Some(encoding), encoding.name());
+                }
+            })
+            .response
+            .on_hover_text(state.localizer.t(state.language, "default_encoding_hint"));
+        if default != state.default_encoding() {
+            state.settings.default_encoding = default.map(|encoding| encoding.name().to_string());
+            save_settings(&state.settings);
+        }
+
+        ui.label(state.localizer.t(state.language, "operation_timeout_label"));
+        let timeout = ui
+            .add(
+                egui::DragValue::new(&mut state.settings.operation_timeout_secs)
+                    .range(0..=3600)
+                    .suffix(" s"),
+            )
+            .on_hover_text(state.localizer.t(state.language, "operation_timeout_hint"));
+        if timeout.changed() {
+            save_settings(&state.settings);
+            let limit = state.operation_timeout();
+            state
+                .worker
+                .lock()
+                .unwrap()
+                .send_task(Task::SetTimeout(limit));
+        }
+
+        ui.label(state.localizer.t(state.language, "transfer_retries_label"));
+        let retries = ui
+            .add(egui::DragValue::new(&mut state.settings.transfer_retries).range(0..=10))
+            .on_hover_text(state.localizer.t(state.language, "transfer_retries_hint"));
+        if retries.changed() {
+            save_settings(&state.settings);
+            state
+                .worker
+                .lock()
+                .unwrap()
+                .send_task(Task::SetTransferRetries(state.settings.transfer_retries));
+        }
+
+        ui.label(state.localizer.t(state.language, "speed_limit_label"));
+        let speed_limit = ui
+            .add(speed_limit_drag_value(&mut state.settings.speed_limit_kib))
+            .on_hover_text(state.localizer.t(state.language, "speed_limit_hint"));
+        if speed_limit.changed() {
+            save_settings(&state.settings);
+            state
+                .worker
+                .lock()
+                .unwrap()
+                .send_task(Task::SetSpeedLimit(state.settings.speed_limit_kib));
+        }
+
+        show_logging_controls(ui, state);
+
+        show_confirmation_settings(ui, state);
+
+        ui.label(state.localizer.t(state.language, "max_edit_size_label"));
+        if ui
+            .add(
+                egui::DragValue::new(&mut state.settings.max_edit_size_mb)
+                    .range(1..=1024)
+                    .suffix(" MB"),
+            )
+            .changed()
+        {
+            save_settings(&state.settings);
+        }
+
+        if ui
+            .button(state.localizer.t(state.language, "transfer_history_button"))
+            .clicked()
+        {
+            state.transfer_history = Some(history::load());
+        }
+
+        if ui
+            .button(state.localizer.t(state.language, "about_button"))
+            .clicked()
+        {
+            state.show_about = true;
+            state.session_info = None;
+            state
+                .worker
+                .lock()
+                .unwrap()
+                .send_task(Task::FetchSessionInfo);
+        }
+
+        if state.connected {
+            let due = state
+                .last_latency_check
+                .is_none_or(|last| last.elapsed() >= LATENCY_REFRESH_INTERVAL);
+            // Only measure while idle, so the check does not wait behind a long transfer
+            if due && !state.operation_in_progress {
+                state.last_latency_check = Some(Instant::now());
+                state.worker.lock().unwrap().send_task(Task::MeasureLatency);
+            }
+            let check_due = state
+                .last_connection_check
+                .is_none_or(|last| last.elapsed() >= CONNECTION_CHECK_INTERVAL);
+            if check_due && !state.operation_in_progress {
+                state.last_connection_check = Some(Instant::now());
+                state
+                    .worker
+                    .lock()
+                    .unwrap()
+                    .send_task(Task::CheckConnection);
+            }
+            ui.ctx().request_repaint_after(LATENCY_REFRESH_INTERVAL);
+
+            let latency = match state.latency {
+                Some(latency) => format!("{} ms", latency.as_millis()),
+                None => "-".to_string(),
+            };
+            ui.label(format!(
+                "{} {}",
+                state.localizer.t(state.language, "latency_label"),
+                latency
+            ));
+
+            show_sudo_controls(ui, state);
+
+            if ui
+                .checkbox(
+                    &mut state.settings.dry_run,
+                    state.localizer.t(state.language, "dry_run_checkbox"),
+                )
+                .on_hover_text(state.localizer.t(state.language, "dry_run_hint"))
+                .changed()
+            {
+                save_settings(&state.settings);
+            }
+        }
     });
 
     if state.operation_in_progress {
-        ui.label(state.localizer.t(state.language, "operation_in_progress"));
+        ui.horizontal(|ui| {
+            ui.label(state.localizer.t(state.language, "operation_in_progress"));
+            let (finished, total) = state
+                .worker
+                .lock()
+                .unwrap()
+                .queue
+                .lock()
+                .unwrap()
+                .batch_progress();
+            if total > 1 {
+                ui.add(
+                    egui::ProgressBar::new(finished as f32 / total as f32)
+                        .desired_width(150.0)
+                        .text(format!(
+                            "{} {} / {}",
+                            state.localizer.t(state.language, "progress_transfers"),
+                            finished,
+                            total
+                        )),
+                );
+            }
+            match &state.progress {
+                Some(RecursiveProgress::Enumerating { found, current }) => {
+                    ui.label(format!(
+                        "{} {} ({})",
+                        state.localizer.t(state.language, "progress_enumerating"),
+                        found,
+                        current
+                    ));
+                }
+                Some(RecursiveProgress::Item {
+                    done,
+                    total,
+                    current,
+                }) => {
+                    ui.add(
+                        egui::ProgressBar::new(*done as f32 / (*total).max(1) as f32)
+                            .desired_width(150.0),
+                    );
+                    ui.label(format!(
+                        "{} {} / {} ({})",
+                        state.localizer.t(state.language, "progress_item"),
+                        done,
+                        total,
+                        current
+                    ));
+                }
+                None => {}
+            }
+            if state.connected
+                && ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+            {
+                state.worker.lock().unwrap().cancel();
+            }
+        });
+        // Keep polling the worker while it is busy
+        ui.ctx().request_repaint_after(Duration::from_millis(100));
     }
 
     if !state.connected {
@@ -401,16 +2367,25 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
                 .selected_text(state.localizer.t(state.language, "choose_a_connection"))
                 .show_ui(ui, |ui| {
                     for saved_conn in &state.saved_connections {
-                        if ui
-                            .button(format!(
-                                "{}@{}:{}",
-                                saved_conn.username, saved_conn.hostname, saved_conn.port
-                            ))
-                            .clicked()
-                        {
+                        let address = format!(
+                            "{}@{}:{}",
+                            saved_conn.username, saved_conn.hostname, saved_conn.port
+                        );
+                        let mut text = if saved_conn.label.is_empty() {
+                            egui::RichText::new(address)
+                        } else {
+                            egui::RichText::new(format!("{} — {}", saved_conn.label, address))
+                        };
+                        if let Some([r, g, b]) = saved_conn.color {
+                            text = text.color(egui::Color32::from_rgb(r, g, b));
+                        }
+                        if ui.button(text).clicked() {
                             state.hostname = saved_conn.hostname.clone();
                             state.username = saved_conn.username.clone();
                             state.port = saved_conn.port;
+                            state.algorithms = saved_conn.algorithms.clone();
+                            state.connection_label = saved_conn.label.clone();
+                            state.connection_color = saved_conn.color;
                         }
                     }
                 });
@@ -419,24 +2394,114 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
             }
         });
 
+        // Pressing Enter in any field of the form connects, like clicking Connect
+        let mut submitted = false;
+        let enter_pressed = |ui: &egui::Ui, response: &egui::Response| {
+            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))
+        };
+
         ui.horizontal(|ui| {
             ui.label(state.localizer.t(state.language, "hostname_label"));
-            ui.text_edit_singleline(&mut state.hostname);
+            let response = ui
+                .text_edit_singleline(&mut state.hostname)
+                .on_hover_text(state.localizer.t(state.language, "hostname_paste_hint"));
+            submitted |= enter_pressed(ui, &response);
+            let pasted = ui.input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Paste(_))));
+            if response.changed() && pasted {
+                if let Some(target) = parse_connection_target(&state.hostname) {
+                    state.hostname = target.hostname;
+                    if let Some(username) = target.username {
+                        state.username = username;
+                    }
+                    if let Some(port) = target.port {
+                        state.port = port;
+                    }
+                    state.initial_path = target.path;
+                }
+            }
+            if state.validate_connect_form {
+                if let Err(key) = validate_hostname(&state.hostname) {
+                    mark_invalid(ui, &response);
+                    ui.colored_label(egui::Color32::RED, state.localizer.t(state.language, key));
+                }
+            }
         });
 
         ui.horizontal(|ui| {
             ui.label(state.localizer.t(state.language, "username_label"));
-            ui.text_edit_singleline(&mut state.username);
+            let response = ui.text_edit_singleline(&mut state.username);
+            submitted |= enter_pressed(ui, &response);
         });
 
         ui.horizontal(|ui| {
             ui.label(state.localizer.t(state.language, "password_label"));
-            ui.add(egui::TextEdit::singleline(&mut state.password).password(true));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut state.password).password(!state.show_password),
+            );
+            submitted |= enter_pressed(ui, &response);
+            let toggle_key = if state.show_password {
+                "hide_password"
+            } else {
+                "show_password"
+            };
+            ui.toggle_value(&mut state.show_password, "👁")
+                .on_hover_text(state.localizer.t(state.language, toggle_key));
         });
 
         ui.horizontal(|ui| {
             ui.label(state.localizer.t(state.language, "port_label"));
-            ui.add(egui::DragValue::new(&mut state.port).range(1..=65535));
+            let response = ui.add(egui::DragValue::new(&mut state.port).range(1..=65535));
+            if state.validate_connect_form && state.port == 0 {
+                mark_invalid(ui, &response);
+                ui.colored_label(
+                    egui::Color32::RED,
+                    state.localizer.t(state.language, "port_invalid"),
+                );
+            }
+        });
+
+        ui.collapsing(
+            state.localizer.t(state.language, "advanced_algorithms"),
+            |ui| {
+                ui.label(state.localizer.t(state.language, "algorithms_hint"));
+                egui::Grid::new("algorithm_prefs").show(ui, |ui| {
+                    let fields = [
+                        ("kex_label", &mut state.algorithms.kex),
+                        ("host_key_label", &mut state.algorithms.host_key),
+                        ("ciphers_label", &mut state.algorithms.ciphers),
+                        ("macs_label", &mut state.algorithms.macs),
+                    ];
+                    for (key, value) in fields {
+                        ui.label(state.localizer.t(state.language, key));
+                        ui.text_edit_singleline(value);
+                        ui.end_row();
+                    }
+                });
+            },
+        );
+
+        ui.horizontal(|ui| {
+            ui.label(state.localizer.t(state.language, "connection_label_label"));
+            ui.add(
+                egui::TextEdit::singleline(&mut state.connection_label)
+                    .hint_text(state.localizer.t(state.language, "connection_label_hint"))
+                    .desired_width(140.0),
+            );
+            let mut use_color = state.connection_color.is_some();
+            if ui
+                .checkbox(
+                    &mut use_color,
+                    state
+                        .localizer
+                        .t(state.language, "connection_color_checkbox"),
+                )
+                .changed()
+            {
+                state.connection_color = use_color.then_some([200, 30, 30]);
+            }
+            if let Some(color) = &mut state.connection_color {
+                ui.color_edit_button_srgb(color);
+            }
         });
 
         if ui
@@ -447,39 +2512,126 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
                 hostname: state.hostname.clone(),
                 username: state.username.clone(),
                 port: state.port,
+                algorithms: state.algorithms.clone(),
+                label: state.connection_label.trim().to_string(),
+                color: state.connection_color,
             };
-            if !state.saved_connections.contains(&new_conn) {
-                state.saved_connections.push(new_conn);
-                save_connections(&state.saved_connections);
+            // Saving the same server again updates its label, color and algorithms
+            let existing = state.saved_connections.iter_mut().find(|saved| {
+                saved.hostname == new_conn.hostname
+                    && saved.username == new_conn.username
+                    && saved.port == new_conn.port
+            });
+            match existing {
+                Some(saved) if *saved == new_conn => {}
+                Some(saved) => {
+                    *saved = new_conn;
+                    save_connections(&state.saved_connections);
+                }
+                None => {
+                    state.saved_connections.push(new_conn);
+                    save_connections(&state.saved_connections);
+                }
             }
         }
 
-        if ui
-            .button(state.localizer.t(state.language, "connect_button"))
+        // Disabled while connecting so a double click cannot start a second session
+        let connect_clicked = ui
+            .add_enabled(
+                !state.operation_in_progress,
+                egui::Button::new(state.localizer.t(state.language, "connect_button")),
+            )
             .clicked()
-        {
+            || (submitted && !state.operation_in_progress);
+        if connect_clicked {
+            // Invalid fields stay highlighted until the next successful click
+            state.validate_connect_form =
+                validate_hostname(&state.hostname).is_err() || state.port == 0;
+        }
+        if connect_clicked && !state.validate_connect_form {
+            state.hostname = state.hostname.trim().to_string();
             state.operation_in_progress = true;
             let worker = state.worker.clone();
             let hostname = state.hostname.clone();
             let username = state.username.clone();
             let password = state.password.clone();
             let port = state.port;
-            worker
-                .lock()
-                .unwrap()
-                .send_task(Task::Connect(hostname, username, password, port));
+            let algorithms = state.algorithms.clone();
+            worker.lock().unwrap().send_task(Task::Connect(
+                hostname, username, password, port, algorithms,
+            ));
         }
-
-        if let Some(error) = &state.error_message {
-            ui.colored_label(egui::Color32::RED, error);
+        // A host that does not answer would otherwise hold the form until the timeout
+        if state.operation_in_progress
+            && ui
+                .button(state.localizer.t(state.language, "cancel_connect_button"))
+                .clicked()
+        {
+            state.worker.lock().unwrap().cancel();
+        }
+        if let Some(step) = state
+            .auth_step
+            .as_ref()
+            .filter(|_| state.operation_in_progress)
+        {
+            let status = match step {
+                AuthStep::PublicKey(key) => format!(
+                    "{} {}",
+                    state.localizer.t(state.language, "auth_step_public_key"),
+                    key
+                ),
+                AuthStep::Partial(methods) => format!(
+                    "{} {}",
+                    state.localizer.t(state.language, "auth_step_partial"),
+                    methods
+                ),
+                AuthStep::KeyboardInteractive => state
+                    .localizer
+                    .t(state.language, "auth_step_keyboard_interactive")
+                    .to_string(),
+                AuthStep::Password => state
+                    .localizer
+                    .t(state.language, "auth_step_password")
+                    .to_string(),
+            };
+            ui.label(status);
         }
+        show_auth_questions(ui.ctx(), state);
+
+        show_status(ui, state);
     } else {
-        ui.collapsing("Dashboard", |ui| {
-            if ui.button("Refresh Stats").clicked() {
-                state.operation_in_progress = true;
+        show_connection_banner(ui, state);
+
+        show_quick_stats(ui, state);
+
+        if state.stats_auto_refresh {
+            let due = state
+                .last_stats_refresh
+                .is_none_or(|last| last.elapsed() >= STATS_REFRESH_INTERVAL);
+            if due {
+                state.last_stats_refresh = Some(Instant::now());
                 let worker = state.worker.clone();
                 worker.lock().unwrap().send_task(Task::FetchStats);
             }
+            ui.ctx().request_repaint_after(STATS_REFRESH_INTERVAL);
+        }
+
+        ui.collapsing("Dashboard", |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Refresh Stats").clicked() {
+                    state.operation_in_progress = true;
+                    state.last_stats_refresh = Some(Instant::now());
+                    let worker = state.worker.clone();
+                    worker.lock().unwrap().send_task(Task::FetchStats);
+                }
+                if ui
+                    .checkbox(&mut state.stats_auto_refresh, "Auto-refresh")
+                    .changed()
+                    && !state.stats_auto_refresh
+                {
+                    state.stats_history.clear();
+                }
+            });
 
             if let Some(stats) = &state.server_stats {
                 ui.label(format!("CPU Usage:\n  {}", stats.cpu_usage));
@@ -488,56 +2640,278 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
             } else {
                 ui.label("No stats available. Click 'Refresh Stats' to fetch.");
             }
-        });
-        ui.heading(state.localizer.t(state.language, "ssh_file_manager"));
 
-        ui.horizontal(|ui| {
-            ui.label(state.localizer.t(state.language, "current_path_label"));
-            if ui
-                .text_edit_singleline(&mut state.current_path)
-                .lost_focus()
-                && ui.input(|state| state.key_pressed(egui::Key::Enter))
-            {
-                state.operation_in_progress = true;
-                let worker = state.worker.clone();
-                let path = state.current_path.clone();
-                worker.lock().unwrap().send_task(Task::ListDirectory(path));
+            if state.stats_auto_refresh && !state.stats_history.is_empty() {
+                let cpu: Vec<f32> = state.stats_history.iter().map(|s| s.0).collect();
+                let memory: Vec<f32> = state.stats_history.iter().map(|s| s.1).collect();
+                ui.horizontal(|ui| {
+                    ui.label(format!("CPU: {:.1}%", cpu.last().unwrap()));
+                    draw_sparkline(ui, &cpu, egui::Color32::LIGHT_BLUE);
+                });
+                ui.horizontal(|ui| {
+                    ui.label(format!("Memory: {:.1}%", memory.last().unwrap()));
+                    draw_sparkline(ui, &memory, egui::Color32::LIGHT_GREEN);
+                });
             }
         });
-
-        ui.horizontal(|ui| {
-            ui.label(state.localizer.t(state.language, "create_directory_label"));
-            ui.text_edit_singleline(&mut state.new_directory_name);
-            if ui
-                .button(state.localizer.t(state.language, "create_label"))
-                .clicked()
-            {
-                if !state.new_directory_name.is_empty() {
-                    let full_path = format!("{}/{}", state.current_path, state.new_directory_name);
-                    state.operation_in_progress = true;
-                    state.new_directory_name.clear();
+        let mut save_output = None;
+        ui.collapsing(state.localizer.t(state.language, "command_panel"), |ui| {
+            ui.horizontal(|ui| {
+                let input = ui.text_edit_singleline(&mut state.command_input);
+                let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if state.command_running {
+                    ui.spinner();
+                    if ui
+                        .button(state.localizer.t(state.language, "stop_button"))
+                        .clicked()
+                    {
+                        state.worker.lock().unwrap().stop_command();
+                    }
+                } else if (ui
+                    .button(state.localizer.t(state.language, "run_button"))
+                    .clicked()
+                    || submitted)
+                    && !state.command_input.trim().is_empty()
+                {
+                    state.command_running = true;
+                    state.command_stopped = false;
+                    state.command_output = Some(CommandTranscript::default());
                     let worker = state.worker.clone();
                     worker
                         .lock()
                         .unwrap()
-                        .send_task(Task::CreateDirectory(full_path));
-                } else {
-                    state.error_message = Some(
-                        state
-                            .localizer
-                            .t(state.language, "directory_name_empty_error")
-                            .to_string(),
+                        .send_task(Task::RunCommand(state.command_input.clone()));
+                }
+            });
+
+            if let Some(output) = &state.command_output {
+                ui.horizontal(|ui| {
+                    let has_output = !output.chunks.is_empty();
+                    if ui
+                        .add_enabled(
+                            has_output,
+                            egui::Button::new(state.localizer.t(state.language, "copy_button")),
+                        )
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(output.text());
+                    }
+                    if ui
+                        .add_enabled(
+                            has_output,
+                            egui::Button::new(
+                                state.localizer.t(state.language, "save_output_button"),
+                            ),
+                        )
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_file_name("output.txt")
+                            .save_file()
+                        {
+                            save_output = Some((path, output.text()));
+                        }
+                    }
+                });
+                if state.command_running {
+                    ui.label(state.localizer.t(state.language, "command_running"));
+                } else if state.command_stopped {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        state.localizer.t(state.language, "status_command_stopped"),
+                    );
+                } else if let Some(exit_code) = output.exit_code {
+                    let exit_color = if exit_code == 0 {
+                        egui::Color32::GREEN
+                    } else {
+                        egui::Color32::RED
+                    };
+                    ui.colored_label(
+                        exit_color,
+                        format!(
+                            "{} {}",
+                            state.localizer.t(state.language, "exit_code_label"),
+                            exit_code
+                        ),
                     );
                 }
+                egui::ScrollArea::vertical()
+                    .id_salt("command_output")
+                    .max_height(200.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if !output.chunks.is_empty() {
+                            ui.label(output.layout(ui));
+                        }
+                    });
             }
         });
-
+        if let Some((path, text)) = save_output {
+            match std::fs::write(&path, text) {
+                Ok(()) => state.toast(format!(
+                    "{} {}",
+                    state.localizer.t(state.language, "output_saved"),
+                    path.display()
+                )),
+                Err(e) => {
+                    state.error_message = Some(format!(
+                        "{} {}",
+                        state.localizer.t(state.language, "output_save_failed"),
+                        e
+                    ))
+                }
+            }
+        }
+
+        show_terminal_panel(ui, state);
+
+        show_find_panel(ui, state);
+
+        show_grep_panel(ui, state);
+
+        ui.heading(state.localizer.t(state.language, "ssh_file_manager"));
+
+        ui.horizontal(|ui| {
+            ui.label(state.localizer.t(state.language, "current_path_label"));
+            // Tab accepts a completion instead of moving the focus
+            let response = ui
+                .add(egui::TextEdit::singleline(&mut state.current_path).lock_focus(true))
+                .on_hover_text(state.localizer.t(state.language, "path_box_hint"));
+            if ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::L)) {
+                response.request_focus();
+            }
+            if response.changed() {
+                state.path_edited_at = Some(Instant::now());
+            }
+            if response.lost_focus() && ui.input(|state| state.key_pressed(egui::Key::Enter)) {
+                state.operation_in_progress = true;
+                let worker = state.worker.clone();
+                // Paths not starting with a slash are relative to the listed directory
+                let path = resolve_remote_path(&state.listed_path, state.current_path.trim());
+                state.current_path = path.clone();
+                worker
+                    .lock()
+                    .unwrap()
+                    .send_task(Task::CheckPath(path, PathCheck::Open));
+            }
+            show_path_completions(ui, state, &response);
+            if let Some(real) = state
+                .canonical_path
+                .as_ref()
+                .filter(|real| **real != state.listed_path)
+            {
+                ui.label(format!(
+                    "{} {}",
+                    state.localizer.t(state.language, "canonical_path_label"),
+                    real
+                ))
+                .on_hover_text(state.localizer.t(state.language, "canonical_path_hint"));
+            }
+            if ui
+                .checkbox(
+                    &mut state.settings.canonical_paths,
+                    state
+                        .localizer
+                        .t(state.language, "canonical_paths_checkbox"),
+                )
+                .on_hover_text(state.localizer.t(state.language, "canonical_paths_hint"))
+                .changed()
+            {
+                save_settings(&state.settings);
+            }
+            if state.listing_source == ListingSource::ShellFallback {
+                ui.label(state.localizer.t(state.language, "listing_shell_fallback"))
+                    .on_hover_text(
+                        state
+                            .localizer
+                            .t(state.language, "listing_shell_fallback_hint"),
+                    );
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(state.localizer.t(state.language, "create_directory_label"));
+            ui.text_edit_singleline(&mut state.new_directory_name);
+            ui.label(state.localizer.t(state.language, "mode_label"));
+            ui.add(egui::TextEdit::singleline(&mut state.new_directory_mode).desired_width(40.0));
+            if ui
+                .button(state.localizer.t(state.language, "create_label"))
+                .clicked()
+            {
+                if state.new_directory_name.is_empty() {
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "directory_name_empty_error")
+                            .to_string(),
+                    );
+                } else if let Some(mode) = parse_mode(&state.new_directory_mode) {
+                    let full_path = format!("{}/{}", state.current_path, state.new_directory_name);
+                    state.operation_in_progress = true;
+                    state.new_directory_name.clear();
+                    let worker = state.worker.clone();
+                    worker
+                        .lock()
+                        .unwrap()
+                        .send_task(Task::CheckPath(full_path, PathCheck::CreateDirectory(mode)));
+                } else {
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "invalid_mode_error")
+                            .to_string(),
+                    );
+                }
+            }
+        });
+
         ui.horizontal(|ui| {
             ui.label(state.localizer.t(state.language, "create_file_label"));
             ui.text_edit_singleline(&mut state.new_file_name);
+            ui.label(state.localizer.t(state.language, "mode_label"));
+            ui.add(egui::TextEdit::singleline(&mut state.new_file_mode).desired_width(40.0));
+            ui.checkbox(
+                &mut state.edit_after_create,
+                state
+                    .localizer
+                    .t(state.language, "edit_after_create_checkbox"),
+            );
             if ui
                 .button(state.localizer.t(state.language, "create_label"))
                 .clicked()
+            {
+                if state.new_file_name.is_empty() {
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "file_name_empty_error")
+                            .to_string(),
+                    );
+                } else if let Some(mode) = parse_mode(&state.new_file_mode) {
+                    let full_path = format!("{}/{}", state.current_path, state.new_file_name);
+                    state.operation_in_progress = true;
+                    state.new_file_name.clear();
+                    if state.edit_after_create {
+                        state.created_file_to_edit = Some(full_path.clone());
+                    }
+                    let worker = state.worker.clone();
+                    worker
+                        .lock()
+                        .unwrap()
+                        .send_task(Task::CheckPath(full_path, PathCheck::CreateFile(mode)));
+                } else {
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "invalid_mode_error")
+                            .to_string(),
+                    );
+                }
+            }
+            if ui
+                .button(state.localizer.t(state.language, "touch_button"))
+                .on_hover_text(state.localizer.t(state.language, "touch_create_hint"))
+                .clicked()
             {
                 if !state.new_file_name.is_empty() {
                     let full_path = format!("{}/{}", state.current_path, state.new_file_name);
@@ -547,7 +2921,7 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
                     worker
                         .lock()
                         .unwrap()
-                        .send_task(Task::CreateFile(full_path));
+                        .send_task(Task::Touch(full_path, true));
                 } else {
                     state.error_message = Some(
                         state
@@ -559,21 +2933,43 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
             }
         });
 
+        let (alt_left, alt_right) = ui.input(|i| {
+            (
+                i.modifiers.alt && i.key_pressed(egui::Key::ArrowLeft),
+                i.modifiers.alt && i.key_pressed(egui::Key::ArrowRight),
+            )
+        });
         ui.horizontal(|ui| {
+            let back = ui
+                .add_enabled(
+                    !state.back_history.is_empty(),
+                    egui::Button::new(state.localizer.t(state.language, "back_button")),
+                )
+                .on_hover_text("Alt+←");
+            if (back.clicked() || alt_left) && !state.operation_in_progress {
+                state.move_in_history(HistoryMove::Back);
+            }
+            let forward = ui
+                .add_enabled(
+                    !state.forward_history.is_empty(),
+                    egui::Button::new(state.localizer.t(state.language, "forward_button")),
+                )
+                .on_hover_text("Alt+→");
+            if (forward.clicked() || alt_right) && !state.operation_in_progress {
+                state.move_in_history(HistoryMove::Forward);
+            }
             if ui
                 .button(state.localizer.t(state.language, "up_button"))
                 .clicked()
+                && state.current_path.contains('/')
             {
-                if let Some(pos) = state.current_path.rfind('/') {
-                    state.current_path.truncate(pos);
-                    if state.current_path.is_empty() {
-                        state.current_path = "/".to_string();
-                    }
-                    state.operation_in_progress = true;
-                    let worker = state.worker.clone();
-                    let path = state.current_path.clone();
-                    worker.lock().unwrap().send_task(Task::ListDirectory(path));
-                }
+                state.current_path = remote_parent(&state.current_path)
+                    .unwrap_or("/")
+                    .to_string();
+                state.operation_in_progress = true;
+                let worker = state.worker.clone();
+                let path = state.current_path.clone();
+                worker.lock().unwrap().send_task(Task::ListDirectory(path));
             }
             if ui
                 .button(state.localizer.t(state.language, "home_button"))
@@ -585,152 +2981,391 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
                 let path = state.current_path.clone();
                 worker.lock().unwrap().send_task(Task::ListDirectory(path));
             }
+            ui.add_enabled_ui(!state.recent_dirs.is_empty(), |ui| {
+                ui.menu_button(state.localizer.t(state.language, "recent_button"), |ui| {
+                    let mut chosen = None;
+                    for dir in &state.recent_dirs {
+                        if ui.button(display_name(dir)).clicked() {
+                            chosen = Some(dir.clone());
+                            ui.close_menu();
+                        }
+                    }
+                    if let Some(dir) = chosen {
+                        state.current_path = dir.clone();
+                        state.operation_in_progress = true;
+                        state
+                            .worker
+                            .lock()
+                            .unwrap()
+                            .send_task(Task::ListDirectory(dir));
+                    }
+                });
+            });
             if ui
                 .button(state.localizer.t(state.language, "disconnect_button"))
                 .clicked()
             {
-                state.operation_in_progress = true;
-                let worker = state.worker.clone();
-                worker.lock().unwrap().send_task(Task::Disconnect);
+                if state.busy() && state.settings.confirmations.exit_with_transfers {
+                    state.pending_exit = Some(PendingExit::Disconnect);
+                } else if state.busy() {
+                    let worker = state.worker.clone();
+                    let worker = worker.lock().unwrap();
+                    worker.cancel();
+                    worker.queue.lock().unwrap().paused = true;
+                    state.operation_in_progress = true;
+                    worker.send_task(Task::Disconnect);
+                } else {
+                    state.operation_in_progress = true;
+                    let worker = state.worker.clone();
+                    worker.lock().unwrap().send_task(Task::Disconnect);
+                }
             }
         });
 
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            for (name, is_dir) in state.files.clone() {
-                ui.horizontal(|ui| {
-                    if let Some(renaming_file) = &state.renaming_file {
-                        if renaming_file == &name {
-                            ui.text_edit_singleline(&mut state.new_name);
-                            if ui
-                                .button(state.localizer.t(state.language, "save_button"))
-                                .clicked()
-                            {
-                                let old_path = format!("{}/{}", state.current_path, name);
-                                let new_path = format!("{}/{}", state.current_path, state.new_name);
-                                state.operation_in_progress = true;
-                                state.renaming_file = None;
-                                state.new_name.clear();
+        show_selection_bar(ui, state);
+
+        if state.listing_truncated {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!(
+                    "{} {} {}",
+                    state
+                        .localizer
+                        .t(state.language, "listing_truncated_prefix"),
+                    MAX_LISTING_ENTRIES,
+                    state
+                        .localizer
+                        .t(state.language, "listing_truncated_suffix")
+                ),
+            );
+        }
+
+        // A stable id keeps the offset when the same directory is listed again
+        let mut file_list = egui::ScrollArea::vertical().id_salt("file_list");
+        if std::mem::take(&mut state.scroll_to_top) {
+            file_list = file_list.vertical_scroll_offset(0.0);
+        }
+        // Only the rows in view are laid out, so huge directories stay responsive
+        match state.view.mode {
+            ViewMode::List => {
+                let row_height = ui.spacing().interact_size.y;
+                file_list.show_rows(ui, row_height, state.files.len(), |ui, rows| {
+                    show_file_list(ui, state, rows)
+                });
+            }
+            ViewMode::Grid => {
+                let spacing = ui.spacing().item_spacing.x;
+                let per_row = ((ui.available_width() + spacing) / (GRID_TILE_SIZE.x + spacing))
+                    .floor()
+                    .max(1.0) as usize;
+                let row_count = state.files.len().div_ceil(per_row);
+                file_list.show_rows(ui, GRID_TILE_SIZE.y, row_count, |ui, rows| {
+                    show_file_grid(ui, state, rows, per_row)
+                });
+            }
+        }
+
+        if let Some(editing_file) = &state.editing_file {
+            let editing_file_clone = editing_file.clone();
+            egui::Window::new(state.localizer.t(state.language, "edit_file_window"))
+                .resizable(true)
+                .collapsible(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!(
+                        "{} {}",
+                        state.localizer.t(state.language, "editing_label"),
+                        editing_file_clone
+                    ));
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .checkbox(
+                                &mut state.settings.editor_wrap,
+                                state.localizer.t(state.language, "wrap_lines"),
+                            )
+                            .changed()
+                        {
+                            save_settings(&state.settings);
+                        }
+                        ui.separator();
+                        ui.label(state.localizer.t(state.language, "go_to_line_label"));
+                        let input = ui.add(
+                            egui::TextEdit::singleline(&mut state.goto_line_input)
+                                .desired_width(60.0),
+                        );
+                        let submitted =
+                            input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if (ui
+                            .button(state.localizer.t(state.language, "go_button"))
+                            .clicked()
+                            || submitted)
+                            && !state.goto_line_input.is_empty()
+                        {
+                            state.goto_line_request = state.goto_line_input.trim().parse().ok();
+                        }
+                        ui.separator();
+                        ui.label(state.localizer.t(state.language, "encoding_label"));
+                        let mut encoding = state.file_encoding;
+                        egui::ComboBox::from_id_salt("file_encoding")
+                            .selected_text(encoding.name())
+                            .show_ui(ui, |ui| {
+                                // The detected encoding may not be one of the usual choices
+                                let detected = Some(state.file_encoding)
+                                    .filter(|detected| !ENCODINGS.contains(detected));
+                                for choice in detected.into_iter().chain(ENCODINGS.iter().copied())
+                                {
+                                    ui.selectable_value(&mut encoding, choice, choice.name());
+                                }
+                            })
+                            .response
+                            .on_hover_text(state.localizer.t(state.language, "encoding_hint"));
+                        if encoding != state.file_encoding {
+                            state.file_encoding = encoding;
+                            state.file_content = text_encoding::decode(&state.file_bytes, encoding);
+                        }
+                    });
+
+                    if state.editing_preview {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            state.localizer.t(state.language, "preview_notice"),
+                        );
+                    }
+
+                    let goto_line = state.goto_line_request.take();
+                    let scroll = if state.settings.editor_wrap {
+                        egui::ScrollArea::vertical()
+                    } else {
+                        egui::ScrollArea::both()
+                    };
+                    let output = scroll
+                        .id_salt("file_editor")
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            // Previews are read-only: editing a truncated file and
+                            // saving it would cut off the rest of the file.
+                            if state.editing_preview {
+                                show_code_editor(
+                                    ui,
+                                    &mut state.file_content.as_str(),
+                                    state.settings.editor_wrap,
+                                    goto_line,
+                                )
+                            } else {
+                                show_code_editor(
+                                    ui,
+                                    &mut state.file_content,
+                                    state.settings.editor_wrap,
+                                    goto_line,
+                                )
+                            }
+                        })
+                        .inner;
+
+                    ui.horizontal(|ui| {
+                        if let Some(cursor) = output.cursor_range {
+                            ui.label(format!(
+                                "{} {}, {} {}",
+                                state.localizer.t(state.language, "cursor_line"),
+                                cursor.primary.pcursor.paragraph + 1,
+                                state.localizer.t(state.language, "cursor_column"),
+                                cursor.primary.pcursor.offset + 1
+                            ));
+                            ui.separator();
+                        }
+                        ui.label(format!(
+                            "{} {}",
+                            state.file_content.split('\n').count(),
+                            state.localizer.t(state.language, "total_lines")
+                        ));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(state.localizer.t(state.language, "append_label"));
+                        ui.text_edit_singleline(&mut state.append_content);
+                        if ui
+                            .button(state.localizer.t(state.language, "append_button"))
+                            .clicked()
+                            && !state.append_content.is_empty()
+                        {
+                            let mut content = state.append_content.clone();
+                            if !content.ends_with('\n') {
+                                content.push('\n');
+                            }
+                            if let Some(content) = state.encode_fragment(&content) {
                                 let worker = state.worker.clone();
+                                state.operation_in_progress = true;
+                                let path = editing_file_clone.clone();
                                 worker
                                     .lock()
                                     .unwrap()
-                                    .send_task(Task::RenameFile(old_path, new_path));
-                            }
-                            if ui
-                                .button(state.localizer.t(state.language, "cancel_button"))
-                                .clicked()
-                            {
-                                state.renaming_file = None;
-                                state.new_name.clear();
+                                    .send_task(Task::AppendFile(path, content));
                             }
                         }
-                    } else {
-                        if is_dir {
-                            if ui.button(format!("📁 {}", name)).clicked() {
-                                state.current_path = format!(
-                                    "{}/{}",
-                                    state.current_path.trim_end_matches('/'),
-                                    name
-                                );
-                                state.operation_in_progress = true;
-                                let worker = state.worker.clone();
-                                let path = state.current_path.clone();
-                                worker.lock().unwrap().send_task(Task::ListDirectory(path));
-                            }
-                        } else {
-                            ui.label(format!("📄 {}", name));
-                        }
+                    });
 
-                        if !is_dir
-                            && ui
-                                .button(state.localizer.t(state.language, "download_button"))
-                                .clicked()
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(state.localizer.t(state.language, "show_diff_button"))
+                            .clicked()
                         {
-                            if let Some(local_path) = rfd::FileDialog::new()
-                                .set_file_name(name.clone())
-                                .save_file()
-                            {
-                                let remote_path = format!("{}/{}", state.current_path, name);
+                            state.show_diff();
+                        }
+                        if ui
+                            .checkbox(
+                                &mut state.settings.review_before_save,
+                                state.localizer.t(state.language, "review_before_save"),
+                            )
+                            .on_hover_text(
+                                state.localizer.t(state.language, "review_before_save_hint"),
+                            )
+                            .changed()
+                        {
+                            save_settings(&state.settings);
+                        }
+                        if ui
+                            .checkbox(
+                                &mut state.settings.atomic_save,
+                                state.localizer.t(state.language, "atomic_save"),
+                            )
+                            .on_hover_text(state.localizer.t(state.language, "atomic_save_hint"))
+                            .changed()
+                        {
+                            save_settings(&state.settings);
+                        }
+                        ui.separator();
+                        // With review enabled, the current text must have been seen in the diff
+                        let reviewed = !state.settings.review_before_save
+                            || state.reviewed_content.as_ref() == Some(&state.file_content);
+                        if ui
+                            .add_enabled(
+                                !state.editing_preview && reviewed,
+                                egui::Button::new(state.localizer.t(state.language, "save_button")),
+                            )
+                            .clicked()
+                        {
+                            let content = state.file_content.clone();
+                            if let Some(content) = state.encode_for_file(&content) {
                                 let worker = state.worker.clone();
                                 state.operation_in_progress = true;
-                                worker.lock().unwrap().send_task(Task::DownloadFile(
-                                    remote_path,
-                                    local_path.to_str().unwrap().to_string(),
+                                let path = editing_file_clone.clone();
+                                state.saving_bytes = Some(content.clone());
+                                // A file created for editing may be saved even if it vanished meanwhile
+                                let must_exist = !state.editing_new_file;
+                                worker.lock().unwrap().send_task(Task::WriteFile(
+                                    path,
+                                    content,
+                                    must_exist,
+                                    state.settings.atomic_save,
+                                    state.edit_version,
                                 ));
                             }
                         }
-
                         if ui
-                            .button(state.localizer.t(state.language, "delete_button"))
+                            .button(state.localizer.t(state.language, "close_button"))
                             .clicked()
                         {
-                            let remote_path = format!("{}/{}", state.current_path, name);
-                            let worker = state.worker.clone();
-                            state.operation_in_progress = true;
-                            worker
-                                .lock()
-                                .unwrap()
-                                .send_task(Task::DeleteFile(remote_path));
+                            state.editing_file = None;
+                            state.editing_preview = false;
                         }
+                    });
+                });
+        }
 
-                        if !is_dir
-                            && ui
-                                .button(state.localizer.t(state.language, "modify_button"))
-                                .clicked()
+        show_diff_window(ui, state);
+
+        if state.missing_edit_target {
+            egui::Window::new(state.localizer.t(state.language, "file_missing_window"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(state.localizer.t(state.language, "file_missing_message"));
+                    if let Some(path) = &state.editing_file {
+                        ui.monospace(path);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(state.localizer.t(state.language, "recreate_button"))
+                            .clicked()
                         {
-                            let remote_path = format!("{}/{}", state.current_path, name);
-                            let worker = state.worker.clone();
-                            state.operation_in_progress = true;
-                            worker
-                                .lock()
-                                .unwrap()
-                                .send_task(Task::ReadFile(remote_path));
+                            state.missing_edit_target = false;
+                            let content = state.file_content.clone();
+                            if let (Some(path), Some(content)) =
+                                (state.editing_file.clone(), state.encode_for_file(&content))
+                            {
+                                state.operation_in_progress = true;
+                                state.saving_bytes = Some(content.clone());
+                                let worker = state.worker.clone();
+                                worker.lock().unwrap().send_task(Task::WriteFile(
+                                    path,
+                                    content,
+                                    false,
+                                    state.settings.atomic_save,
+                                    None,
+                                ));
+                            }
                         }
-
                         if ui
-                            .button(state.localizer.t(state.language, "rename_button"))
+                            .button(state.localizer.t(state.language, "discard_edits_button"))
                             .clicked()
                         {
-                            state.renaming_file = Some(name.clone());
-                            state.new_name = name.clone();
+                            state.missing_edit_target = false;
+                            state.editing_file = None;
+                            state.editing_preview = false;
                         }
-                    }
+                        if ui
+                            .button(state.localizer.t(state.language, "cancel_button"))
+                            .clicked()
+                        {
+                            state.missing_edit_target = false;
+                        }
+                    });
                 });
-            }
-        });
+        }
 
-        if let Some(editing_file) = &state.editing_file {
-            let editing_file_clone = editing_file.clone();
-            egui::Window::new(state.localizer.t(state.language, "edit_file_window"))
-                .resizable(true)
+        show_changed_file_dialog(ui, state);
+
+        show_save_refused_dialog(ui, state);
+
+        if let Some((path, size)) = state.large_file_prompt.clone() {
+            egui::Window::new(state.localizer.t(state.language, "file_too_large_window"))
                 .collapsible(false)
+                .resizable(false)
                 .show(ui.ctx(), |ui| {
+                    ui.label(&path);
                     ui.label(format!(
-                        "{} {}",
-                        state.localizer.t(state.language, "editing_label"),
-                        editing_file_clone
+                        "{} ({:.1} MB > {} MB)",
+                        state.localizer.t(state.language, "file_too_large_message"),
+                        size as f64 / (1024.0 * 1024.0),
+                        state.settings.max_edit_size_mb
                     ));
-                    ui.text_edit_multiline(&mut state.file_content);
-
                     ui.horizontal(|ui| {
                         if ui
-                            .button(state.localizer.t(state.language, "save_button"))
+                            .button(state.localizer.t(state.language, "preview_head_button"))
                             .clicked()
                         {
-                            let worker = state.worker.clone();
+                            state.large_file_prompt = None;
                             state.operation_in_progress = true;
-                            let path = editing_file_clone.clone();
-                            let content = state.file_content.clone();
-                            worker
-                                .lock()
-                                .unwrap()
-                                .send_task(Task::WriteFile(path, content));
+                            let worker = state.worker.clone();
+                            worker.lock().unwrap().send_task(Task::ReadFileHead(
+                                path.clone(),
+                                state.max_edit_size(),
+                                state.default_encoding(),
+                            ));
+                        }
+                        if ui
+                            .button(state.localizer.t(state.language, "write_into_button"))
+                            .on_hover_text(state.localizer.t(state.language, "write_into_hint"))
+                            .clicked()
+                        {
+                            state.large_file_prompt = None;
+                            state.write_into = Some(WriteInto::new(path.clone()));
                         }
                         if ui
                             .button(state.localizer.t(state.language, "cancel_button"))
                             .clicked()
                         {
-                            state.editing_file = None;
+                            state.large_file_prompt = None;
                         }
                     });
                 });
@@ -740,29 +3375,2841 @@ pub fn render_ui(ui: &mut egui::Ui, state: &mut UIState, _connection: &mut Optio
             .button(state.localizer.t(state.language, "upload_file_button"))
             .clicked()
         {
-            if let Some(local_path) = rfd::FileDialog::new().pick_file() {
+            for local_path in rfd::FileDialog::new().pick_files().unwrap_or_default() {
                 let remote_path = format!(
                     "{}/{}",
-                    state.current_path,
-                    local_path.file_name().unwrap().to_str().unwrap()
+                    state.current_path.trim_end_matches('/'),
+                    name_from_os(local_path.file_name().unwrap())
                 );
-                let worker = state.worker.clone();
-                state.operation_in_progress = true;
-                worker.lock().unwrap().send_task(Task::UploadFile(
-                    local_path.to_str().unwrap().to_string(),
-                    remote_path,
-                ));
+                state.enqueue(Transfer::UploadFile {
+                    local: name_from_os(local_path.as_os_str()),
+                    remote: remote_path,
+                    create_parents: state.create_parents,
+                });
+            }
+        }
+
+        if ui
+            .button(state.localizer.t(state.language, "upload_folder_button"))
+            .clicked()
+        {
+            if let Some(local_dir) = rfd::FileDialog::new().pick_folder() {
+                if let Some(dir_name) = local_dir.file_name() {
+                    let remote_path = format!(
+                        "{}/{}",
+                        state.current_path.trim_end_matches('/'),
+                        name_from_os(dir_name)
+                    );
+                    let transfer = Transfer::UploadDirectory {
+                        local: name_from_os(local_dir.as_os_str()),
+                        remote: remote_path,
+                        sync: state.sync_mode(),
+                        mirror: state.mirror,
+                        create_parents: state.create_parents,
+                    };
+                    if state.settings.confirmations.overwrite {
+                        // Ask first if existing remote files would be overwritten
+                        state.operation_in_progress = true;
+                        let worker = state.worker.clone();
+                        worker
+                            .lock()
+                            .unwrap()
+                            .send_task(Task::CheckUploadConflicts(transfer));
+                    } else {
+                        state.enqueue(transfer);
+                    }
+                }
+            }
+        }
+        ui.checkbox(
+            &mut state.skip_unchanged,
+            state.localizer.t(state.language, "skip_unchanged_label"),
+        )
+        .on_hover_text(state.localizer.t(state.language, "skip_unchanged_hint"));
+        ui.checkbox(
+            &mut state.mirror,
+            state.localizer.t(state.language, "mirror_label"),
+        )
+        .on_hover_text(state.localizer.t(state.language, "mirror_hint"));
+        ui.checkbox(
+            &mut state.create_parents,
+            state.localizer.t(state.language, "create_parents_label"),
+        )
+        .on_hover_text(state.localizer.t(state.language, "create_parents_hint"));
+
+        if state.mirror_plan.is_some() {
+            show_mirror_confirmation(ui.ctx(), state);
+        }
+        if state.upload_conflicts.is_some() {
+            show_upload_conflicts(ui.ctx(), state);
+        }
+        if state.missing_upload_parent.is_some() {
+            show_missing_upload_parent(ui.ctx(), state);
+        }
+        if state.confirm_replace.is_some() {
+            show_replace_confirmation(ui.ctx(), state);
+        }
+
+        if let Some(path) = state.confirm_delete_directory.clone() {
+            egui::Window::new(state.localizer.t(state.language, "confirm_delete_window"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(
+                        state
+                            .localizer
+                            .t(state.language, "confirm_delete_directory"),
+                    );
+                    ui.monospace(&path);
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(state.localizer.t(state.language, "delete_button"))
+                            .clicked()
+                        {
+                            state.confirm_delete_directory = None;
+                            state.operation_in_progress = true;
+                            let task = Destructive::DeleteDirectory(path.clone())
+                                .task(state.settings.dry_run);
+                            let worker = state.worker.clone();
+                            worker.lock().unwrap().send_task(task);
+                        }
+                        if ui
+                            .button(state.localizer.t(state.language, "cancel_button"))
+                            .clicked()
+                        {
+                            state.confirm_delete_directory = None;
+                        }
+                    });
+                });
+        }
+
+        show_dry_run_plan(ui.ctx(), state);
+
+        show_hardlink_dialog(ui, state);
+
+        show_write_into_dialog(ui, state);
+
+        show_transfer_queue(ui, state);
+
+        show_status(ui, state);
+
+        if state.offer_reconnect
+            && ui
+                .button(state.localizer.t(state.language, "reconnect_button"))
+                .clicked()
+        {
+            state.operation_in_progress = true;
+            state.offer_reconnect = false;
+            state.reconnecting = true;
+            // Come back to the same directory, or to the root if it is gone by then
+            state.initial_path = Some(state.listed_path.clone());
+            let worker = state.worker.clone();
+            worker.lock().unwrap().send_task(Task::Reconnect(
+                state.hostname.clone(),
+                state.username.clone(),
+                state.password.clone(),
+                state.port,
+                state.algorithms.clone(),
+            ));
+        }
+    }
+
+    if let Some(pending) = state.pending_exit {
+        egui::Window::new(state.localizer.t(state.language, "confirm_exit_window"))
+            .collapsible(false)
+            .resizable(false)
+            .show(&ctx, |ui| {
+                let (message, confirm) = match pending {
+                    PendingExit::Disconnect => ("confirm_disconnect_message", "disconnect_anyway"),
+                    PendingExit::CloseWindow => ("confirm_quit_message", "quit_anyway"),
+                };
+                ui.label(state.localizer.t(state.language, message));
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(state.localizer.t(state.language, confirm))
+                        .clicked()
+                    {
+                        state.pending_exit = None;
+                        let worker = state.worker.clone();
+                        let worker = worker.lock().unwrap();
+                        worker.cancel();
+                        match pending {
+                            PendingExit::Disconnect => {
+                                // Hold the remaining transfers so the worker gets to the disconnect
+                                worker.queue.lock().unwrap().paused = true;
+                                state.operation_in_progress = true;
+                                worker.send_task(Task::Disconnect);
+                            }
+                            PendingExit::CloseWindow => {
+                                state.allow_close = true;
+                                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
+                        }
+                    }
+                    if ui
+                        .button(state.localizer.t(state.language, "cancel_button"))
+                        .clicked()
+                    {
+                        state.pending_exit = None;
+                    }
+                });
+            });
+    }
+
+    show_toasts(&ctx, state);
+}
+
+/// Choose which actions ask for confirmation first
+fn show_confirmation_settings(ui: &mut egui::Ui, state: &mut UIState) {
+    ui.menu_button(
+        state.localizer.t(state.language, "confirmations_button"),
+        |ui| {
+            let confirmations = &mut state.settings.confirmations;
+            let mut changed = false;
+            for (value, key) in [
+                (
+                    &mut confirmations.delete_directory,
+                    "confirm_delete_directory_setting",
+                ),
+                (&mut confirmations.overwrite, "confirm_overwrite_setting"),
+                (
+                    &mut confirmations.exit_with_transfers,
+                    "confirm_exit_with_transfers_setting",
+                ),
+            ] {
+                changed |= ui
+                    .checkbox(value, state.localizer.t(state.language, key))
+                    .changed();
+            }
+            if changed {
+                save_settings(&state.settings);
+            }
+        },
+    );
+}
+
+/// Questions of the server during login and the answers typed so far
+struct PendingAuthQuestions {
+    questions: AuthQuestions,
+    answers: Vec<String>,
+    reply: Sender<Option<Vec<String>>>,
+}
+
+/// Ask the user the server's login questions, such as for a one-time code
+fn show_auth_questions(ctx: &egui::Context, state: &mut UIState) {
+    let Some(pending) = state.auth_questions.as_mut() else {
+        return;
+    };
+
+    let mut reply = None;
+    egui::Window::new(state.localizer.t(state.language, "auth_questions_window"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if !pending.questions.instructions.is_empty() {
+                ui.label(&pending.questions.instructions);
+            }
+            let mut submitted = false;
+            for (index, ((prompt, echo), answer)) in pending
+                .questions
+                .prompts
+                .iter()
+                .zip(&mut pending.answers)
+                .enumerate()
+            {
+                ui.label(prompt);
+                let input = ui.add(egui::TextEdit::singleline(answer).password(!echo));
+                if index == 0 && input.ctx.memory(|memory| memory.focused().is_none()) {
+                    input.request_focus();
+                }
+                submitted |= input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .button(state.localizer.t(state.language, "ok_button"))
+                    .clicked()
+                    || submitted
+                {
+                    reply = Some(Some(pending.answers.clone()));
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    reply = Some(None);
+                }
+            });
+        });
+    if let Some(answers) = reply {
+        if let Some(pending) = state.auth_questions.take() {
+            let _ = pending.reply.send(answers);
+        }
+    }
+}
+
+/// Toggle sudo mode, and while it is on, edit the sudo password and show a warning
+fn show_sudo_controls(ui: &mut egui::Ui, state: &mut UIState) {
+    let mut changed = ui
+        .checkbox(
+            &mut state.use_sudo,
+            state.localizer.t(state.language, "use_sudo_checkbox"),
+        )
+        .on_hover_text(state.localizer.t(state.language, "use_sudo_hint"))
+        .changed();
+    if state.use_sudo {
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut state.sudo_password)
+                    .password(true)
+                    .hint_text(state.localizer.t(state.language, "sudo_password_hint"))
+                    .desired_width(120.0),
+            )
+            .changed();
+        ui.label(
+            egui::RichText::new(state.localizer.t(state.language, "sudo_active"))
+                .strong()
+                .color(egui::Color32::WHITE)
+                .background_color(egui::Color32::DARK_RED),
+        );
+    }
+    if changed {
+        let password = state.use_sudo.then(|| state.sudo_password.clone());
+        state
+            .worker
+            .lock()
+            .unwrap()
+            .send_task(Task::SetSudo(password));
+    }
+}
+
+/// Size of a tile in the grid view
+const GRID_TILE_SIZE: egui::Vec2 = egui::vec2(110.0, 70.0);
+
+/// Longest name shown in full on a grid tile
+const GRID_NAME_CHARS: usize = 14;
+
+/// What can be done to an entry of the file list, from its buttons or context menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryAction {
+    /// Enter a directory
+    Open,
+    Touch,
+    Download,
+    Delete,
+    Modify,
+    Rename,
+    HardLink,
+    /// Append to or patch a file without opening it
+    WriteInto,
+    Properties,
+}
+
+impl EntryAction {
+    /// The actions offered for an entry, in display order
+    fn for_entry(is_dir: bool) -> &'static [EntryAction] {
+        if is_dir {
+            &[
+                EntryAction::Download,
+                EntryAction::Delete,
+                EntryAction::Rename,
+            ]
+        } else {
+            &[
+                EntryAction::Download,
+                EntryAction::Delete,
+                EntryAction::Modify,
+                EntryAction::Rename,
+            ]
+        }
+    }
+
+    fn label_key(self) -> &'static str {
+        match self {
+            EntryAction::Open => "open_button",
+            EntryAction::Touch => "touch_button",
+            EntryAction::Download => "download_button",
+            EntryAction::Delete => "delete_button",
+            EntryAction::Modify => "modify_button",
+            EntryAction::Rename => "rename_button",
+            EntryAction::HardLink => "hardlink_button",
+            EntryAction::WriteInto => "write_into_button",
+            EntryAction::Properties => "properties_button",
+        }
+    }
+
+    /// The icon of the action in the hover toolbar of the list view
+    fn icon(self) -> &'static str {
+        match self {
+            EntryAction::Download => "⬇",
+            EntryAction::Delete => "🗑",
+            EntryAction::Modify => "✏",
+            EntryAction::Rename => "🏷",
+            _ => "…",
+        }
+    }
+}
+
+/// Run an action on an entry of the current directory
+fn apply_entry_action(state: &mut UIState, entry: &FileEntry, action: EntryAction) {
+    let name = &entry.name;
+    let remote_path = format!("{}/{}", state.current_path, name);
+    match action {
+        EntryAction::Open => {
+            if entry.is_dir {
+                state.current_path =
+                    format!("{}/{}", state.current_path.trim_end_matches('/'), name);
+                state.operation_in_progress = true;
+                let worker = state.worker.clone();
+                let path = state.current_path.clone();
+                worker.lock().unwrap().send_task(Task::ListDirectory(path));
+            }
+        }
+        EntryAction::Touch => {
+            state.operation_in_progress = true;
+            let worker = state.worker.clone();
+            worker
+                .lock()
+                .unwrap()
+                .send_task(Task::Touch(remote_path, false));
+        }
+        EntryAction::Download => {
+            if entry.is_dir {
+                if let Some(local_dir) = rfd::FileDialog::new().pick_folder() {
+                    let local_path = local_dir.join(name);
+                    state.enqueue(Transfer::DownloadDirectory {
+                        remote: remote_path,
+                        local: name_from_os(local_path.as_os_str()),
+                        sync: state.sync_mode(),
+                        mirror: state.mirror,
+                    });
+                }
+            } else if let Some(local_path) = rfd::FileDialog::new()
+                .set_file_name(display_name(name))
+                .save_file()
+            {
+                state.enqueue(Transfer::DownloadFile {
+                    remote: remote_path,
+                    local: name_from_os(local_path.as_os_str()),
+                });
+            }
+        }
+        EntryAction::Delete => {
+            if entry.is_dir && state.settings.confirmations.delete_directory {
+                state.confirm_delete_directory = Some(remote_path);
+            } else if entry.is_dir {
+                state.operation_in_progress = true;
+                let task = Destructive::DeleteDirectory(remote_path).task(state.settings.dry_run);
+                let worker = state.worker.clone();
+                worker.lock().unwrap().send_task(task);
+            } else {
+                let worker = state.worker.clone();
+                state.operation_in_progress = true;
+                worker
+                    .lock()
+                    .unwrap()
+                    .send_task(Task::DeleteFile(remote_path));
+            }
+        }
+        EntryAction::Modify => {
+            let worker = state.worker.clone();
+            state.operation_in_progress = true;
+            worker.lock().unwrap().send_task(Task::ReadFile(
+                remote_path,
+                state.max_edit_size(),
+                state.default_encoding(),
+            ));
+        }
+        EntryAction::Rename => {
+            state.renaming_file = Some(name.clone());
+            state.new_name = name.clone();
+        }
+        EntryAction::HardLink => {
+            let link = format!("{}.link", remote_path);
+            state.hardlink = Some((remote_path, link));
+        }
+        EntryAction::WriteInto => state.write_into = Some(WriteInto::new(remote_path)),
+        EntryAction::Properties => {
+            state.operation_in_progress = true;
+            let worker = state.worker.clone();
+            worker
+                .lock()
+                .unwrap()
+                .send_task(Task::FetchProperties(remote_path));
+        }
+    }
+}
+
+/// The new-name box and its Save/Cancel buttons for the entry being renamed
+fn show_rename_editor(ui: &mut egui::Ui, state: &mut UIState, name: &str) {
+    ui.text_edit_singleline(&mut state.new_name);
+    if ui
+        .button(state.localizer.t(state.language, "save_button"))
+        .clicked()
+    {
+        let old_path = format!("{}/{}", state.current_path, name);
+        let new_path = format!("{}/{}", state.current_path, state.new_name);
+        state.renaming_file = None;
+        state.new_name.clear();
+        // Told here already, as the connection skips it without a word
+        if is_same_remote_path(&old_path, &new_path) {
+            let message = state.localizer.t(state.language, "rename_same_path");
+            state.toast(message.to_string());
+        } else {
+            state.operation_in_progress = true;
+            let worker = state.worker.clone();
+            worker
+                .lock()
+                .unwrap()
+                .send_task(Task::CheckPath(new_path, PathCheck::Rename(old_path)));
+        }
+    }
+    if ui
+        .button(state.localizer.t(state.language, "cancel_button"))
+        .clicked()
+    {
+        state.renaming_file = None;
+        state.new_name.clear();
+    }
+}
+
+/// The line-level differences between the loaded file and the editor's text
+fn show_diff_window(ui: &mut egui::Ui, state: &mut UIState) {
+    let Some(lines) = &state.diff_lines else {
+        return;
+    };
+    let mut open = true;
+    egui::Window::new(state.localizer.t(state.language, "diff_window"))
+        .open(&mut open)
+        .resizable(true)
+        .default_size([600.0, 400.0])
+        .show(ui.ctx(), |ui| {
+            if lines.is_empty() {
+                ui.label(state.localizer.t(state.language, "diff_no_changes"));
+                return;
+            }
+            if state.reviewed_content.as_ref() != Some(&state.file_content) {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    state.localizer.t(state.language, "diff_outdated"),
+                );
+            }
+            egui::ScrollArea::both().show(ui, |ui| {
+                for (tag, line) in lines {
+                    let (prefix, color) = match tag {
+                        Some(ChangeTag::Insert) => {
+                            ("+", Some(egui::Color32::from_rgb(60, 170, 80)))
+                        }
+                        Some(ChangeTag::Delete) => {
+                            ("-", Some(egui::Color32::from_rgb(210, 70, 70)))
+                        }
+                        Some(ChangeTag::Equal) => (" ", None),
+                        None => ("", Some(ui.visuals().weak_text_color())),
+                    };
+                    let mut text = egui::RichText::new(format!("{} {}", prefix, line)).monospace();
+                    if let Some(color) = color {
+                        text = text.color(color);
+                    }
+                    ui.label(text);
+                }
+            });
+        });
+    if !open {
+        state.diff_lines = None;
+    }
+}
+
+/// Ask what to do when saving found the edited file changed on the server
+/// since it was opened: compare, reload it, or overwrite it anyway
+fn show_changed_file_dialog(ui: &mut egui::Ui, state: &mut UIState) {
+    let Some((version, bytes)) = state.changed_edit_target.clone() else {
+        return;
+    };
+    let Some(path) = state.editing_file.clone() else {
+        state.changed_edit_target = None;
+        return;
+    };
+    egui::Window::new(state.localizer.t(state.language, "file_changed_window"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ui.ctx(), |ui| {
+            ui.label(state.localizer.t(state.language, "file_changed_message"));
+            ui.monospace(&path);
+            ui.horizontal(|ui| {
+                if ui
+                    .button(state.localizer.t(state.language, "compare_button"))
+                    .on_hover_text(state.localizer.t(state.language, "compare_hint"))
+                    .clicked()
+                {
+                    let current = text_encoding::decode(&bytes, state.file_encoding);
+                    state.show_diff_from(&current);
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "reload_button"))
+                    .on_hover_text(state.localizer.t(state.language, "reload_hint"))
+                    .clicked()
+                {
+                    let encoding = state.file_encoding;
+                    let decoded = DecodedText {
+                        text: text_encoding::decode(&bytes, encoding),
+                        bytes,
+                        encoding,
+                    };
+                    state.open_in_editor(path.clone(), decoded, false);
+                    state.edit_version = Some(version);
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "overwrite_button"))
+                    .clicked()
+                {
+                    state.changed_edit_target = None;
+                    let content = state.file_content.clone();
+                    if let Some(content) = state.encode_for_file(&content) {
+                        state.operation_in_progress = true;
+                        state.saving_bytes = Some(content.clone());
+                        state.worker.lock().unwrap().send_task(Task::WriteFile(
+                            path.clone(),
+                            content,
+                            false,
+                            state.settings.atomic_save,
+                            None,
+                        ));
+                    }
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    state.changed_edit_target = None;
+                }
+            });
+        });
+}
+
+/// Why the server refused to save the edited file
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SaveRefusal {
+    /// The user may not write the file
+    PermissionDenied,
+    /// The file is on read-only storage, so sudo would not help either
+    ReadOnly,
+}
+
+/// Offer another place to save edits the server refused to write, or, when
+/// permissions are in the way, to retry through sudo
+fn show_save_refused_dialog(ui: &mut egui::Ui, state: &mut UIState) {
+    let Some(refusal) = state.save_refused else {
+        return;
+    };
+    let Some(path) = state.editing_file.clone() else {
+        state.save_refused = None;
+        return;
+    };
+    let message = match refusal {
+        SaveRefusal::PermissionDenied => "save_permission_denied_message",
+        SaveRefusal::ReadOnly => "save_read_only_message",
+    };
+    // Where to write, and whether the file being edited becomes that one
+    let mut save_to = None;
+    egui::Window::new(state.localizer.t(state.language, "save_refused_window"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ui.ctx(), |ui| {
+            ui.label(state.localizer.t(state.language, message));
+            ui.monospace(&path);
+            ui.horizontal(|ui| {
+                ui.label(state.localizer.t(state.language, "save_as_label"));
+                ui.text_edit_singleline(&mut state.save_as_path);
+                if ui
+                    .add_enabled(
+                        !state.save_as_path.trim().is_empty(),
+                        egui::Button::new(state.localizer.t(state.language, "save_as_button")),
+                    )
+                    .clicked()
+                {
+                    let target = resolve_remote_path(&state.listed_path, state.save_as_path.trim());
+                    save_to = Some(target);
+                }
+            });
+            ui.horizontal(|ui| {
+                if refusal == SaveRefusal::PermissionDenied
+                    && !state.use_sudo
+                    && ui
+                        .button(state.localizer.t(state.language, "retry_with_sudo_button"))
+                        .on_hover_text(state.localizer.t(state.language, "retry_with_sudo_hint"))
+                        .clicked()
+                {
+                    state.use_sudo = true;
+                    let password = Some(state.sudo_password.clone());
+                    state
+                        .worker
+                        .lock()
+                        .unwrap()
+                        .send_task(Task::SetSudo(password));
+                    save_to = Some(path.clone());
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    state.save_refused = None;
+                }
+            });
+        });
+
+    let Some(target) = save_to else {
+        return;
+    };
+    state.save_refused = None;
+    let content = state.file_content.clone();
+    if let Some(content) = state.encode_for_file(&content) {
+        if target != path {
+            // From now on the editor saves to the new file
+            state.editing_file = Some(target.clone());
+            state.editing_new_file = true;
+            state.edit_version = None;
+        }
+        state.operation_in_progress = true;
+        state.saving_bytes = Some(content.clone());
+        state.worker.lock().unwrap().send_task(Task::WriteFile(
+            target,
+            content,
+            false,
+            state.settings.atomic_save,
+            state.edit_version,
+        ));
+    }
+}
+
+/// Ask where to create the hard link requested from a file's context menu
+fn show_hardlink_dialog(ui: &mut egui::Ui, state: &mut UIState) {
+    let Some((target, mut link)) = state.hardlink.clone() else {
+        return;
+    };
+    let mut close = false;
+    egui::Window::new(state.localizer.t(state.language, "hardlink_window"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ui.ctx(), |ui| {
+            ui.label(state.localizer.t(state.language, "hardlink_target_label"));
+            ui.monospace(display_name(&target));
+            ui.label(state.localizer.t(state.language, "hardlink_path_label"));
+            ui.text_edit_singleline(&mut link);
+            ui.horizontal(|ui| {
+                if ui
+                    .button(state.localizer.t(state.language, "create_label"))
+                    .clicked()
+                    && !link.trim().is_empty()
+                {
+                    close = true;
+                    state.operation_in_progress = true;
+                    state
+                        .worker
+                        .lock()
+                        .unwrap()
+                        .send_task(Task::CreateHardlink(target.clone(), link.clone()));
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    close = true;
+                }
+            });
+        });
+    state.hardlink = if close { None } else { Some((target, link)) };
+}
+
+/// Text to write into a remote file without reading or rewriting the rest of it
+#[derive(Debug, Clone)]
+struct WriteInto {
+    path: String,
+    text: String,
+    /// The byte offset to write at, as typed; empty appends to the end
+    offset: String,
+}
+
+impl WriteInto {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            text: String::new(),
+            offset: String::new(),
+        }
+    }
+}
+
+/// Append text to a file, or overwrite its bytes from an offset on, without
+/// transferring the whole file; meant for files too large to edit
+fn show_write_into_dialog(ui: &mut egui::Ui, state: &mut UIState) {
+    let Some(mut write) = state.write_into.clone() else {
+        return;
+    };
+    let mut close = false;
+    egui::Window::new(state.localizer.t(state.language, "write_into_window"))
+        .collapsible(false)
+        .show(ui.ctx(), |ui| {
+            ui.monospace(display_name(&write.path));
+            ui.add(
+                egui::TextEdit::multiline(&mut write.text)
+                    .code_editor()
+                    .desired_rows(6),
+            );
+            ui.horizontal(|ui| {
+                ui.label(state.localizer.t(state.language, "write_offset_label"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut write.offset)
+                        .hint_text(state.localizer.t(state.language, "write_offset_hint"))
+                        .desired_width(120.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        !write.text.is_empty(),
+                        egui::Button::new(state.localizer.t(state.language, "write_button")),
+                    )
+                    .clicked()
+                {
+                    let offset = match write.offset.trim() {
+                        "" => Ok(None),
+                        offset => offset.parse::<u64>().map(Some),
+                    };
+                    let encoding = state.default_encoding().unwrap_or(encoding_rs::UTF_8);
+                    match (offset, text_encoding::encode(&write.text, encoding)) {
+                        (Err(_), _) => {
+                            state.error_message = Some(
+                                state
+                                    .localizer
+                                    .t(state.language, "invalid_offset_error")
+                                    .to_string(),
+                            )
+                        }
+                        (_, Err(message)) => state.error_message = Some(message),
+                        (Ok(offset), Ok(content)) => {
+                            close = true;
+                            state.operation_in_progress = true;
+                            state.worker.lock().unwrap().send_task(Task::WriteAt(
+                                write.path.clone(),
+                                offset,
+                                content,
+                            ));
+                        }
+                    }
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    close = true;
+                }
+            });
+        });
+    state.write_into = if close { None } else { Some(write) };
+}
+
+/// Tick or untick an entry for the batch actions
+fn set_selected(state: &mut UIState, name: &str, selected: bool) {
+    if selected {
+        state.selected.insert(name.to_string());
+    } else {
+        state.selected.remove(name);
+    }
+}
+
+/// Change the selection for a click on an entry: shift selects the range of
+/// shown entries from the anchor to it, adding to the selection with
+/// ctrl (cmd on macOS) held; otherwise the entry is toggled and becomes the anchor
+fn select_clicked(state: &mut UIState, name: &str, modifiers: egui::Modifiers) {
+    if modifiers.shift {
+        // The anchor is kept by name, as sorting or filtering moves entries
+        let index = |name: &str| state.files.iter().position(|entry| entry.name == name);
+        let clicked = index(name);
+        let anchor = state
+            .selection_anchor
+            .as_deref()
+            .and_then(index)
+            .or(clicked);
+        let (Some(anchor), Some(clicked)) = (anchor, clicked) else {
+            return;
+        };
+        if !modifiers.command {
+            state.selected.clear();
+        }
+        let range = anchor.min(clicked)..=anchor.max(clicked);
+        state
+            .selected
+            .extend(state.files[range].iter().map(|entry| entry.name.clone()));
+    } else {
+        let selected = state.selected.contains(name);
+        set_selected(state, name, !selected);
+        state.selection_anchor = Some(name.to_string());
+    }
+}
+
+/// The actions of the context menu shared by both views
+fn entry_context_menu(
+    ui: &mut egui::Ui,
+    state: &UIState,
+    entry: &FileEntry,
+) -> Option<EntryAction> {
+    let mut actions = vec![EntryAction::Touch];
+    if entry.is_dir {
+        actions.insert(0, EntryAction::Open);
+    } else {
+        // Directories cannot be hard linked
+        actions.push(EntryAction::HardLink);
+        actions.push(EntryAction::WriteInto);
+    }
+    actions.push(EntryAction::Properties);
+    for action in actions {
+        if ui
+            .button(state.localizer.t(state.language, action.label_key()))
+            .clicked()
+        {
+            ui.close_menu();
+            return Some(action);
+        }
+    }
+    None
+}
+
+/// One row per entry, with its actions as buttons
+fn show_file_list(ui: &mut egui::Ui, state: &mut UIState, rows: Range<usize>) {
+    let mut pending = None;
+    for entry in &state.files[rows].to_vec() {
+        let name = &entry.name;
+        ui.horizontal(|ui| {
+            if let Some(renaming_file) = &state.renaming_file {
+                if renaming_file == name {
+                    show_rename_editor(ui, state, name);
+                }
+                return;
+            }
+            // The whole width of the row, before its widgets are laid out
+            let row_hovered = ui.rect_contains_pointer(ui.max_rect());
+            let mut selected = state.selected.contains(name);
+            if ui.checkbox(&mut selected, "").changed() {
+                select_clicked(state, name, ui.input(|i| i.modifiers));
+            }
+            let entry_response = if entry.is_dir {
+                ui.button(format!("📁 {}", display_name(name)))
+            } else {
+                ui.add(
+                    egui::Label::new(format!("📄 {}", display_name(name)))
+                        .sense(egui::Sense::click()),
+                )
+            };
+            if entry_response.clicked() {
+                let modifiers = ui.input(|i| i.modifiers);
+                if modifiers.shift || modifiers.command {
+                    select_clicked(state, name, modifiers);
+                } else if entry.is_dir {
+                    pending = Some((entry.clone(), EntryAction::Open));
+                }
+            }
+            let hover_actions = state.settings.hover_actions;
+            entry_response.context_menu(|ui| {
+                // Directories open with a click in this view
+                if let Some(action) = entry_context_menu(ui, state, entry)
+                    .filter(|action| *action != EntryAction::Open)
+                {
+                    pending = Some((entry.clone(), action));
+                }
+                // The hover toolbar is out of reach without a pointer
+                if hover_actions {
+                    ui.separator();
+                    for &action in EntryAction::for_entry(entry.is_dir) {
+                        if ui
+                            .button(state.localizer.t(state.language, action.label_key()))
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            pending = Some((entry.clone(), action));
+                        }
+                    }
+                }
+            });
+            if !hover_actions {
+                for &action in EntryAction::for_entry(entry.is_dir) {
+                    if ui
+                        .button(state.localizer.t(state.language, action.label_key()))
+                        .clicked()
+                    {
+                        pending = Some((entry.clone(), action));
+                    }
+                }
+            } else if row_hovered {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    // Right to left: the first action ends up leftmost
+                    for &action in EntryAction::for_entry(entry.is_dir).iter().rev() {
+                        if ui
+                            .small_button(action.icon())
+                            .on_hover_text(state.localizer.t(state.language, action.label_key()))
+                            .clicked()
+                        {
+                            pending = Some((entry.clone(), action));
+                        }
+                    }
+                });
+            }
+        });
+    }
+    if let Some((entry, action)) = pending {
+        apply_entry_action(state, &entry, action);
+    }
+}
+
+/// Entries as tiles with an icon and name, `per_row` to a row; actions live in the context menu
+fn show_file_grid(ui: &mut egui::Ui, state: &mut UIState, rows: Range<usize>, per_row: usize) {
+    let mut pending = None;
+    let start = (rows.start * per_row).min(state.files.len());
+    let end = (rows.end * per_row).min(state.files.len());
+    for row in state.files[start..end].to_vec().chunks(per_row) {
+        ui.horizontal(|ui| {
+            for entry in row {
+                let name = &entry.name;
+                if state.renaming_file.as_deref() == Some(name.as_str()) {
+                    ui.vertical(|ui| {
+                        ui.set_width(GRID_TILE_SIZE.x * 2.0);
+                        show_rename_editor(ui, state, name);
+                    });
+                    continue;
+                }
+                let shown = display_name(name);
+                let short: String = if shown.chars().count() > GRID_NAME_CHARS {
+                    let head: String = shown.chars().take(GRID_NAME_CHARS - 1).collect();
+                    format!("{}…", head)
+                } else {
+                    shown.to_string()
+                };
+                let icon = if entry.is_dir { "📁" } else { "📄" };
+                let selected = state.selected.contains(name);
+                let response = ui
+                    .add(
+                        egui::Button::new(format!("{}\n{}", icon, short))
+                            .min_size(GRID_TILE_SIZE)
+                            .selected(selected),
+                    )
+                    .on_hover_text(shown.as_ref());
+                if response.clicked() {
+                    let modifiers = ui.input(|i| i.modifiers);
+                    if modifiers.shift || modifiers.command {
+                        select_clicked(state, name, modifiers);
+                    } else {
+                        pending = Some((entry.clone(), EntryAction::Open));
+                    }
+                }
+                if response.double_clicked() && !entry.is_dir {
+                    pending = Some((entry.clone(), EntryAction::Modify));
+                }
+                response.context_menu(|ui| {
+                    let mut ticked = selected;
+                    if ui
+                        .checkbox(
+                            &mut ticked,
+                            state.localizer.t(state.language, "select_entry_checkbox"),
+                        )
+                        .changed()
+                    {
+                        select_clicked(state, name, egui::Modifiers::NONE);
+                    }
+                    ui.separator();
+                    if let Some(action) = entry_context_menu(ui, state, entry) {
+                        pending = Some((entry.clone(), action));
+                    }
+                    for &action in EntryAction::for_entry(entry.is_dir) {
+                        if ui
+                            .button(state.localizer.t(state.language, action.label_key()))
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            pending = Some((entry.clone(), action));
+                        }
+                    }
+                });
+            }
+        });
+    }
+    if let Some((entry, action)) = pending {
+        apply_entry_action(state, &entry, action);
+    }
+}
+
+/// Actions on the selected entries: select all, clear, and download them into one folder
+fn show_selection_bar(ui: &mut egui::Ui, state: &mut UIState) {
+    ui.horizontal(|ui| {
+        let mut view = state.view.clone();
+        ui.selectable_value(
+            &mut view.mode,
+            ViewMode::List,
+            state.localizer.t(state.language, "view_list"),
+        );
+        ui.selectable_value(
+            &mut view.mode,
+            ViewMode::Grid,
+            state.localizer.t(state.language, "view_grid"),
+        );
+        let sort_key = |key| match key {
+            SortKey::Name => "sort_name",
+            SortKey::Modified => "sort_modified",
+            SortKey::Size => "sort_size",
+        };
+        egui::ComboBox::from_id_salt("sort_key")
+            .selected_text(format!(
+                "{} {}",
+                state.localizer.t(state.language, "sort_label"),
+                state.localizer.t(state.language, sort_key(view.sort))
+            ))
+            .show_ui(ui, |ui| {
+                for key in [SortKey::Name, SortKey::Modified, SortKey::Size] {
+                    ui.selectable_value(
+                        &mut view.sort,
+                        key,
+                        state.localizer.t(state.language, sort_key(key)),
+                    );
+                }
+            });
+        let arrow = if view.descending { "⬇" } else { "⬆" };
+        ui.toggle_value(&mut view.descending, arrow)
+            .on_hover_text(state.localizer.t(state.language, "sort_descending"));
+        let filter = ui.add(
+            egui::TextEdit::singleline(&mut view.filter)
+                .desired_width(100.0)
+                .hint_text(state.localizer.t(state.language, "filter_hint")),
+        );
+        ui.checkbox(
+            &mut view.show_hidden,
+            state.localizer.t(state.language, "show_hidden_checkbox"),
+        );
+        let list_mode = view.mode == ViewMode::List;
+        if view != state.view {
+            // Typing a filter narrows the list at once but is saved only when
+            // the edit ends, not on every keystroke
+            let typing = filter.has_focus() && view.filter != state.view.filter;
+            state.set_view(view, !typing);
+        }
+        if filter.lost_focus() {
+            save_settings(&state.settings);
+        }
+        if ui
+            .checkbox(
+                &mut state.settings.views_per_directory,
+                state
+                    .localizer
+                    .t(state.language, "views_per_directory_checkbox"),
+            )
+            .on_hover_text(
+                state
+                    .localizer
+                    .t(state.language, "views_per_directory_hint"),
+            )
+            .changed()
+        {
+            // Keep what is shown now, as this folder's view or as the one for all
+            state.set_view(state.view.clone(), true);
+        }
+        if list_mode
+            && ui
+                .checkbox(
+                    &mut state.settings.hover_actions,
+                    state.localizer.t(state.language, "hover_actions_checkbox"),
+                )
+                .on_hover_text(state.localizer.t(state.language, "hover_actions_hint"))
+                .changed()
+        {
+            save_settings(&state.settings);
+        }
+        ui.separator();
+        if ui
+            .small_button(state.localizer.t(state.language, "select_all_button"))
+            .clicked()
+        {
+            state.selected = state.files.iter().map(|entry| entry.name.clone()).collect();
+        }
+        if state.selected.is_empty() {
+            return;
+        }
+        ui.label(format!(
+            "{} {}",
+            state.selected.len(),
+            state.localizer.t(state.language, "selected_count")
+        ));
+        if ui
+            .button(
+                state
+                    .localizer
+                    .t(state.language, "download_selected_button"),
+            )
+            .clicked()
+        {
+            // One folder for the whole batch instead of a dialog per file
+            if let Some(local_dir) = rfd::FileDialog::new().pick_folder() {
+                let base = state.current_path.trim_end_matches('/').to_string();
+                let entries: Vec<FileEntry> = state
+                    .files
+                    .iter()
+                    .filter(|entry| state.selected.contains(&entry.name))
+                    .cloned()
+                    .collect();
+                for entry in entries {
+                    let remote = format!("{}/{}", base, entry.name);
+                    let local = name_from_os(local_dir.join(&entry.name).as_os_str());
+                    state.enqueue(if entry.is_dir {
+                        Transfer::DownloadDirectory {
+                            remote,
+                            local,
+                            sync: state.sync_mode(),
+                            mirror: state.mirror,
+                        }
+                    } else {
+                        Transfer::DownloadFile { remote, local }
+                    });
+                }
+                state.selected.clear();
+            }
+        }
+        if ui
+            .small_button(state.localizer.t(state.language, "clear_selection_button"))
+            .clicked()
+        {
+            state.selected.clear();
+        }
+    });
+}
+
+/// A full-width strip in the connection's accent color with its label, so
+/// that a production server is hard to mistake for another one
+fn show_connection_banner(ui: &mut egui::Ui, state: &UIState) {
+    let label = state.connection_label.trim();
+    if label.is_empty() && state.connection_color.is_none() {
+        return;
+    }
+    let fill = state
+        .connection_color
+        .map(|[r, g, b]| egui::Color32::from_rgb(r, g, b))
+        .unwrap_or(ui.visuals().selection.bg_fill);
+    // Dark text on light colors, light text on dark ones
+    let luminance = 0.299 * fill.r() as f32 + 0.587 * fill.g() as f32 + 0.114 * fill.b() as f32;
+    let text_color = if luminance > 150.0 {
+        egui::Color32::BLACK
+    } else {
+        egui::Color32::WHITE
+    };
+    let text = if label.is_empty() {
+        format!("{}@{}", state.username, state.hostname)
+    } else {
+        format!("{} — {}@{}", label, state.username, state.hostname)
+    };
+    egui::Frame::none()
+        .fill(fill)
+        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.label(
+                egui::RichText::new(text)
+                    .strong()
+                    .size(16.0)
+                    .color(text_color),
+            );
+        });
+}
+
+/// Choose the log level and the file the log is written to
+fn show_logging_controls(ui: &mut egui::Ui, state: &mut UIState) {
+    ui.label(state.localizer.t(state.language, "log_level_label"));
+    let mut level = state.settings.log_level;
+    egui::ComboBox::from_id_salt("log_level")
+        .selected_text(level.as_str())
+        .show_ui(ui, |ui| {
+            for choice in log::LevelFilter::iter() {
+                ui.selectable_value(&mut level, choice, choice.as_str());
+            }
+        });
+    if level != state.settings.log_level {
+        state.settings.log_level = level;
+        logging::set_level(level);
+        save_settings(&state.settings);
+    }
+    let file_hint = if state.settings.log_file.is_empty() {
+        state
+            .localizer
+            .t(state.language, "log_file_hint")
+            .to_string()
+    } else {
+        state.settings.log_file.clone()
+    };
+    let mut file = None;
+    ui.menu_button(state.localizer.t(state.language, "log_file_button"), |ui| {
+        if ui
+            .button(state.localizer.t(state.language, "log_file_choose"))
+            .clicked()
+        {
+            ui.close_menu();
+            if let Some(path) = rfd::FileDialog::new()
+                .set_file_name("ssh-browser.log")
+                .save_file()
+            {
+                file = Some(name_from_os(path.as_os_str()));
+            }
+        }
+        if ui
+            .add_enabled(
+                !state.settings.log_file.is_empty(),
+                egui::Button::new(state.localizer.t(state.language, "log_file_none")),
+            )
+            .clicked()
+        {
+            ui.close_menu();
+            file = Some(String::new());
+        }
+    })
+    .response
+    .on_hover_text(file_hint);
+    if let Some(file) = file {
+        match logging::set_file(&file) {
+            Ok(()) => {
+                state.settings.log_file = file;
+                save_settings(&state.settings);
+            }
+            Err(e) => state.error_message = Some(e),
+        }
+    }
+}
+
+/// Name the connected server and its label in the window title, so that the
+/// window can be told apart in the taskbar
+fn update_window_title(ctx: &egui::Context, state: &mut UIState) {
+    let label = state.connection_label.trim();
+    let title = if !state.connected {
+        APP_TITLE.to_string()
+    } else if label.is_empty() {
+        format!("{}@{} — {}", state.username, state.hostname, APP_TITLE)
+    } else {
+        format!(
+            "[{}] {}@{} — {}",
+            label, state.username, state.hostname, APP_TITLE
+        )
+    };
+    if title != state.window_title {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+        state.window_title = title;
+    }
+}
+
+/// Slim always-visible badges with the server's load, memory and root disk usage
+fn show_quick_stats(ui: &mut egui::Ui, state: &mut UIState) {
+    let due = state
+        .last_quick_stats_refresh
+        .is_none_or(|last| last.elapsed() >= QUICK_STATS_REFRESH_INTERVAL);
+    if due && !state.operation_in_progress {
+        state.last_quick_stats_refresh = Some(Instant::now());
+        state
+            .worker
+            .lock()
+            .unwrap()
+            .send_task(Task::FetchQuickStats);
+    }
+    ui.ctx().request_repaint_after(QUICK_STATS_REFRESH_INTERVAL);
+
+    let Some(stats) = &state.quick_stats else {
+        return;
+    };
+    let t = |key| state.localizer.t(state.language, key);
+    ui.horizontal(|ui| {
+        if let Some([one, five, fifteen]) = stats.load {
+            let ratio = one / stats.cpus.unwrap_or(1).max(1) as f32;
+            stats_badge(
+                ui,
+                &format!(
+                    "{} {:.2} {:.2} {:.2}",
+                    t("quick_stats_load"),
+                    one,
+                    five,
+                    fifteen
+                ),
+                ratio,
+            );
+        }
+        if let Some((used, total)) = stats.memory {
+            stats_badge(
+                ui,
+                &format!(
+                    "{} {} / {}",
+                    t("quick_stats_memory"),
+                    format_bytes(used),
+                    format_bytes(total)
+                ),
+                used as f32 / total.max(1) as f32,
+            );
+        }
+        if let Some((used, total)) = stats.disk {
+            stats_badge(
+                ui,
+                &format!(
+                    "{} {} / {}",
+                    t("quick_stats_disk"),
+                    format_bytes(used),
+                    format_bytes(total)
+                ),
+                used as f32 / total.max(1) as f32,
+            );
+        }
+    });
+}
+
+/// A small label colored green, yellow or red depending on how close `ratio` is to 1
+fn stats_badge(ui: &mut egui::Ui, text: &str, ratio: f32) {
+    let color = if ratio >= 0.9 {
+        egui::Color32::from_rgb(200, 60, 60)
+    } else if ratio >= 0.7 {
+        egui::Color32::from_rgb(200, 160, 40)
+    } else {
+        egui::Color32::from_rgb(60, 150, 80)
+    };
+    ui.label(
+        egui::RichText::new(format!(" {} ", text))
+            .small()
+            .color(egui::Color32::WHITE)
+            .background_color(color),
+    );
+}
+
+/// Format a byte count with a binary unit, e.g. `3.2 GiB`
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// A pseudo-terminal running a login shell on the server, for interactive
+/// programs such as `top` or anything that prompts for input
+fn show_terminal_panel(ui: &mut egui::Ui, state: &mut UIState) {
+    ui.collapsing(state.localizer.t(state.language, "terminal_panel"), |ui| {
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let (char_width, row_height) =
+            ui.fonts(|fonts| (fonts.glyph_width(&font, 'M'), fonts.row_height(&font)));
+        let cols = ((ui.available_width() - 8.0) / char_width).floor() as usize;
+        let size = (cols.clamp(20, 400), TERMINAL_ROWS);
+
+        ui.horizontal(|ui| {
+            let running = state.terminal.as_ref().is_some_and(|t| t.running);
+            if !running
+                && ui
+                    .button(state.localizer.t(state.language, "open_terminal_button"))
+                    .clicked()
+            {
+                let (input, receiver) = mpsc::channel();
+                state.terminal = Some(TerminalSession {
+                    input,
+                    screen: Screen::new(size.0, size.1),
+                    running: true,
+                });
+                state.worker.lock().unwrap().send_task(Task::OpenTerminal(
+                    size.0 as u32,
+                    size.1 as u32,
+                    receiver,
+                ));
+            }
+            if running {
+                ui.label(state.localizer.t(state.language, "terminal_hint"));
+            }
+            if state.terminal.is_some()
+                && ui
+                    .button(state.localizer.t(state.language, "close_terminal_button"))
+                    .clicked()
+            {
+                state.terminal = None;
+            }
+        });
+
+        let Some(terminal) = state.terminal.as_mut() else {
+            return;
+        };
+        if terminal.running && terminal.screen.size() != size {
+            terminal.screen.resize(size.0, size.1);
+            let _ = terminal
+                .input
+                .send(TerminalInput::Resize(size.0 as u32, size.1 as u32));
+        }
+
+        egui::ScrollArea::vertical()
+            .id_salt("terminal")
+            .max_height(row_height * (TERMINAL_ROWS as f32 + 0.5))
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                let galley = ui.fonts(|fonts| fonts.layout_job(terminal_layout(terminal, &font)));
+                let (rect, _) = ui.allocate_exact_size(
+                    egui::vec2(ui.available_width(), galley.size().y),
+                    egui::Sense::hover(),
+                );
+                let response =
+                    ui.interact(rect, ui.id().with("terminal_screen"), egui::Sense::click());
+                if response.clicked() {
+                    response.request_focus();
+                }
+                let focused = response.has_focus();
+                ui.painter().rect_filled(rect, 2.0, egui::Color32::BLACK);
+                ui.painter()
+                    .galley(rect.min, galley, egui::Color32::from_gray(220));
+                if focused {
+                    ui.painter()
+                        .rect_stroke(rect, 2.0, ui.visuals().selection.stroke);
+                    // Keep Tab, arrows and Escape for the shell instead of moving focus
+                    ui.memory_mut(|memory| {
+                        memory.set_focus_lock_filter(
+                            response.id,
+                            egui::EventFilter {
+                                tab: true,
+                                horizontal_arrows: true,
+                                vertical_arrows: true,
+                                escape: true,
+                            },
+                        )
+                    });
+                    if terminal.running {
+                        let input: Vec<u8> = ui.input(|i| {
+                            i.events
+                                .iter()
+                                .filter_map(terminal_bytes)
+                                .flatten()
+                                .collect()
+                        });
+                        if !input.is_empty() {
+                            let _ = terminal.input.send(TerminalInput::Data(input));
+                        }
+                    }
+                }
+            });
+        if terminal.running {
+            // Output arrives without user input, so keep polling for it
+            ui.ctx().request_repaint_after(Duration::from_millis(50));
+        }
+    });
+}
+
+/// The scrollback and screen of a terminal, with the cursor shown inverted
+fn terminal_layout(terminal: &TerminalSession, font: &egui::FontId) -> egui::text::LayoutJob {
+    let text_color = egui::Color32::from_gray(220);
+    let plain = egui::TextFormat::simple(font.clone(), text_color);
+    let mut job = egui::text::LayoutJob::default();
+    let mut text = String::new();
+    for line in terminal.screen.scrollback() {
+        text.push_str(line);
+        text.push('\n');
+    }
+    let (cursor_row, cursor_col) = terminal.screen.cursor();
+    for (index, row) in terminal.screen.rows().enumerate() {
+        if index > 0 {
+            text.push('\n');
+        }
+        if index == cursor_row && terminal.running {
+            let mut chars = row.chars();
+            text.extend(chars.by_ref().take(cursor_col));
+            job.append(&std::mem::take(&mut text), 0.0, plain.clone());
+            let cursor = egui::TextFormat {
+                background: text_color,
+                ..egui::TextFormat::simple(font.clone(), egui::Color32::BLACK)
+            };
+            job.append(&chars.next().unwrap_or(' ').to_string(), 0.0, cursor);
+            text.extend(chars);
+        } else {
+            text.push_str(&row);
+        }
+    }
+    job.append(&text, 0.0, plain);
+    job
+}
+
+/// The bytes a terminal sends for a key press or pasted text
+fn terminal_bytes(event: &egui::Event) -> Option<Vec<u8>> {
+    use egui::Key;
+    let bytes: &[u8] = match event {
+        egui::Event::Text(text) | egui::Event::Paste(text) => {
+            return Some(text.as_bytes().to_vec())
+        }
+        // Ctrl+C and Ctrl+X arrive as clipboard commands
+        egui::Event::Copy => b"\x03",
+        egui::Event::Cut => b"\x18",
+        egui::Event::Key {
+            key,
+            pressed: true,
+            modifiers,
+            ..
+        } => match key {
+            Key::Enter => b"\r",
+            Key::Backspace => b"\x7f",
+            Key::Tab => b"\t",
+            Key::Escape => b"\x1b",
+            Key::ArrowUp => b"\x1b[A",
+            Key::ArrowDown => b"\x1b[B",
+            Key::ArrowRight => b"\x1b[C",
+            Key::ArrowLeft => b"\x1b[D",
+            Key::Home => b"\x1b[H",
+            Key::End => b"\x1b[F",
+            Key::Delete => b"\x1b[3~",
+            Key::PageUp => b"\x1b[5~",
+            Key::PageDown => b"\x1b[6~",
+            key if modifiers.ctrl && !matches!(key, Key::C | Key::X | Key::V) => {
+                // Ctrl+A is 1, Ctrl+B is 2, ...
+                let letter = key
+                    .name()
+                    .bytes()
+                    .next()
+                    .filter(|_| key.name().len() == 1)?;
+                return letter.is_ascii_uppercase().then(|| vec![letter - b'A' + 1]);
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(bytes.to_vec())
+}
+
+/// Search the current directory tree by name and jump to the folder of a result
+fn show_find_panel(ui: &mut egui::Ui, state: &mut UIState) {
+    let mut jump_to = None;
+    ui.collapsing(state.localizer.t(state.language, "find_panel"), |ui| {
+        ui.horizontal(|ui| {
+            let input = ui.text_edit_singleline(&mut state.find_pattern);
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.label(state.localizer.t(state.language, "find_depth_label"));
+            ui.add(egui::DragValue::new(&mut state.find_max_depth).range(1..=100));
+            ui.label(state.localizer.t(state.language, "find_time_label"));
+            ui.add(
+                egui::DragValue::new(&mut state.find_time_limit)
+                    .range(1..=600)
+                    .suffix(" s"),
+            );
+            if (ui
+                .button(state.localizer.t(state.language, "find_button"))
+                .clicked()
+                || submitted)
+                && !state.find_pattern.trim().is_empty()
+            {
+                state.operation_in_progress = true;
+                // Matches are added as they arrive
+                state.find_results = Some(Vec::new());
+                state.worker.lock().unwrap().send_task(Task::FindFiles(
+                    state.current_path.clone(),
+                    state.find_pattern.trim().to_string(),
+                    state.find_max_depth,
+                    state.find_time_limit,
+                ));
+            }
+        });
+
+        if let Some(results) = &state.find_results {
+            let mut summary = format!(
+                "{} {}",
+                results.len(),
+                state.localizer.t(state.language, "find_results_count")
+            );
+            if results.len() >= MAX_FIND_RESULTS {
+                summary.push_str(&format!(
+                    " ({})",
+                    state.localizer.t(state.language, "find_results_truncated")
+                ));
+            }
+            ui.label(summary);
+            egui::ScrollArea::vertical()
+                .id_salt("find_results")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for path in results {
+                        if ui
+                            .link(path)
+                            .on_hover_text(state.localizer.t(state.language, "find_result_hint"))
+                            .clicked()
+                        {
+                            jump_to = Some(path.clone());
+                        }
+                    }
+                });
+        }
+    });
+
+    if let Some(path) = jump_to {
+        let parent = match path.rfind('/') {
+            Some(0) => "/".to_string(),
+            Some(slash) => path[..slash].to_string(),
+            None => return,
+        };
+        state.current_path = parent.clone();
+        state.operation_in_progress = true;
+        state
+            .worker
+            .lock()
+            .unwrap()
+            .send_task(Task::ListDirectory(parent));
+    }
+}
+
+/// Search the contents of the files below the current directory and open a
+/// result in the editor at the matching line
+fn show_grep_panel(ui: &mut egui::Ui, state: &mut UIState) {
+    let mut open = None;
+    ui.collapsing(state.localizer.t(state.language, "grep_panel"), |ui| {
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut state.grep_query.pattern)
+                    .hint_text(state.localizer.t(state.language, "grep_pattern_hint")),
+            );
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.checkbox(
+                &mut state.grep_query.ignore_case,
+                state.localizer.t(state.language, "grep_ignore_case"),
+            );
+            ui.label(state.localizer.t(state.language, "grep_files_label"));
+            ui.add(
+                egui::TextEdit::singleline(&mut state.grep_query.file_glob)
+                    .desired_width(80.0)
+                    .hint_text("*.conf"),
+            );
+            if state.grep_running {
+                if ui
+                    .button(state.localizer.t(state.language, "stop_button"))
+                    .clicked()
+                {
+                    state.worker.lock().unwrap().stop_grep();
+                }
+                ui.spinner();
+            } else if (ui
+                .button(state.localizer.t(state.language, "find_button"))
+                .clicked()
+                || submitted)
+                && !state.grep_query.pattern.is_empty()
+            {
+                state.grep_running = true;
+                // Matches are added as they arrive
+                state.grep_results = Some(Vec::new());
+                let mut query = state.grep_query.clone();
+                query.file_glob = query.file_glob.trim().to_string();
+                state
+                    .worker
+                    .lock()
+                    .unwrap()
+                    .send_task(Task::Grep(state.current_path.clone(), query));
+            }
+        });
+
+        if let Some(results) = &state.grep_results {
+            let mut summary = format!(
+                "{} {}",
+                results.len(),
+                state.localizer.t(state.language, "grep_results_count")
+            );
+            if results.len() >= MAX_GREP_RESULTS {
+                summary.push_str(&format!(
+                    " ({})",
+                    state.localizer.t(state.language, "find_results_truncated")
+                ));
+            }
+            ui.label(summary);
+            egui::ScrollArea::vertical()
+                .id_salt("grep_results")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for found in results {
+                        ui.horizontal(|ui| {
+                            if ui
+                                .link(format!("{}:{}", found.path, found.line))
+                                .on_hover_text(
+                                    state.localizer.t(state.language, "grep_result_hint"),
+                                )
+                                .clicked()
+                            {
+                                open = Some((found.path.clone(), found.line));
+                            }
+                            ui.monospace(&found.text);
+                        });
+                    }
+                });
+        }
+    });
+
+    if let Some((path, line)) = open {
+        state.open_at_line = Some((path.clone(), line));
+        state.operation_in_progress = true;
+        let task = Task::ReadFile(path, state.max_edit_size(), state.default_encoding());
+        state.worker.lock().unwrap().send_task(task);
+    }
+}
+
+/// Split a typed path into the directory to list and the partial name to complete,
+/// e.g. `/var/lo` into (`/var/`, `lo`). A name without a slash is completed in
+/// the current directory.
+fn split_typed_path(typed: &str) -> (&str, &str) {
+    match typed.rfind('/') {
+        Some(slash) => (&typed[..=slash], &typed[slash + 1..]),
+        None => ("", typed),
+    }
+}
+
+/// The names of the subdirectories among listed entries, for completion
+fn subdirectory_names(entries: &[FileEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|entry| entry.is_dir)
+        .map(|entry| entry.name.clone())
+        .collect()
+}
+
+/// How well a directory name matches the partial name typed, lower is better:
+/// a prefix, a prefix ignoring case, a part of the name ignoring case, or
+/// just the typed letters in order (`dcmt` for `Documents`)
+fn completion_rank(name: &str, partial: &str) -> Option<u8> {
+    if name.starts_with(partial) {
+        return Some(0);
+    }
+    let name = name.to_lowercase();
+    let partial = partial.to_lowercase();
+    if name.starts_with(&partial) {
+        Some(1)
+    } else if name.contains(&partial) {
+        Some(2)
+    } else {
+        let mut letters = name.chars();
+        partial
+            .chars()
+            .all(|wanted| letters.any(|c| c == wanted))
+            .then_some(3)
+    }
+}
+
+/// Offer the subdirectories matching the path being typed, fetching them once
+/// typing pauses unless they were listed before. Tab completes the best match
+/// in the path box; clicking one navigates there.
+fn show_path_completions(ui: &mut egui::Ui, state: &mut UIState, response: &egui::Response) {
+    let popup_id = ui.make_persistent_id("path_completions");
+    let (dir, partial) = split_typed_path(&state.current_path);
+    let (dir, partial) = (
+        resolve_remote_path(&state.listed_path, dir),
+        partial.to_string(),
+    );
+
+    let settled = state
+        .path_edited_at
+        .is_some_and(|edited| edited.elapsed() >= COMPLETION_DEBOUNCE);
+    if response.has_focus() && state.path_edited_at.is_some() {
+        if settled {
+            state.path_edited_at = None;
+            if !state.completion_cache.contains_key(&dir)
+                && state.completion_dir.as_deref() != Some(dir.as_str())
+            {
+                state.completion_dir = Some(dir.clone());
+                state
+                    .worker
+                    .lock()
+                    .unwrap()
+                    .send_task(Task::ListCompletions(dir.clone()));
+            }
+            ui.memory_mut(|memory| memory.open_popup(popup_id));
+        } else {
+            ui.ctx().request_repaint_after(COMPLETION_DEBOUNCE);
+        }
+    }
+
+    let Some(names) = state.completion_cache.get(&dir) else {
+        return;
+    };
+    let mut matches: Vec<(u8, &String)> = names
+        .iter()
+        .filter(|name| **name != partial)
+        .filter_map(|name| Some((completion_rank(name, &partial)?, name)))
+        .collect();
+    matches.sort();
+    let matches: Vec<String> = matches
+        .into_iter()
+        .take(MAX_COMPLETIONS)
+        .map(|(_, name)| name.clone())
+        .collect();
+    if matches.is_empty() {
+        return;
+    }
+
+    if response.has_focus() && ui.input(|input| input.key_pressed(egui::Key::Tab)) {
+        state.current_path = format!("{}/{}/", dir.trim_end_matches('/'), matches[0]);
+        // Complete further inside the chosen directory
+        state.path_edited_at = Some(Instant::now());
+        if let Some(mut text_state) = egui::TextEdit::load_state(ui.ctx(), response.id) {
+            let end = egui::text::CCursor::new(state.current_path.chars().count());
+            text_state
+                .cursor
+                .set_char_range(Some(egui::text::CCursorRange::one(end)));
+            text_state.store(ui.ctx(), response.id);
+        }
+        return;
+    }
+
+    let mut chosen = None;
+    egui::popup_below_widget(
+        ui,
+        popup_id,
+        response,
+        egui::PopupCloseBehavior::CloseOnClickOutside,
+        |ui| {
+            for (index, name) in matches.iter().enumerate() {
+                let label = ui.selectable_label(index == 0, format!("📁 {}", name));
+                let label = if index == 0 {
+                    label.on_hover_text(state.localizer.t(state.language, "completion_tab_hint"))
+                } else {
+                    label
+                };
+                if label.clicked() {
+                    chosen = Some(name.clone());
+                }
+            }
+        },
+    );
+    if let Some(name) = chosen {
+        ui.memory_mut(|memory| memory.close_popup());
+        state.current_path = format!("{}/{}", dir.trim_end_matches('/'), name);
+        state.operation_in_progress = true;
+        let path = state.current_path.clone();
+        state
+            .worker
+            .lock()
+            .unwrap()
+            .send_task(Task::ListDirectory(path));
+    }
+}
+
+/// Deletions above this count need an extra acknowledgement before mirroring
+const MIRROR_LARGE_DELETE: usize = 50;
+
+/// Ask before deleting the extra destination entries found by a mirror sync.
+/// Large deletions, or ones removing most of the destination, must be acknowledged first.
+fn show_mirror_confirmation(ctx: &egui::Context, state: &mut UIState) {
+    let Some(plan) = state.mirror_plan.clone() else {
+        return;
+    };
+    let count = plan.entries.len();
+    let large = count > MIRROR_LARGE_DELETE || count * 2 > plan.destination_total;
+
+    egui::Window::new(state.localizer.t(state.language, "mirror_confirm_window"))
+        .collapsible(false)
+        .show(ctx, |ui| {
+            let side_key = match plan.side {
+                MirrorSide::Local => "mirror_side_local",
+                MirrorSide::Remote => "mirror_side_remote",
+            };
+            ui.label(format!(
+                "{} {} ({})",
+                count,
+                state.localizer.t(state.language, "mirror_confirm_message"),
+                state.localizer.t(state.language, side_key)
+            ));
+            ui.monospace(&plan.root);
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for (path, is_dir) in &plan.entries {
+                        ui.label(format!("{} {}", if *is_dir { "📁" } else { "📄" }, path));
+                    }
+                });
+            if large {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    state.localizer.t(state.language, "mirror_large_warning"),
+                );
+                ui.checkbox(
+                    &mut state.mirror_acknowledged,
+                    state.localizer.t(state.language, "mirror_acknowledge"),
+                );
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        !large || state.mirror_acknowledged,
+                        egui::Button::new(state.localizer.t(state.language, "delete_button")),
+                    )
+                    .clicked()
+                {
+                    state.mirror_plan = None;
+                    state.operation_in_progress = true;
+                    let worker = state.worker.clone();
+                    worker
+                        .lock()
+                        .unwrap()
+                        .send_task(Task::ApplyMirrorPlan(plan.clone()));
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    state.mirror_plan = None;
+                }
+            });
+        });
+}
+
+/// Ask what to do about the remote files a folder upload would overwrite
+fn show_upload_conflicts(ctx: &egui::Context, state: &mut UIState) {
+    let Some((transfer, files)) = state.upload_conflicts.clone() else {
+        return;
+    };
+    let Transfer::UploadDirectory { remote, .. } = &transfer else {
+        return;
+    };
+
+    egui::Window::new(state.localizer.t(state.language, "upload_conflicts_window"))
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "{} {}",
+                files.len(),
+                state
+                    .localizer
+                    .t(state.language, "upload_conflicts_message")
+            ));
+            ui.monospace(display_name(remote));
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for file in &files {
+                        ui.label(format!("📄 {}", display_name(file)));
+                    }
+                });
+            ui.horizontal(|ui| {
+                let mut chosen = None;
+                if ui
+                    .button(state.localizer.t(state.language, "skip_existing_button"))
+                    .clicked()
+                {
+                    chosen = Some(SyncMode::SkipExisting);
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "overwrite_all_button"))
+                    .clicked()
+                {
+                    chosen = Some(state.sync_mode());
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    state.upload_conflicts = None;
+                }
+                if let Some(mode) = chosen {
+                    state.upload_conflicts = None;
+                    let mut transfer = transfer.clone();
+                    if let Transfer::UploadDirectory { sync, .. } = &mut transfer {
+                        *sync = mode;
+                    }
+                    state.enqueue(transfer);
+                }
+            });
+        });
+}
+
+/// Offer to create the missing remote directories an upload needs and run it again
+fn show_missing_upload_parent(ctx: &egui::Context, state: &mut UIState) {
+    let Some(transfer) = state.missing_upload_parent.clone() else {
+        return;
+    };
+    let Some(remote) = transfer.upload_target() else {
+        return;
+    };
+    let parent = remote_parent(remote).unwrap_or("/");
+
+    egui::Window::new(state.localizer.t(state.language, "missing_parent_window"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(state.localizer.t(state.language, "missing_parent_message"));
+            ui.monospace(display_name(parent));
+            ui.horizontal(|ui| {
+                if ui
+                    .button(
+                        state
+                            .localizer
+                            .t(state.language, "create_and_upload_button"),
+                    )
+                    .clicked()
+                {
+                    state.missing_upload_parent = None;
+                    state.enqueue(transfer.clone().with_create_parents());
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    state.missing_upload_parent = None;
+                }
+            });
+        });
+}
+
+/// List the changes a dry run found, and carry out the operation once confirmed
+fn show_dry_run_plan(ctx: &egui::Context, state: &mut UIState) {
+    let Some((operation, changes)) = state.dry_run_plan.clone() else {
+        return;
+    };
+
+    egui::Window::new(state.localizer.t(state.language, "dry_run_window"))
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "{} {}",
+                changes.len(),
+                state.localizer.t(state.language, "dry_run_message")
+            ));
+            egui::ScrollArea::vertical()
+                .id_salt("dry_run_changes")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for change in &changes {
+                        match change {
+                            PlannedChange::Delete(path) => ui.monospace(format!(
+                                "{} {}",
+                                state.localizer.t(state.language, "dry_run_delete"),
+                                display_name(path)
+                            )),
+                            PlannedChange::Move { from, to } => ui.monospace(format!(
+                                "{} {} → {}",
+                                state.localizer.t(state.language, "dry_run_move"),
+                                display_name(from),
+                                display_name(to)
+                            )),
+                        };
+                    }
+                });
+            ui.horizontal(|ui| {
+                if ui
+                    .button(state.localizer.t(state.language, "proceed_button"))
+                    .clicked()
+                {
+                    state.dry_run_plan = None;
+                    state.operation_in_progress = true;
+                    let worker = state.worker.clone();
+                    worker
+                        .lock()
+                        .unwrap()
+                        .send_task(operation.clone().into_task());
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    state.dry_run_plan = None;
+                }
+            });
+        });
+}
+
+/// Ask before creating or renaming over an existing file
+fn show_replace_confirmation(ctx: &egui::Context, state: &mut UIState) {
+    let Some((path, check)) = state.confirm_replace.clone() else {
+        return;
+    };
+
+    egui::Window::new(state.localizer.t(state.language, "confirm_replace_window"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(state.localizer.t(state.language, "confirm_replace_message"));
+            ui.monospace(display_name(&path));
+            ui.horizontal(|ui| {
+                if ui
+                    .button(state.localizer.t(state.language, "replace_button"))
+                    .clicked()
+                {
+                    state.confirm_replace = None;
+                    state.operation_in_progress = true;
+                    let task = check
+                        .clone()
+                        .into_replacing_task(path.clone(), state.settings.dry_run);
+                    let worker = state.worker.clone();
+                    worker.lock().unwrap().send_task(task);
+                }
+                if ui
+                    .button(state.localizer.t(state.language, "cancel_button"))
+                    .clicked()
+                {
+                    state.confirm_replace = None;
+                    state.created_file_to_edit = None;
+                }
+            });
+        });
+}
+
+/// A change to the transfer queue requested from the queue panel
+enum QueueAction {
+    SetPaused(bool),
+    /// Move a pending transfer before another one (moved id, target id)
+    MoveBefore(u64, u64),
+    CancelPending(u64),
+    /// Run a pending transfer before or after its pending neighbour
+    MoveUp(u64),
+    MoveDown(u64),
+    /// Give a pending transfer its own speed limit in KiB/s, or use the global one
+    SetSpeedLimit(u64, Option<u32>),
+    CancelActive,
+    /// Cancel every pending transfer and the active one
+    StopAll,
+    RemoveFinished(u64),
+    ClearFinished,
+}
+
+/// Draw the transfer queue: Pause/Resume, drag-and-drop reordering of pending
+/// transfers and per-item cancel/remove buttons
+fn show_transfer_queue(ui: &mut egui::Ui, state: &mut UIState) {
+    let (items, paused) = {
+        let worker = state.worker.lock().unwrap();
+        let queue = worker.queue.lock().unwrap();
+        (queue.items().to_vec(), queue.paused)
+    };
+    if items.is_empty() {
+        return;
+    }
+
+    let mut actions = Vec::new();
+    ui.collapsing(state.localizer.t(state.language, "transfer_queue"), |ui| {
+        ui.horizontal(|ui| {
+            if paused {
+                ui.label(state.localizer.t(state.language, "queue_paused"));
+                if ui
+                    .button(state.localizer.t(state.language, "resume_queue"))
+                    .clicked()
+                {
+                    actions.push(QueueAction::SetPaused(false));
+                }
+            } else if ui
+                .button(state.localizer.t(state.language, "pause_queue"))
+                .clicked()
+            {
+                actions.push(QueueAction::SetPaused(true));
+            }
+            if items.iter().any(|item| !item.status.is_finished())
+                && ui
+                    .button(state.localizer.t(state.language, "stop_all_button"))
+                    .on_hover_text(state.localizer.t(state.language, "stop_all_hint"))
+                    .clicked()
+            {
+                actions.push(QueueAction::StopAll);
+            }
+            if items.iter().any(|item| item.status.is_finished())
+                && ui
+                    .button(state.localizer.t(state.language, "clear_finished"))
+                    .clicked()
+            {
+                actions.push(QueueAction::ClearFinished);
+            }
+        });
+
+        for item in &items {
+            let row = ui.horizontal(|ui| {
+                let status_key = match &item.status {
+                    TransferStatus::Pending => "transfer_pending",
+                    TransferStatus::Active => "transfer_active",
+                    TransferStatus::Done => "transfer_done",
+                    TransferStatus::Cancelled => "transfer_cancelled",
+                    TransferStatus::Failed(_) => "transfer_failed",
+                    TransferStatus::Interrupted(_) => "transfer_interrupted",
+                };
+                let status = ui.label(state.localizer.t(state.language, status_key));
+                if let TransferStatus::Failed(error) | TransferStatus::Interrupted(error) =
+                    &item.status
+                {
+                    status.on_hover_text(error);
+                }
+                if item.status == TransferStatus::Pending {
+                    ui.dnd_drag_source(
+                        egui::Id::new(("queued_transfer", item.id)),
+                        item.id,
+                        |ui| {
+                            ui.label(format!("☰ {}", item.transfer.describe()));
+                        },
+                    )
+                    .response
+                    .on_hover_text(state.localizer.t(state.language, "drag_to_reorder"));
+                    if ui
+                        .small_button("↑")
+                        .on_hover_text(state.localizer.t(state.language, "move_up_hint"))
+                        .clicked()
+                    {
+                        actions.push(QueueAction::MoveUp(item.id));
+                    }
+                    if ui
+                        .small_button("↓")
+                        .on_hover_text(state.localizer.t(state.language, "move_down_hint"))
+                        .clicked()
+                    {
+                        actions.push(QueueAction::MoveDown(item.id));
+                    }
+                } else {
+                    ui.label(item.transfer.describe());
+                }
+                if item.status == TransferStatus::Pending {
+                    let mut own = item.speed_limit_kib.is_some();
+                    let mut kib = item
+                        .speed_limit_kib
+                        .unwrap_or(state.settings.speed_limit_kib);
+                    let mut changed = ui
+                        .checkbox(
+                            &mut own,
+                            state.localizer.t(state.language, "own_speed_limit"),
+                        )
+                        .on_hover_text(state.localizer.t(state.language, "own_speed_limit_hint"))
+                        .changed();
+                    if own {
+                        changed |= ui.add(speed_limit_drag_value(&mut kib)).changed();
+                    }
+                    if changed {
+                        actions.push(QueueAction::SetSpeedLimit(item.id, own.then_some(kib)));
+                    }
+                }
+                if item.status == TransferStatus::Active {
+                    if let Some(RecursiveProgress::Item { done, total, .. }) = &state.progress {
+                        ui.label(format!("{} / {}", done, total));
+                    }
+                }
+
+                let (key, action) = match item.status {
+                    TransferStatus::Pending => {
+                        ("cancel_button", QueueAction::CancelPending(item.id))
+                    }
+                    TransferStatus::Active => ("cancel_button", QueueAction::CancelActive),
+                    _ => ("remove_button", QueueAction::RemoveFinished(item.id)),
+                };
+                if ui
+                    .small_button(state.localizer.t(state.language, key))
+                    .clicked()
+                {
+                    actions.push(action);
+                }
+            });
+            if item.status == TransferStatus::Pending {
+                if let Some(dragged) = row.response.dnd_release_payload::<u64>() {
+                    actions.push(QueueAction::MoveBefore(*dragged, item.id));
+                }
+            }
+        }
+    });
+
+    for action in actions {
+        match action {
+            QueueAction::SetPaused(paused) => state.set_queue_paused(paused),
+            QueueAction::CancelActive => state.worker.lock().unwrap().cancel(),
+            QueueAction::StopAll => {
+                let worker = state.worker.lock().unwrap();
+                let mut queue = worker.queue.lock().unwrap();
+                // Pending ones first, so that none starts once the active one stops
+                queue.cancel_all_pending();
+                if queue.has_active() {
+                    worker.cancel();
+                }
+            }
+            action => {
+                let worker = state.worker.lock().unwrap();
+                let mut queue = worker.queue.lock().unwrap();
+                match action {
+                    QueueAction::MoveBefore(id, target) => queue.move_before(id, target),
+                    QueueAction::CancelPending(id) => queue.cancel_pending(id),
+                    QueueAction::MoveUp(id) => queue.move_up(id),
+                    QueueAction::MoveDown(id) => queue.move_down(id),
+                    QueueAction::SetSpeedLimit(id, kib) => queue.set_speed_limit(id, kib),
+                    QueueAction::RemoveFinished(id) => queue.remove_finished(id),
+                    QueueAction::ClearFinished => queue.clear_finished(),
+                    QueueAction::SetPaused(_)
+                    | QueueAction::CancelActive
+                    | QueueAction::StopAll => {}
+                }
+            }
+        }
+    }
+}
+
+/// An input for a speed limit in KiB per second that reads "unlimited" at 0
+fn speed_limit_drag_value(kib: &mut u32) -> egui::DragValue<'_> {
+    egui::DragValue::new(kib)
+        .range(0..=1_048_576)
+        .speed(16.0)
+        .custom_formatter(|value, _| {
+            if value == 0.0 {
+                "∞".to_string()
+            } else {
+                format!("{} KiB/s", value)
+            }
+        })
+        .custom_parser(|text| {
+            text.trim()
+                .trim_end_matches("KiB/s")
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .or_else(|| (text.trim() == "∞").then_some(0.0))
+        })
+}
+
+/// Draw the active toasts in the bottom-right corner and drop expired ones
+fn show_toasts(ctx: &egui::Context, state: &mut UIState) {
+    state
+        .toasts
+        .retain(|toast| toast.shown_at.elapsed() < TOAST_DURATION);
+    if state.toasts.is_empty() {
+        return;
+    }
+
+    egui::Area::new(egui::Id::new("toasts"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            for toast in &state.toasts {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(&toast.message);
+                });
+            }
+        });
+    ctx.request_repaint_after(Duration::from_millis(250));
+}
+
+/// Check that a hostname looks like a host name or an IP address before
+/// trying to connect. Returns the localization key of the problem.
+fn validate_hostname(hostname: &str) -> Result<(), &'static str> {
+    let hostname = hostname.trim();
+    if hostname.is_empty() {
+        return Err("hostname_empty");
+    }
+    if hostname.parse::<std::net::IpAddr>().is_ok() {
+        return Ok(());
+    }
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if hostname.len() <= 253 && hostname.trim_end_matches('.').split('.').all(valid_label) {
+        Ok(())
+    } else {
+        Err("hostname_invalid")
+    }
+}
+
+/// Connection details pasted as `sftp://user@host:port/path`, `user@host`
+/// or scp-style `user@host:/path`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionTarget {
+    pub hostname: String,
+    pub username: Option<String>,
+    pub port: Option<u16>,
+    pub path: Option<String>,
+}
+
+/// Recognize a pasted URL or `user@host` shorthand. Plain host names are left alone.
+pub fn parse_connection_target(text: &str) -> Option<ConnectionTarget> {
+    let text = text.trim();
+    let (rest, is_url) = match text.split_once("://") {
+        Some(("sftp" | "scp" | "ssh", rest)) => (rest, true),
+        Some(_) => return None,
+        None => (text, false),
+    };
+    if !is_url && !rest.contains('@') {
+        return None;
+    }
+
+    let (username, rest) = match rest.rsplit_once('@') {
+        Some((user, rest)) => (Some(user.split(';').next().unwrap_or(user)), rest),
+        None => (None, rest),
+    };
+
+    // IPv6 addresses are written in brackets so their colons are not mistaken for a port
+    let (hostname, rest) = if let Some(bracketed) = rest.strip_prefix('[') {
+        let (host, rest) = bracketed.split_once(']')?;
+        (host, rest)
+    } else {
+        let end = rest.find([':', '/']).unwrap_or(rest.len());
+        rest.split_at(end)
+    };
+    if hostname.is_empty() {
+        return None;
+    }
+
+    let (port, path) = match rest.strip_prefix(':') {
+        Some(rest) => {
+            let digits = rest.find('/').unwrap_or(rest.len());
+            match rest[..digits].parse::<u16>() {
+                Ok(port) if is_url || digits == rest.len() => (Some(port), &rest[digits..]),
+                // `user@host:dir` is an scp path; SFTP resolves it against the login directory
+                _ if !is_url => (None, rest),
+                _ => return None,
+            }
+        }
+        None => (None, rest),
+    };
+    let path = match path {
+        "" | "/" => None,
+        path if path.starts_with('/') => Some(normalize_remote_path(path)),
+        path => Some(path.trim_end_matches('/').to_string()),
+    };
+
+    Some(ConnectionTarget {
+        hostname: hostname.to_string(),
+        username: username.filter(|u| !u.is_empty()).map(str::to_string),
+        port,
+        path,
+    })
+}
+
+/// Draw a red border around an input that failed validation
+fn mark_invalid(ui: &egui::Ui, response: &egui::Response) {
+    ui.painter().rect_stroke(
+        response.rect.expand(1.0),
+        2.0,
+        egui::Stroke::new(1.5, egui::Color32::RED),
+    );
+}
+
+/// Parse an octal permission mode such as `755` or `0700`
+fn parse_mode(input: &str) -> Option<i32> {
+    let mode = i32::from_str_radix(input.trim(), 8).ok()?;
+    (0..=0o7777).contains(&mode).then_some(mode)
+}
+
+/// Draw a small line plot of percentage values (0-100), oldest sample first
+fn draw_sparkline(ui: &mut egui::Ui, values: &[f32], color: egui::Color32) {
+    let size = egui::vec2(200.0, 40.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(
+        rect,
+        2.0,
+        egui::Stroke::new(1.0, ui.visuals().widgets.noninteractive.bg_stroke.color),
+    );
+
+    if values.len() < 2 {
+        return;
+    }
+    let step = rect.width() / (STATS_HISTORY_LEN - 1) as f32;
+    let offset = (STATS_HISTORY_LEN - values.len()) as f32 * step;
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            egui::pos2(
+                rect.left() + offset + i as f32 * step,
+                rect.bottom() - v.clamp(0.0, 100.0) / 100.0 * rect.height(),
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
+
+/// Show the multiline file editor with a line number gutter.
+///
+/// When `goto_line` (1-based) is given, the cursor is moved to the start of
+/// that line and the editor scrolls to it.
+fn show_code_editor(
+    ui: &mut egui::Ui,
+    content: &mut dyn egui::TextBuffer,
+    wrap: bool,
+    goto_line: Option<usize>,
+) -> egui::text_edit::TextEditOutput {
+    ui.horizontal_top(|ui| {
+        let (gutter_rect, _) =
+            ui.allocate_exact_size(egui::vec2(EDITOR_GUTTER_WIDTH, 0.0), egui::Sense::hover());
+        let desired_width = if wrap {
+            ui.available_width()
+        } else {
+            f32::INFINITY
+        };
+        let mut output = egui::TextEdit::multiline(content)
+            .code_editor()
+            .desired_width(desired_width)
+            .desired_rows(20)
+            .show(ui);
+
+        // Number each logical line at its first (possibly wrapped) row
+        let painter = ui.painter();
+        let clip = ui.clip_rect();
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let color = ui.visuals().weak_text_color();
+        let mut line = 1;
+        let mut line_start = true;
+        for row in &output.galley.rows {
+            let row_rect = row.rect.translate(output.galley_pos.to_vec2());
+            if line_start && row_rect.bottom() >= clip.top() && row_rect.top() <= clip.bottom() {
+                painter.text(
+                    egui::pos2(gutter_rect.right() - 6.0, row_rect.top()),
+                    egui::Align2::RIGHT_TOP,
+                    line.to_string(),
+                    font.clone(),
+                    color,
+                );
+            }
+            line_start = row.ends_with_newline;
+            if line_start {
+                line += 1;
+            }
+        }
+
+        if let Some(line) = goto_line {
+            let char_index: usize = content
+                .as_str()
+                .split('\n')
+                .take(line.saturating_sub(1))
+                .map(|l| l.chars().count() + 1)
+                .sum();
+            let ccursor =
+                egui::text::CCursor::new(char_index.min(content.as_str().chars().count()));
+            output
+                .state
+                .cursor
+                .set_char_range(Some(egui::text::CCursorRange::one(ccursor)));
+            output.state.clone().store(ui.ctx(), output.response.id);
+            output.response.request_focus();
+            let cursor_rect = output
+                .galley
+                .pos_from_cursor(&output.galley.from_ccursor(ccursor))
+                .translate(output.galley_pos.to_vec2());
+            ui.scroll_to_rect(cursor_rect, Some(egui::Align::Center));
+        }
+
+        output
+    })
+    .inner
+}
+
+/// Versions of the application and the libraries it relies on, the system
+/// it runs on and the current session, for bug reports
+fn show_about_window(ctx: &egui::Context, state: &mut UIState) {
+    if !state.show_about {
+        return;
+    }
+    let t = |key| state.localizer.t(state.language, key);
+    let mut rows = vec![
+        (t("about_version"), env!("CARGO_PKG_VERSION").to_string()),
+        (t("about_ssh_library"), SSH_LIBRARY_VERSION.to_string()),
+        (
+            t("about_os"),
+            format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH),
+        ),
+    ];
+    if let Some(info) = state.session_info.as_ref().filter(|_| state.connected) {
+        rows.push((
+            t("about_server"),
+            format!("{}:{}", state.hostname, state.port),
+        ));
+        if let Some(banner) = &info.banner {
+            rows.push((t("about_server_banner"), banner.clone()));
+        }
+        rows.extend(
+            info.methods
+                .iter()
+                .map(|(purpose, method)| (*purpose, method.clone())),
+        );
+    }
+
+    let mut open = true;
+    egui::Window::new(state.localizer.t(state.language, "about_window"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.heading(APP_TITLE);
+            egui::Grid::new("about_grid").striped(true).show(ui, |ui| {
+                for (label, value) in &rows {
+                    ui.label(*label);
+                    ui.monospace(value);
+                    ui.end_row();
+                }
+            });
+            if !state.connected {
+                ui.label(state.localizer.t(state.language, "about_not_connected"));
+            }
+            if ui
+                .button(state.localizer.t(state.language, "copy_button"))
+                .clicked()
+            {
+                let text: String = rows
+                    .iter()
+                    .map(|(label, value)| format!("{}: {}\n", label, value))
+                    .collect();
+                ui.ctx().copy_text(text);
+            }
+        });
+    if !open {
+        state.show_about = false;
+    }
+}
+
+/// The transfers recorded in the history file, for auditing
+fn show_transfer_history(ctx: &egui::Context, state: &mut UIState) {
+    let Some(entries) = &state.transfer_history else {
+        return;
+    };
+
+    let mut open = true;
+    let mut clear = false;
+    egui::Window::new(state.localizer.t(state.language, "transfer_history_window"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            if entries.is_empty() {
+                ui.label(state.localizer.t(state.language, "transfer_history_empty"));
+                return;
+            }
+            egui::ScrollArea::both()
+                .id_salt("transfer_history")
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("transfer_history_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for entry in entries {
+                                ui.monospace(&entry.time);
+                                ui.label(&entry.server);
+                                let (status_key, error) = match &entry.outcome {
+                                    Outcome::Done => ("transfer_done", None),
+                                    Outcome::Cancelled => ("transfer_cancelled", None),
+                                    Outcome::Failed(e) => ("transfer_failed", Some(e)),
+                                    Outcome::Interrupted(e) => ("transfer_interrupted", Some(e)),
+                                };
+                                let status =
+                                    ui.label(state.localizer.t(state.language, status_key));
+                                if let Some(error) = error {
+                                    status.on_hover_text(error);
+                                }
+                                let description = match entry.direction {
+                                    Direction::Download => {
+                                        format!("↓ {} → {}", entry.remote, entry.local)
+                                    }
+                                    Direction::Upload => {
+                                        format!("↑ {} → {}", entry.local, entry.remote)
+                                    }
+                                };
+                                ui.label(description);
+                                ui.label(entry.size.map(format_bytes).unwrap_or_default());
+                                ui.end_row();
+                            }
+                        });
+                });
+            if ui
+                .button(state.localizer.t(state.language, "transfer_history_clear"))
+                .clicked()
+            {
+                clear = true;
             }
+        });
+    if clear {
+        match history::clear() {
+            Ok(()) => state.transfer_history = Some(Vec::new()),
+            Err(e) => state.error_message = Some(e),
         }
+    }
+    if !open {
+        state.transfer_history = None;
+    }
+}
 
-        if let Some(error) = &state.error_message {
-            ui.colored_label(egui::Color32::RED, error);
-        }
+/// The metadata of one path, from the Properties action
+fn show_properties_window(ctx: &egui::Context, state: &mut UIState) {
+    let Some(properties) = &state.properties else {
+        return;
+    };
+    let t = |key| state.localizer.t(state.language, key);
+    let kind = match properties.kind {
+        Some(EntryKind::Directory) => t("properties_directory"),
+        Some(EntryKind::File) => t("properties_file"),
+        Some(EntryKind::Other) => t("properties_other"),
+        None => t("properties_broken_link"),
+    };
+    let id = |id: Option<u32>, name: &Option<String>| match (id, name) {
+        (Some(id), Some(name)) => format!("{} ({})", name, id),
+        (Some(id), None) => id.to_string(),
+        (None, Some(name)) => name.clone(),
+        (None, None) => "-".to_string(),
+    };
+    let time =
+        |secs: Option<u64>| secs.map_or("-".to_string(), |secs| logging::format_utc(secs) + " UTC");
+    let mut rows = vec![
+        (
+            t("properties_path"),
+            display_name(&properties.path).to_string(),
+        ),
+        (t("properties_type"), kind.to_string()),
+    ];
+    if let Some(target) = &properties.link_target {
+        rows.push((
+            t("properties_link_target"),
+            display_name(target).to_string(),
+        ));
+    }
+    rows.extend([
+        (
+            t("properties_size"),
+            properties.size.map_or("-".to_string(), |size| {
+                format!("{} ({} B)", format_bytes(size), size)
+            }),
+        ),
+        (
+            t("properties_permissions"),
+            properties.perm.map_or("-".to_string(), |perm| {
+                format!("{} ({:04o})", symbolic_mode(perm), perm & 0o7777)
+            }),
+        ),
+        (t("properties_owner"), id(properties.uid, &properties.owner)),
+        (t("properties_group"), id(properties.gid, &properties.group)),
+        (
+            t("properties_links"),
+            properties
+                .links
+                .map_or("-".to_string(), |links| links.to_string()),
+        ),
+        (t("properties_modified"), time(properties.mtime)),
+        (t("properties_accessed"), time(properties.atime)),
+    ]);
+
+    let mut open = true;
+    egui::Window::new(t("properties_window"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("properties_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    for (label, value) in &rows {
+                        ui.label(*label);
+                        ui.monospace(value);
+                        ui.end_row();
+                    }
+                });
+            if ui.button(t("copy_button")).clicked() {
+                let text: String = rows
+                    .iter()
+                    .map(|(label, value)| format!("{}: {}\n", label, value))
+                    .collect();
+                ui.ctx().copy_text(text);
+            }
+        });
+    if !open {
+        state.properties = None;
     }
 }
 
-/// Apply the chosen theme (dark or light mode)
-fn apply_theme(ctx: &egui::Context, dark_mode: bool) {
+/// Apply the chosen theme, resolving "follow system" from the OS appearance
+fn apply_theme(ctx: &egui::Context, theme: ThemeMode) {
+    let dark_mode = match theme {
+        ThemeMode::Dark => true,
+        ThemeMode::Light => false,
+        ThemeMode::System => ctx.system_theme() != Some(egui::Theme::Light),
+    };
     let mut style = (*ctx.style()).clone();
     if dark_mode {
         style.visuals = egui::Visuals::dark();
@@ -776,121 +6223,839 @@ fn apply_theme(ctx: &egui::Context, dark_mode: bool) {
 fn poll_worker(state: &mut UIState) {
     let worker = state.worker.clone();
     let worker = worker.lock().unwrap();
-    while let Ok(result) = worker.result_receiver.try_recv() {
+    while let Ok((id, result)) = worker.result_receiver.try_recv() {
+        // A slow answer must not overwrite what a later request already showed
+        if worker.is_superseded(id, &result) {
+            log::debug!("Dropping the result of superseded task {}", id);
+            continue;
+        }
+        if let TaskResult::Progress(progress) = result {
+            state.progress = Some(progress);
+            continue;
+        }
+        if let TaskResult::AuthProgress(step) = result {
+            state.auth_step = Some(step);
+            continue;
+        }
+        if let TaskResult::AuthQuestionsAsked(questions, reply) = result {
+            state.auth_questions = Some(PendingAuthQuestions {
+                answers: vec![String::new(); questions.prompts.len()],
+                questions,
+                reply,
+            });
+            continue;
+        }
+        // Mirror plans arrive between queued transfers and must not end the running queue
+        if let TaskResult::MirrorPlanResult(res) = result {
+            match res {
+                Ok(plan) if plan.entries.is_empty() => {}
+                Ok(plan) => {
+                    state.mirror_plan = Some(plan);
+                    state.mirror_acknowledged = false;
+                }
+                Err(e) => handle_error(state, e),
+            }
+            continue;
+        }
+        if let TaskResult::MissingUploadParent(transfer) = result {
+            state.missing_upload_parent = Some(transfer);
+            continue;
+        }
+        // Completions are fetched in the background while typing
+        if let TaskResult::CompletionsResult(dir, res) = result {
+            let names = res
+                .map(|listing| subdirectory_names(&listing.entries))
+                .unwrap_or_default();
+            state.completion_cache.insert(dir, names);
+            continue;
+        }
+        if let TaskResult::FindMatch(path) = result {
+            state.find_results.get_or_insert_with(Vec::new).push(path);
+            continue;
+        }
+        // Content searches run beside other operations, on a session of their own
+        if let TaskResult::GrepMatch(found) = result {
+            state.grep_results.get_or_insert_with(Vec::new).push(found);
+            continue;
+        }
+        if let TaskResult::GrepResult(res) = result {
+            state.grep_running = false;
+            match res {
+                // A stopped search keeps what it found
+                Ok(()) | Err(SshError::Cancelled(..)) => {}
+                Err(SshError::NotFound(..)) => {
+                    state.grep_results = None;
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "grep_unavailable")
+                            .to_string(),
+                    );
+                }
+                Err(e) => handle_error(state, e),
+            }
+            continue;
+        }
+        if let TaskResult::Elevated(path) = result {
+            state.toast(format!(
+                "{} {}",
+                state.localizer.t(state.language, "sudo_ran_elevated"),
+                path
+            ));
+            continue;
+        }
+        // Latency checks run in the background and must not end the running operation
+        if let TaskResult::LatencyResult(res) = result {
+            state.latency = res.ok();
+            continue;
+        }
+        if let TaskResult::QuickStatsResult(res) = result {
+            state.quick_stats = res.ok();
+            continue;
+        }
+        // The server dropped the session: the worker has let go of it, so back to the login form
+        if let TaskResult::ConnectionChecked(alive) = result {
+            if !alive && state.connected {
+                state.end_session();
+                state.error_message = Some(
+                    state
+                        .localizer
+                        .t(state.language, "connection_lost")
+                        .to_string(),
+                );
+            }
+            continue;
+        }
+        // Commands run beside other operations, on a session of their own
+        if let TaskResult::CommandChunk(stream, text) = result {
+            if let Some(output) = state.command_output.as_mut() {
+                output.push(stream, &text);
+            }
+            continue;
+        }
+        if let TaskResult::TerminalOutput(text) = result {
+            if let Some(terminal) = state.terminal.as_mut() {
+                terminal.screen.feed(&text);
+            }
+            continue;
+        }
+        if let TaskResult::TerminalClosed(res) = result {
+            if let Some(terminal) = state.terminal.as_mut() {
+                terminal.running = false;
+            }
+            match res {
+                Ok(code) => state.toast(format!(
+                    "{} {}",
+                    state.localizer.t(state.language, "terminal_exited"),
+                    code
+                )),
+                Err(SshError::Cancelled(..)) => {}
+                Err(e) => handle_error(state, e),
+            }
+            continue;
+        }
+        if let TaskResult::RunCommandResult(res) = result {
+            state.command_running = false;
+            match res {
+                Ok(code) => {
+                    if let Some(output) = state.command_output.as_mut() {
+                        output.exit_code = Some(code);
+                    }
+                }
+                Err(SshError::Cancelled(..)) => state.command_stopped = true,
+                Err(e) => {
+                    state.command_output = None;
+                    handle_error(state, e);
+                }
+            }
+            continue;
+        }
         state.operation_in_progress = false;
+        state.progress = None;
+        state.error_details = None;
         match result {
             TaskResult::ConnectResult(res) => {
+                state.auth_step = None;
+                state.auth_questions = None;
                 match res {
                     Ok(_) => {
                         state.connected = true;
-                        state.current_path = "/".to_string();
+                        state.offer_reconnect = false;
+                        state.current_path =
+                            state.initial_path.take().unwrap_or_else(|| "/".to_string());
+                        state.listed_path = state.current_path.clone();
+                        state.view = state
+                            .settings
+                            .view_for(&state.directory_key(&state.listed_path));
+                        state.restoring_path = state.current_path != "/";
+                        if !std::mem::take(&mut state.reconnecting) {
+                            state.clear_history();
+                        }
+                        let key = state.connection_key();
+                        state.recent_dirs = state
+                            .settings
+                            .recent_dirs
+                            .get(&key)
+                            .map(|dirs| dirs.iter().cloned().collect())
+                            .unwrap_or_default();
+                        state.remember_connection_defaults();
                         // Once connected, immediately list the directory
                         state.operation_in_progress = true;
                         let path = state.current_path.clone();
                         worker.send_task(Task::ListDirectory(path));
+
+                        let mut queue = worker.queue.lock().unwrap();
+                        let requeued = queue.requeue_interrupted();
+                        if requeued > 0 && !queue.paused {
+                            worker.send_task(Task::ProcessQueue);
+                        }
+                        drop(queue);
+                        if requeued > 0 {
+                            let message = state.localizer.t(state.language, "transfers_requeued");
+                            state.toast(format!("{} {}", requeued, message));
+                        }
+                    }
+                    Err(SshError::Cancelled(..)) => {
+                        state.reconnecting = false;
+                        state.initial_path = None;
+                        state.connected = false;
+                        state.error_message = Some(
+                            state
+                                .localizer
+                                .t(state.language, "status_connect_cancelled")
+                                .to_string(),
+                        );
                     }
                     Err(e) => {
-                        state.error_message = Some(e);
+                        state.reconnecting = false;
+                        state.initial_path = None;
                         state.connected = false;
+                        handle_error(state, e);
+                    }
+                }
+            }
+            TaskResult::AlreadyConnected => {
+                state.connected = true;
+                state.operation_in_progress = true;
+                let path = state.current_path.clone();
+                worker.send_task(Task::ListDirectory(path));
+                state.error_message = Some(
+                    state
+                        .localizer
+                        .t(state.language, "already_connected")
+                        .to_string(),
+                );
+            }
+            TaskResult::LatencyResult(_)
+            | TaskResult::ConnectionChecked(_)
+            | TaskResult::QuickStatsResult(_)
+            | TaskResult::MirrorPlanResult(_)
+            | TaskResult::MissingUploadParent(_)
+            | TaskResult::FindMatch(_)
+            | TaskResult::GrepMatch(_)
+            | TaskResult::GrepResult(_)
+            | TaskResult::CompletionsResult(..)
+            | TaskResult::CommandChunk(..)
+            | TaskResult::TerminalOutput(_)
+            | TaskResult::TerminalClosed(_)
+            | TaskResult::RunCommandResult(_)
+            | TaskResult::AuthProgress(_)
+            | TaskResult::AuthQuestionsAsked(..) => {}
+            TaskResult::UploadConflictsResult(transfer, res) => match res {
+                Ok(files) if files.is_empty() => {
+                    // `enqueue` would lock the worker, which is held here
+                    let mut queue = worker.queue.lock().unwrap();
+                    queue.push(transfer);
+                    if !queue.paused {
+                        state.operation_in_progress = true;
+                        worker.send_task(Task::ProcessQueue);
+                    }
+                }
+                Ok(files) => state.upload_conflicts = Some((transfer, files)),
+                Err(e) => handle_error(state, e),
+            },
+            TaskResult::ApplyMirrorPlanResult(side, res) => {
+                match res {
+                    Ok(()) => {
+                        state.error_message = Some(
+                            state
+                                .localizer
+                                .t(state.language, "status_mirror_done")
+                                .to_string(),
+                        )
                     }
+                    Err(e) => handle_error(state, e),
+                }
+                if side == MirrorSide::Remote {
+                    state.operation_in_progress = true;
+                    let path = state.current_path.clone();
+                    worker.send_task(Task::ListDirectory(path));
                 }
             }
-            TaskResult::ListDirectoryResult(res) => match res {
-                Ok(files) => {
-                    state.files = files;
+            TaskResult::ListDirectoryResult(path, res) => match res {
+                // The directory to come back to after connecting is gone: start from the root
+                Err(e) if std::mem::take(&mut state.restoring_path) && state.connected => {
+                    let message = state.localizer.t(state.language, "restore_path_failed");
+                    state.toast(format!("{} {}: {}", message, path, e.message()));
+                    state.current_path = "/".to_string();
+                    state.listed_path = "/".to_string();
+                    state.operation_in_progress = true;
+                    worker.send_task(Task::ListDirectory("/".to_string()));
+                }
+                Ok(listing) => {
+                    state.restoring_path = false;
+                    let path = match listing.canonical_path.clone() {
+                        Some(real) if state.settings.canonical_paths => {
+                            state.current_path = real.clone();
+                            real
+                        }
+                        _ => path,
+                    };
+                    state
+                        .completion_cache
+                        .insert(path.clone(), subdirectory_names(&listing.entries));
+                    state.listed_files = listing.entries;
+                    state.listing_source = listing.source;
+                    state.listing_truncated = listing.truncated;
+                    state.canonical_path = listing.canonical_path;
+                    state.record_history(&path);
+                    state.record_recent_dir(&path);
+                    state.scroll_to_top = path != state.listed_path;
+                    if path != state.listed_path {
+                        state.view = state.settings.view_for(&state.directory_key(&path));
+                    }
+                    state.show_files();
+                    if path == state.listed_path {
+                        // Keep the selection across refreshes, minus entries that are gone
+                        let names: HashSet<&String> =
+                            state.listed_files.iter().map(|entry| &entry.name).collect();
+                        state.selected.retain(|name| names.contains(name));
+                    } else {
+                        state.selected.clear();
+                        state.selection_anchor = None;
+                    }
+                    state.listed_path = path;
+                    state.completion_dir = None;
                     state.error_message = None;
                 }
+                // Stay in the previous directory instead of showing an empty one
+                Err(SshError::PermissionDenied(..)) if state.connected => {
+                    state.history_move = None;
+                    state.current_path = state.listed_path.clone();
+                    let message = state.localizer.t(state.language, "permission_denied_toast");
+                    state.toast(format!("{}: {}", message, path));
+                }
+                Err(SshError::NotFound(..)) if state.connected => {
+                    state.history_move = None;
+                    state.current_path = state.listed_path.clone();
+                    let message = state.localizer.t(state.language, "not_found_toast");
+                    state.toast(format!("{}: {}", message, path));
+                }
                 Err(e) => {
-                    state.error_message = Some(e);
+                    state.history_move = None;
+                    handle_error(state, e);
                 }
             },
             TaskResult::CreateDirectoryResult(res) => match res {
                 Ok(_) => {
-                    state.error_message = Some("Directory created successfully.".to_string());
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_directory_created")
+                            .to_string(),
+                    );
                     state.operation_in_progress = true;
                     let path = state.current_path.clone();
                     worker.send_task(Task::ListDirectory(path));
                 }
                 Err(e) => {
-                    state.error_message = Some(e);
+                    handle_error(state, e);
                 }
             },
             TaskResult::CreateFileResult(res) => match res {
                 Ok(_) => {
-                    state.error_message = Some("File created successfully.".to_string());
+                    if let Some(path) = state.created_file_to_edit.take() {
+                        let empty = text_encoding::decode_detected(Vec::new());
+                        state.open_in_editor(path, empty, false);
+                        state.editing_new_file = true;
+                    }
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_file_created")
+                            .to_string(),
+                    );
                     state.operation_in_progress = true;
                     let path = state.current_path.clone();
                     worker.send_task(Task::ListDirectory(path));
                 }
                 Err(e) => {
-                    state.error_message = Some(e);
+                    state.created_file_to_edit = None;
+                    handle_error(state, e);
                 }
             },
-            TaskResult::DownloadFileResult(res) => match res {
-                Ok(_) => state.error_message = Some("Download successful".to_string()),
-                Err(e) => state.error_message = Some(e),
-            },
-            TaskResult::UploadFileResult(res) => match res {
-                Ok(_) => {
-                    state.error_message = Some("Upload successful".to_string());
+            TaskResult::TransferResult(transfer, res) => {
+                match res {
+                    Ok(_) => {
+                        let key = if transfer.is_upload() {
+                            "status_upload_successful"
+                        } else {
+                            "status_download_successful"
+                        };
+                        state.error_message =
+                            Some(state.localizer.t(state.language, key).to_string());
+                    }
+                    Err(e) => handle_error(state, e),
+                }
+                let more = worker.queue.lock().unwrap().will_continue();
+                state.operation_in_progress = more;
+                // Refresh once the queue stops, even on failure: part of a tree may have been uploaded
+                if transfer.is_upload() && !more && state.connected {
                     state.operation_in_progress = true;
                     let path = state.current_path.clone();
                     worker.send_task(Task::ListDirectory(path));
                 }
-                Err(e) => state.error_message = Some(e),
+            }
+            TaskResult::DryRunResult(operation, res) => match res {
+                Ok(changes) => state.dry_run_plan = Some((operation, changes)),
+                Err(e) => handle_error(state, e),
             },
+            TaskResult::DeleteDirectoryResult(res) => {
+                match res {
+                    Ok(_) => {
+                        state.error_message = Some(
+                            state
+                                .localizer
+                                .t(state.language, "status_directory_deleted")
+                                .to_string(),
+                        )
+                    }
+                    Err(e) => handle_error(state, e),
+                }
+                state.operation_in_progress = true;
+                let path = state.current_path.clone();
+                worker.send_task(Task::ListDirectory(path));
+            }
+            TaskResult::Progress(..) | TaskResult::Elevated(_) => {}
             TaskResult::DeleteFileResult(res) => match res {
                 Ok(_) => {
-                    state.error_message = Some("File deleted successfully.".to_string());
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_file_deleted")
+                            .to_string(),
+                    );
                     state.operation_in_progress = true;
                     let path = state.current_path.clone();
                     worker.send_task(Task::ListDirectory(path));
                 }
-                Err(e) => state.error_message = Some(e),
+                Err(e) => handle_error(state, e),
             },
             TaskResult::RenameFileResult(res) => match res {
                 Ok(_) => {
-                    state.error_message = Some("File renamed successfully.".to_string());
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_file_renamed")
+                            .to_string(),
+                    );
+                    state.operation_in_progress = true;
+                    let path = state.current_path.clone();
+                    worker.send_task(Task::ListDirectory(path));
+                }
+                Err(e) => handle_error(state, e),
+            },
+            TaskResult::PathChecked(path, check, res) => match (check, res) {
+                // A file typed in the path box opens in the editor
+                (PathCheck::Open, Ok(Some(EntryKind::File))) => {
+                    state.current_path = state.listed_path.clone();
+                    state.operation_in_progress = true;
+                    let (max_size, encoding) = (state.max_edit_size(), state.default_encoding());
+                    worker.send_task(Task::ReadFile(path, max_size, encoding));
+                }
+                // Anything else is listed, which reports a missing path or other errors
+                (PathCheck::Open, _) => {
+                    state.operation_in_progress = true;
+                    worker.send_task(Task::ListDirectory(path));
+                }
+                (_, Err(e)) => {
+                    state.created_file_to_edit = None;
+                    handle_error(state, e);
+                }
+                (PathCheck::Rename(old_path), Ok(None)) => {
+                    state.operation_in_progress = true;
+                    let operation = Destructive::Rename(old_path, path, false);
+                    worker.send_task(operation.task(state.settings.dry_run));
+                }
+                (check, Ok(None)) => {
+                    state.operation_in_progress = true;
+                    worker.send_task(check.into_task(path));
+                }
+                (
+                    check @ (PathCheck::CreateFile(_) | PathCheck::Rename(_)),
+                    Ok(Some(EntryKind::File)),
+                ) => {
+                    if state.settings.confirmations.overwrite {
+                        state.confirm_replace = Some((path, check));
+                    } else {
+                        state.operation_in_progress = true;
+                        worker.send_task(check.into_replacing_task(path, state.settings.dry_run));
+                    }
+                }
+                // A directory or special file cannot be replaced
+                (_, Ok(Some(_))) => {
+                    state.created_file_to_edit = None;
+                    state.error_message = Some(format!(
+                        "{}: {}",
+                        state.localizer.t(state.language, "path_exists_error"),
+                        path
+                    ));
+                }
+            },
+            TaskResult::CreateHardlinkResult(res) => match res {
+                Ok(_) => {
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_hardlink_created")
+                            .to_string(),
+                    );
                     state.operation_in_progress = true;
                     let path = state.current_path.clone();
                     worker.send_task(Task::ListDirectory(path));
                 }
-                Err(e) => state.error_message = Some(e),
+                Err(e) => handle_error(state, e),
+            },
+            TaskResult::ReadFileResult(path, res) => match res {
+                Ok((decoded, version)) => {
+                    state.open_in_editor(path, decoded, false);
+                    state.edit_version = Some(version);
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_file_loaded")
+                            .to_string(),
+                    );
+                }
+                Err(e) => {
+                    handle_error(state, e);
+                }
             },
-            TaskResult::ReadFileResult(res) => match res {
-                Ok(content) => {
-                    state.file_content = content;
-                    state.error_message = Some("File content loaded.".to_string());
+            TaskResult::FileTooLarge(path, size) => {
+                state.large_file_prompt = Some((path, size));
+            }
+            TaskResult::ReadFileHeadResult(path, res) => match res {
+                Ok(decoded) => {
+                    state.open_in_editor(path, decoded, true);
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_file_loaded")
+                            .to_string(),
+                    );
                 }
                 Err(e) => {
-                    state.error_message = Some(e);
+                    handle_error(state, e);
                 }
             },
+            TaskResult::WriteTargetMissing(path) => {
+                if state.editing_file.as_deref() == Some(path.as_str()) {
+                    state.missing_edit_target = true;
+                }
+            }
+            TaskResult::WriteTargetChanged(path, version, content) => {
+                if state.editing_file.as_deref() == Some(path.as_str()) {
+                    match content {
+                        Ok(bytes) => state.changed_edit_target = Some((version, bytes)),
+                        Err(e) => handle_error(state, e),
+                    }
+                }
+            }
+            // The editor stays open after saving, with its cursor and scroll position
             TaskResult::WriteFileResult(res) => match res {
-                Ok(_) => {
-                    state.error_message = Some("File saved successfully.".to_string());
-                    state.editing_file = None;
+                Ok(version) => {
+                    if let Some(bytes) = state.saving_bytes.take() {
+                        state.file_bytes = bytes;
+                    }
+                    state.edit_version = version;
+                    state.editing_new_file = false;
+                    let message = state.localizer.t(state.language, "status_file_saved");
+                    state.toast(message.to_string());
+                }
+                Err(e) => {
+                    state.saving_bytes = None;
+                    // The edits stay in the editor: offer somewhere else to put them
+                    let refusal = match &e {
+                        SshError::PermissionDenied(..) => Some(SaveRefusal::PermissionDenied),
+                        SshError::ReadOnly(..) => Some(SaveRefusal::ReadOnly),
+                        _ => None,
+                    };
+                    if let (Some(refusal), Some(path)) = (refusal, state.editing_file.as_deref()) {
+                        // A temporary directory is writable on most servers
+                        let name = path.rsplit('/').next().unwrap_or(path);
+                        state.save_as_path = format!("/tmp/{}", name);
+                        state.save_refused = Some(refusal);
+                    }
+                    handle_error(state, e);
+                }
+            },
+            TaskResult::AppendFileResult(res) => match res {
+                Ok((appended, version)) => {
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_content_appended")
+                            .to_string(),
+                    );
+                    state.append_content.clear();
+                    if state.editing_file.is_some() && state.editor_modified() {
+                        // Reloading would throw the unsaved edits away: add the
+                        // appended text after them, as it now is on the server
+                        let text = text_encoding::decode(&appended, state.file_encoding);
+                        state.file_content.push_str(&text);
+                        state.file_bytes.extend_from_slice(&appended);
+                        state.edit_version = version;
+                    } else if let Some(path) = state.editing_file.clone() {
+                        // Reload the file so the editor shows the appended content
+                        state.operation_in_progress = true;
+                        let max_size = state.max_edit_size();
+                        // Keep the encoding the file is being edited in
+                        let encoding = Some(state.file_encoding);
+                        if state.editing_preview {
+                            worker.send_task(Task::ReadFileHead(path, max_size, encoding));
+                        } else {
+                            worker.send_task(Task::ReadFile(path, max_size, encoding));
+                        }
+                    }
                 }
                 Err(e) => {
-                    state.error_message = Some(e);
+                    handle_error(state, e);
+                }
+            },
+            TaskResult::WriteAtResult(res) => match res {
+                Ok(_) => {
+                    let message = state.localizer.t(state.language, "status_written_into");
+                    state.toast(message.to_string());
+                    // The size shown in the listing changed
+                    state.operation_in_progress = true;
+                    let path = state.current_path.clone();
+                    worker.send_task(Task::ListDirectory(path));
+                }
+                Err(e) => handle_error(state, e),
+            },
+            TaskResult::TouchResult(res) => match res {
+                Ok(_) => {
+                    state.error_message = Some(
+                        state
+                            .localizer
+                            .t(state.language, "status_touched")
+                            .to_string(),
+                    );
+                    state.operation_in_progress = true;
+                    let path = state.current_path.clone();
+                    worker.send_task(Task::ListDirectory(path));
                 }
+                Err(e) => handle_error(state, e),
+            },
+            TaskResult::PropertiesResult(res) => match res {
+                Ok(properties) => state.properties = Some(*properties),
+                Err(e) => handle_error(state, e),
             },
+            TaskResult::SessionInfoResult(info) => {
+                state.session_info = info;
+            }
             TaskResult::DisconnectResult => {
-                state.connected = false;
-                state.files.clear();
-                state.current_path = "/".to_string();
-                state.error_message = Some("Disconnected".to_string());
+                state.end_session();
+                state.error_message = Some(
+                    state
+                        .localizer
+                        .t(state.language, "status_disconnected")
+                        .to_string(),
+                );
             }
+            TaskResult::FindFilesResult(res) => match res {
+                // A stopped search keeps what it found
+                Ok(()) | Err(SshError::Cancelled(..)) => {}
+                Err(e) => handle_error(state, e),
+            },
             TaskResult::FetchStatsResult(res) => match res {
                 Ok(stats) => {
+                    if let (Some(cpu), Some(memory)) = (stats.cpu_percent, stats.memory_percent) {
+                        if state.stats_history.len() == STATS_HISTORY_LEN {
+                            state.stats_history.pop_front();
+                        }
+                        state.stats_history.push_back((cpu, memory));
+                    }
                     state.server_stats = Some(stats);
                     state.error_message = None;
                 }
                 Err(e) => {
-                    state.error_message = Some(e);
+                    handle_error(state, e);
                     state.server_stats = None;
                 }
             },
         }
     }
 }
+
+/// Tell the UI that a successful change or read went through sudo
+fn report_elevated<T>(
+    conn: &SSHConnection,
+    result: &Result<T, SshError>,
+    path: &str,
+    result_sender: &ResultSender,
+) {
+    if result.is_ok() && conn.is_elevated() {
+        let _ = result_sender.send(TaskResult::Elevated(path.to_string()));
+    }
+}
+
+/// Display an error and react to its kind: an authentication failure sends the
+/// user back to the login form, a network failure offers to reconnect.
+fn handle_error(state: &mut UIState, error: SshError) {
+    log::warn!("{}", error);
+    match error {
+        SshError::Auth(..) => {
+            state.connected = false;
+            state.listed_files.clear();
+            state.files.clear();
+        }
+        // A server that stopped answering usually leaves the session unusable
+        SshError::Network(..) | SshError::Timeout(..) | SshError::NotConnected
+            if state.connected =>
+        {
+            state.offer_reconnect = true;
+        }
+        _ => {}
+    }
+    state.error_message = Some(if let SshError::NotConnected = error {
+        state
+            .localizer
+            .t(state.language, "not_connected")
+            .to_string()
+    } else if let SshError::DiskFull(..) = error {
+        state
+            .localizer
+            .t(state.language, "remote_disk_full")
+            .to_string()
+    } else {
+        error.message().to_string()
+    });
+    state.error_details = match error {
+        // The server's wording goes to the details, under the plain message
+        SshError::DiskFull(msg, None) => Some(msg),
+        SshError::DiskFull(msg, Some(details)) => Some(format!("{}\n{}", msg, details)),
+        error => error.details().map(str::to_string),
+    };
+}
+
+/// Draw the status line and, for errors that carry them, the expandable details
+fn show_status(ui: &mut egui::Ui, state: &UIState) {
+    if let Some(error) = &state.error_message {
+        ui.colored_label(egui::Color32::RED, error);
+    }
+    if let Some(details) = &state.error_details {
+        ui.collapsing(state.localizer.t(state.language, "error_details"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(details).monospace());
+                if ui
+                    .small_button(state.localizer.t(state.language, "copy_button"))
+                    .clicked()
+                {
+                    ui.ctx().copy_text(details.clone());
+                }
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hostname, username and port of each parsed connection
+    fn targets(connections: &[SSHConnectionData]) -> Vec<(&str, &str, u16)> {
+        connections
+            .iter()
+            .map(|c| (c.hostname.as_str(), c.username.as_str(), c.port))
+            .collect()
+    }
+
+    #[test]
+    fn saved_connections_empty_file() {
+        let (connections, skipped) = parse_saved_connections("");
+        assert!(connections.is_empty());
+        assert_eq!(skipped, 0);
+        let (connections, skipped) = parse_saved_connections("  \n");
+        assert!(connections.is_empty());
+        assert_eq!(skipped, 0);
+        let (connections, skipped) = parse_saved_connections("[]");
+        assert!(connections.is_empty());
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn saved_connections_truncated_array() {
+        let content = r#"[{"hostname":"a","username":"u","port":22},
+            {"hostname":"b","username":"u","port":2222},
+            {"hostname":"c","userna"#;
+        let (connections, skipped) = parse_saved_connections(content);
+        assert_eq!(targets(&connections), [("a", "u", 22), ("b", "u", 2222)]);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn saved_connections_wrong_top_level_type() {
+        let (connections, skipped) =
+            parse_saved_connections(r#"{"hostname":"a","username":"u","port":22}"#);
+        assert!(connections.is_empty());
+        assert_eq!(skipped, 1);
+        let (connections, skipped) = parse_saved_connections("42");
+        assert!(connections.is_empty());
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn saved_connections_bad_entry_among_good_ones() {
+        let content = r#"[
+            {"hostname":"a","username":"u","port":22},
+            {"hostname":"b","username":"u","port":"ssh"},
+            "not a connection",
+            {"hostname":"  ","username":"u","port":22},
+            {"hostname":" c ","username":" v ","port":22}
+        ]"#;
+        let (connections, skipped) = parse_saved_connections(content);
+        assert_eq!(targets(&connections), [("a", "u", 22), ("c", "v", 22)]);
+        assert_eq!(skipped, 3);
+    }
+
+    #[test]
+    fn saved_connections_port_zero_becomes_22() {
+        let content = r#"[{"hostname":"a","username":"u","port":0}]"#;
+        let (connections, skipped) = parse_saved_connections(content);
+        assert_eq!(targets(&connections), [("a", "u", 22)]);
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn saved_connections_duplicates_are_merged() {
+        let content = r#"[
+            {"hostname":"a","username":"u","port":22,"label":"first"},
+            {"hostname":"a","username":"u","port":0,"label":"second"},
+            {"hostname":" a","username":"u","port":22},
+            {"hostname":"a","username":"w","port":22}
+        ]"#;
+        let (connections, skipped) = parse_saved_connections(content);
+        assert_eq!(targets(&connections), [("a", "u", 22), ("a", "w", 22)]);
+        assert_eq!(connections[0].label, "first");
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn salvage_keeps_complete_values() {
+        assert_eq!(salvage_json_array("[1, 2, {\"a\":"), [1, 2]);
+        assert_eq!(salvage_json_array("[1,,2"), [1, 2]);
+        assert!(salvage_json_array("not json").is_empty());
+        assert!(salvage_json_array("").is_empty());
+    }
+}